@@ -17,6 +17,14 @@ fn main() {
         let driver = SerialDriver::new(port).unwrap();
         let mut controller = Controller::new(driver);
 
+        let identity = controller.identify().unwrap();
+        println!("{} (home ID {:08X}, node {})", identity.library_version(), identity.home_id().value(), identity.node_id().value());
+
+        for node in controller.nodes().unwrap() {
+            let info = node.protocol_info();
+            println!("node {}: generic class {:02X}, listening: {}", node.node_id().value(), info.generic_class(), info.is_listening());
+        }
+
         controller.send_data(node_id, command).unwrap();
         controller.stop();
     }
@@ -1,5 +1,6 @@
 use std::error;
 use std::fmt;
+use std::io;
 use std::result;
 
 use serial;
@@ -15,6 +16,11 @@ pub enum ErrorKind {
     Timeout,
     Nack,
     Cancel,
+    Rejected,
+    Unsupported,
+    Failed,
+    Aborted,
+    Conflict,
 }
 
 #[derive(Debug,Clone,Copy,PartialEq,Eq)]
@@ -42,6 +48,11 @@ impl Error {
             ErrorKind::Timeout => "operation timed out",
             ErrorKind::Nack => "request not acknowledged",
             ErrorKind::Cancel => "request canceled",
+            ErrorKind::Rejected => "request rejected by controller",
+            ErrorKind::Unsupported => "function not supported by controller",
+            ErrorKind::Failed => "operation failed on controller",
+            ErrorKind::Aborted => "transmission aborted",
+            ErrorKind::Conflict => "another node already has the role",
         }
     }
 }
@@ -64,12 +75,44 @@ impl From<serial::Error> for Error {
     }
 }
 
-#[derive(Debug,Default,Clone,Copy,PartialEq,Eq)]
-pub struct NodeId(pub u8);
+impl From<io::Error> for Error {
+    fn from(_: io::Error) -> Self {
+        Error::new(ErrorKind::Io)
+    }
+}
+
+#[derive(Debug,Default,Clone,Copy,Hash,PartialEq,Eq,PartialOrd,Ord)]
+pub struct NodeId(pub u16);
 
 impl NodeId {
-    pub fn value(&self) -> u8 {
+    /// Addresses every node in the network at once.
+    pub const BROADCAST: NodeId = NodeId(0xFF);
+
+    /// The lowest node ID of a Long Range node. Classic nodes use the IDs below.
+    pub const LONG_RANGE_START: NodeId = NodeId(0x100);
+
+    pub fn value(&self) -> u16 {
         let NodeId(value) = *self;
         value
     }
+
+    pub fn is_broadcast(&self) -> bool {
+        *self == NodeId::BROADCAST
+    }
+
+    /// Returns whether the node is a Long Range node, which only the controller can talk to
+    /// directly, without routing.
+    pub fn is_long_range(&self) -> bool {
+        *self >= NodeId::LONG_RANGE_START
+    }
+}
+
+#[derive(Debug,Default,Clone,Copy,PartialEq,Eq)]
+pub struct HomeId(pub u32);
+
+impl HomeId {
+    pub fn value(&self) -> u32 {
+        let HomeId(value) = *self;
+        value
+    }
 }
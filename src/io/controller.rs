@@ -1,98 +1,1609 @@
-use std::sync::{Arc, Mutex, Condvar};
+use std::cell::Cell;
+use std::cmp;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, Condvar};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{channel, Sender, Receiver, TryRecvError};
 use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
 
-use core::{self, NodeId, Error, ErrorKind};
+use core::{self, NodeId, HomeId, Error, ErrorKind};
 use io::driver::Driver;
-use protocol::bits::PreambleId;
+use network::{NetworkMap, NodeRole};
+use nvm;
+use protocol::bits::{PreambleId, FunctionId, TransmitStatus, LibraryType, AddNodeMode, AddNodeStatus};
+use protocol::bits::{RemoveNodeMode, RemoveNodeStatus, LearnMode, LearnModeStatus, FailedNodeStatus};
+use protocol::bits::{NeighborUpdateStatus, SetSucStatus, NvmStatus, RfRegion, NodeIdType};
 use protocol::command::Command;
-use protocol::message::{Ack, SendData};
+use protocol::message::{Frame, Request, CallbackRequest, Callback, AnyMessage, Ack, Nack, SendData, SendDataMulti};
+use protocol::message::SendDataAbort;
+use protocol::message::{ApplicationCommandHandler, ApplicationUpdate};
+use protocol::message::{GetVersion, MemoryGetId, SerialApiGetCapabilities};
+use protocol::message::{SerialApiGetInitData, GetNodeProtocolInfo, NodeProtocolInfo};
+use protocol::message::{NodeInfo, AddNodeToNetwork, AddNodeUpdate, RemoveNodeFromNetwork, RemoveNodeUpdate};
+use protocol::message::{SetLearnMode, LearnModeUpdate, SetDefault, DefaultSet, SoftReset, SerialApiStarted};
+use protocol::message::{GetRoutingInfo, RequestNodeNeighborUpdate, NeighborUpdate, AssignReturnRoute, DeleteReturnRoute};
+use protocol::message::{AssignSucReturnRoute, DeleteSucReturnRoute, GetSucNodeId, EnableSuc, SetSucNodeId};
+use protocol::message::GetControllerCapabilities;
+use protocol::message::{GetNvmId, ExtNvmReadLongBuffer, ExtNvmWriteLongBuffer, NvmBackupRestore};
+use protocol::message::{SerialApiSetup, SerialApiSetupResult, TxPower, GetLongRangeNodes};
+use protocol::message::{IsFailedNode, RemoveFailedNode, ReplaceFailedNode, ReplaceFailedNodeUpdate};
 
-const REPLY_TIMEOUT_MS: u64 = 100;
+const ACK_TIMEOUT_MS: u64 = 1600;
+const RETRANSMISSIONS: u32 = 3;
+const BACKOFF_MS: u64 = 100;
+const BACKOFF_INCREMENT_MS: u64 = 1000;
+const RESPONSE_TIMEOUT_MS: u64 = 10_000;
+const CALLBACK_TIMEOUT_MS: u64 = 65_000;
+const RESTART_TIMEOUT_MS: u64 = 1500;
+const RECEIVE_ERROR_BACKOFF_MS: u64 = 100;
+const NVM_CHUNK_SIZE: usize = 64;
+
+/// Outcome of `Controller::broadcast()`. Nodes don't acknowledge broadcasts, so the controller can
+/// only tell whether the frame went out.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum BroadcastStatus {
+    Transmitted,
+    NotTransmitted(TransmitStatus),
+}
+
+/// Determines how often and how quickly a frame is sent again when the controller doesn't
+/// acknowledge it.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct RetryPolicy {
+    retransmissions: u32,
+    ack_timeout: Duration,
+    backoff: Duration,
+    backoff_increment: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(retransmissions: u32, ack_timeout: Duration) -> Self {
+        RetryPolicy::with_backoff(retransmissions, ack_timeout, Duration::from_millis(BACKOFF_MS), Duration::from_millis(BACKOFF_INCREMENT_MS))
+    }
+
+    pub fn with_backoff(retransmissions: u32, ack_timeout: Duration, backoff: Duration, backoff_increment: Duration) -> Self {
+        RetryPolicy {
+            retransmissions: retransmissions,
+            ack_timeout: ack_timeout,
+            backoff: backoff,
+            backoff_increment: backoff_increment,
+        }
+    }
+
+    pub fn retransmissions(&self) -> u32 {
+        self.retransmissions
+    }
+
+    pub fn ack_timeout(&self) -> Duration {
+        self.ack_timeout
+    }
+
+    /// Returns how long to wait before the given retransmission, counting from zero.
+    pub fn backoff(&self, retransmission: u32) -> Duration {
+        self.backoff + self.backoff_increment * retransmission
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Returns the policy recommended by the Serial API specification.
+    fn default() -> Self {
+        RetryPolicy::new(RETRANSMISSIONS, Duration::from_millis(ACK_TIMEOUT_MS))
+    }
+}
+
+/// Describes the controller that the host is talking to.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Identity {
+    library_version: String,
+    library_type: Option<LibraryType>,
+    home_id: HomeId,
+    node_id: NodeId,
+    application_version: u8,
+    application_revision: u8,
+    manufacturer_id: u16,
+    product_type: u16,
+    product_id: u16,
+    function_mask: [u8; 32],
+}
+
+impl Identity {
+    pub fn library_version(&self) -> &str {
+        &self.library_version
+    }
+
+    pub fn library_type(&self) -> Option<LibraryType> {
+        self.library_type
+    }
+
+    pub fn home_id(&self) -> HomeId {
+        self.home_id
+    }
+
+    /// Returns the controller's own node ID.
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn application_version(&self) -> u8 {
+        self.application_version
+    }
+
+    pub fn application_revision(&self) -> u8 {
+        self.application_revision
+    }
+
+    pub fn manufacturer_id(&self) -> u16 {
+        self.manufacturer_id
+    }
+
+    pub fn product_type(&self) -> u16 {
+        self.product_type
+    }
+
+    pub fn product_id(&self) -> u16 {
+        self.product_id
+    }
+
+    pub fn function_mask(&self) -> &[u8; 32] {
+        &self.function_mask
+    }
+
+    /// Returns whether the controller implements the given Serial API function.
+    pub fn supports(&self, function_id: FunctionId) -> bool {
+        let bit = function_id as usize - 1;
+
+        self.function_mask[bit / 8] & (1 << (bit % 8)) != 0
+    }
+}
+
+/// A node in the controller's network.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Node {
+    node_id: NodeId,
+    protocol_info: NodeProtocolInfo,
+}
+
+impl Node {
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn protocol_info(&self) -> &NodeProtocolInfo {
+        &self.protocol_info
+    }
+}
+
+/// Something that happened on the Z-Wave network or the serial link without being requested by the
+/// host.
+#[derive(Debug)]
+pub enum Event {
+    /// A node sent a command to the controller.
+    ApplicationCommand(ApplicationCommandHandler),
+
+    /// The controller learned something about the network, e.g., received a node's information
+    /// frame or assigned a node ID.
+    ApplicationUpdate(ApplicationUpdate),
+
+    /// The controller has (re)started. Unless it was asked to restart, it may have lost state
+    /// such as a pending request.
+    SerialApiStarted(SerialApiStarted),
+
+    /// `Controller::backup_nvm()` or `restore_nvm()` has transferred another chunk of the NVM.
+    NvmProgress {
+        transfer: NvmTransfer,
+        completed: usize,
+        total: usize,
+    },
+
+    /// `Controller::heal()` is done with a node, successfully or not.
+    HealProgress {
+        node_id: NodeId,
+        completed: usize,
+        total: usize,
+        result: core::Result<()>,
+    },
+
+    /// A message from the controller could not be received or decoded.
+    Error(Error),
+}
+
+/// The part of an NVM backup or restore that `Event::NvmProgress` reports on.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum NvmTransfer {
+    Backup,
+    Restore,
+
+    /// Reading the NVM a second time to check that a backup was read, or a restore written,
+    /// correctly.
+    Verify,
+}
+
+/// How the controller's NVM is read and written.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+enum NvmApi {
+    /// `NvmBackupRestore`, 700 series and later
+    BackupRestore,
+
+    /// `ExtNvmReadLongBuffer` and `ExtNvmWriteLongBuffer`, 500 series
+    Extended,
+}
 
 enum Reply {
     Ack,
     Nack,
     Cancel,
+    Frame(AnyMessage),
+}
+
+struct SharedState<D: Driver> {
+    driver: Mutex<D>,
+    running: AtomicBool,
+    restarted: AtomicBool,
+    aborted: AtomicBool,
+    reply: Condvar,
+    listeners: Mutex<Vec<Box<Fn(&Event) + Send>>>,
 }
 
-struct SharedState<D: Driver> {
-    driver: Mutex<D>,
-    running: AtomicBool,
-    reply: Condvar,
+impl<D: Driver> SharedState<D> {
+    fn notify(&self, event: Event) {
+        for listener in self.listeners.lock().unwrap().iter() {
+            listener(&event);
+        }
+    }
+}
+
+pub struct Controller<D: Driver> {
+    state: Arc<SharedState<D>>,
+    replies: Receiver<Reply>,
+    thread: thread::JoinHandle<()>,
+    callback_id: u8,
+    identity: Option<Identity>,
+    node_id_type: NodeIdType,
+    retry_policy: RetryPolicy,
+    attempts: Cell<u32>,
+    abortable: Cell<bool>,
+    response_timeout: Duration,
+    callback_timeout: Duration,
+}
+
+impl<D: Driver> Controller<D> {
+    pub fn new(driver: D) -> Self {
+        let state = Arc::new(SharedState {
+            driver: Mutex::new(driver),
+            running: AtomicBool::new(true),
+            restarted: AtomicBool::new(false),
+            aborted: AtomicBool::new(false),
+            reply: Condvar::new(),
+            listeners: Mutex::new(Vec::new()),
+        });
+
+
+        let (tx, rx) = channel::<Reply>();
+        let thread = Reader::start(state.clone(), tx);
+
+        Controller {
+            state: state,
+            replies: rx,
+            thread: thread,
+            callback_id: 0,
+            identity: None,
+            node_id_type: NodeIdType::Short,
+            retry_policy: RetryPolicy::default(),
+            attempts: Cell::new(0),
+            abortable: Cell::new(false),
+            response_timeout: Duration::from_millis(RESPONSE_TIMEOUT_MS),
+            callback_timeout: Duration::from_millis(CALLBACK_TIMEOUT_MS),
+        }
+    }
+
+    pub fn stop(self) {
+        self.state.running.store(false, Ordering::Relaxed);
+        self.thread.join().unwrap();
+    }
+
+    /// Registers a function to be called with every event. Listeners are called from the reader
+    /// thread, so they should return quickly and must not call back into the controller.
+    pub fn add_listener<L: Fn(&Event) + Send + 'static>(&self, listener: L) {
+        self.state.listeners.lock().unwrap().push(Box::new(listener));
+    }
+
+    /// Returns a handle for aborting `send_data()` from another thread.
+    pub fn abort_handle(&self) -> AbortHandle<D> {
+        AbortHandle {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Sets how frames that the controller doesn't acknowledge are retransmitted.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Returns how many times the most recent frame had to be sent before the controller
+    /// acknowledged it or the retry policy gave up.
+    pub fn attempts(&self) -> u32 {
+        self.attempts.get()
+    }
+
+    /// Sets how long to wait for the controller to respond to a request.
+    pub fn set_response_timeout(&mut self, timeout: Duration) {
+        self.response_timeout = timeout;
+    }
+
+    /// Sets how long to wait for the controller to report the outcome of a request with a callback.
+    pub fn set_callback_timeout(&mut self, timeout: Duration) {
+        self.callback_timeout = timeout;
+    }
+
+    /// Asks the controller which library, network and hardware it's running. The result is kept for
+    /// later reference by `identity()`.
+    pub fn identify(&mut self) -> core::Result<Identity> {
+        // forget what the previous identification said the controller supports
+        self.identity = None;
+
+        let version = try!(self.request(GetVersion::new()));
+        let memory_id = try!(self.request(MemoryGetId::new()));
+        let capabilities = try!(self.request(SerialApiGetCapabilities::new()));
+
+        let identity = Identity {
+            library_version: version.library_version().to_string(),
+            library_type: version.library_type(),
+            home_id: memory_id.home_id(),
+            node_id: memory_id.node_id(),
+            application_version: capabilities.application_version(),
+            application_revision: capabilities.application_revision(),
+            manufacturer_id: capabilities.manufacturer_id(),
+            product_type: capabilities.product_type(),
+            product_id: capabilities.product_id(),
+            function_mask: *capabilities.function_mask(),
+        };
+
+        self.identity = Some(identity.clone());
+
+        Ok(identity)
+    }
+
+    /// Returns the result of the most recent call to `identify()`.
+    pub fn identity(&self) -> Option<&Identity> {
+        self.identity.as_ref()
+    }
+
+    /// Lists the nodes in the controller's network along with their protocol information.
+    pub fn nodes(&mut self) -> core::Result<Vec<Node>> {
+        let init_data = try!(self.request(SerialApiGetInitData::new()));
+        let mut nodes = Vec::<Node>::new();
+
+        for node_id in init_data.node_ids() {
+            let protocol_info = try!(self.request(GetNodeProtocolInfo::new(*node_id)));
+
+            nodes.push(Node {
+                node_id: *node_id,
+                protocol_info: protocol_info,
+            });
+        }
+
+        Ok(nodes)
+    }
+
+    /// Lists the Long Range nodes in the controller's network along with their protocol
+    /// information. Switches the controller to two-byte node IDs first.
+    pub fn long_range_nodes(&mut self) -> core::Result<Vec<Node>> {
+        try!(self.use_long_node_ids());
+
+        let mut node_ids = Vec::<NodeId>::new();
+        let mut segment = 0;
+
+        loop {
+            // a controller that reports more segments than there are Long Range node IDs is broken
+            if segment >= GetLongRangeNodes::SEGMENTS {
+                return Err(Error::new(ErrorKind::Protocol));
+            }
+
+            let response = try!(self.request(GetLongRangeNodes::new(segment)));
+            node_ids.extend_from_slice(response.node_ids());
+
+            if !response.more() {
+                break;
+            }
+
+            segment += 1;
+        }
+
+        let mut nodes = Vec::<Node>::new();
+
+        for node_id in node_ids {
+            let protocol_info = try!(self.request(GetNodeProtocolInfo::new(node_id)));
+
+            nodes.push(Node {
+                node_id: node_id,
+                protocol_info: protocol_info,
+            });
+        }
+
+        Ok(nodes)
+    }
+
+    /// Puts the controller into inclusion mode. The returned session reports the progress of adding
+    /// a node and takes the controller out of inclusion mode when it ends.
+    pub fn add_node(&mut self, mode: AddNodeMode, high_power: bool, network_wide: bool) -> core::Result<Inclusion<D>> {
+        let callback_id = self.next_callback_id();
+        let timeout = self.response_timeout;
+
+        try!(self.send(&AddNodeToNetwork::with_options(mode, callback_id, high_power, network_wide)));
+
+        let mut inclusion = Inclusion {
+            controller: self,
+            callback_id: callback_id,
+            node: None,
+            active: true,
+        };
+
+        match try!(inclusion.next_status(timeout)) {
+            AddNodeStatus::LearnReady => Ok(inclusion),
+            AddNodeStatus::Failed => Err(Error::new(ErrorKind::Failed)),
+            _ => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
+
+    /// Includes the node with the given S2 DSK over Long Range once it announces itself through
+    /// SmartStart. Switches the controller to two-byte node IDs first, since Long Range nodes get
+    /// IDs past 255.
+    pub fn add_long_range_node(&mut self, dsk: &[u8; 16]) -> core::Result<Inclusion<D>> {
+        try!(self.use_long_node_ids());
+
+        let callback_id = self.next_callback_id();
+
+        try!(self.send(&AddNodeToNetwork::smart_start(dsk, callback_id, true)));
+
+        // the controller doesn't report being ready, only the progress once the node shows up
+        Ok(Inclusion {
+            controller: self,
+            callback_id: callback_id,
+            node: None,
+            active: true,
+        })
+    }
+
+    /// Puts the controller into exclusion mode. The returned session reports the progress of
+    /// removing a node and takes the controller out of exclusion mode when it ends.
+    pub fn remove_node(&mut self, mode: RemoveNodeMode, high_power: bool, network_wide: bool) -> core::Result<Exclusion<D>> {
+        let callback_id = self.next_callback_id();
+        let timeout = self.response_timeout;
+
+        try!(self.send(&RemoveNodeFromNetwork::with_options(mode, callback_id, high_power, network_wide)));
+
+        let mut exclusion = Exclusion {
+            controller: self,
+            callback_id: callback_id,
+            node_id: None,
+            active: true,
+        };
+
+        match try!(exclusion.next_status(timeout)) {
+            RemoveNodeStatus::LearnReady => Ok(exclusion),
+            RemoveNodeStatus::Failed => Err(Error::new(ErrorKind::Failed)),
+            _ => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
+
+    /// Puts the controller into learn mode so that another controller can add it to its network or
+    /// remove it again. The returned session takes the controller out of learn mode when it ends.
+    pub fn learn_mode(&mut self, mode: LearnMode) -> core::Result<Learning<D>> {
+        let callback_id = self.next_callback_id();
+
+        let result = try!(self.request(SetLearnMode::new(mode, callback_id)));
+
+        if !result.accepted() {
+            return Err(Error::new(ErrorKind::Rejected));
+        }
+
+        Ok(Learning {
+            controller: self,
+            callback_id: callback_id,
+            active: true,
+        })
+    }
+
+    /// Resets the controller to its factory defaults. This erases the network, so the controller
+    /// ends up with a new home ID and as node 1.
+    pub fn set_default(&mut self) -> core::Result<()> {
+        let callback_id = self.next_callback_id();
+        let timeout = self.callback_timeout;
+
+        try!(self.send(&SetDefault::new(callback_id)));
+        try!(self.wait_for_callback::<DefaultSet>(callback_id, timeout));
+
+        self.reinitialize()
+    }
+
+    /// Restarts the controller and waits for it to come back.
+    pub fn soft_reset(&mut self) -> core::Result<()> {
+        try!(self.send(&SoftReset::new()));
+
+        {
+            let driver = self.state.driver.lock().unwrap();
+
+            // only 700 series and later announce that they're back
+            let (_driver, _) = self.wait_for_frame::<SerialApiStarted, _>(driver, Duration::from_millis(RESTART_TIMEOUT_MS), |_| true);
+        }
+
+        self.reinitialize()
+    }
+
+    /// Returns whether the controller considers the node to have failed.
+    pub fn is_failed(&mut self, node_id: NodeId) -> core::Result<bool> {
+        let result = try!(self.request(IsFailedNode::new(node_id)));
+
+        Ok(result.failed())
+    }
+
+    /// Removes a failed node from the network. The controller checks first whether the node
+    /// responds, in which case it reports `NodeOk` and keeps it.
+    pub fn remove_failed(&mut self, node_id: NodeId) -> core::Result<FailedNodeStatus> {
+        let (_, callback) = try!(self.request_with_callback(RemoveFailedNode::new(node_id, 0)));
+
+        match callback.status() {
+            Some(status) => Ok(status),
+            None => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
+
+    /// Replaces a failed node with the next node that's included, which takes over its node ID.
+    /// Returns `ReplaceDone` or `ReplaceFailed`, or `NodeOk` if the failed node responds after all.
+    pub fn replace_failed(&mut self, node_id: NodeId) -> core::Result<FailedNodeStatus> {
+        let callback_id = self.next_callback_id();
+        let timeout = self.callback_timeout;
+
+        let result = try!(self.request(ReplaceFailedNode::new(node_id, callback_id)));
+
+        if !result.started() {
+            return Err(Error::new(ErrorKind::Rejected));
+        }
+
+        loop {
+            let update = try!(self.wait_for_callback::<ReplaceFailedNodeUpdate>(callback_id, timeout));
+
+            match update.status() {
+                // the controller is ready to include the replacement
+                Some(FailedNodeStatus::Replace) => (),
+                Some(status) => return Ok(status),
+                None => return Err(Error::new(ErrorKind::Protocol)),
+            }
+        }
+    }
+
+    /// Asks the controller for every node's neighbors and puts them together into a map of the
+    /// network.
+    pub fn network_map(&mut self) -> core::Result<NetworkMap> {
+        let mut network_map = NetworkMap::new();
+
+        for node in try!(self.nodes()) {
+            let routing_info = try!(self.request(GetRoutingInfo::new(node.node_id())));
+
+            network_map.add_node(node.node_id(), NodeRole::of(node.protocol_info()), routing_info.neighbors().to_vec());
+        }
+
+        Ok(network_map)
+    }
+
+    /// Asks a node to find its neighbors and report them to the controller, which updates its
+    /// routing table.
+    pub fn update_neighbors(&mut self, node_id: NodeId) -> core::Result<()> {
+        let callback_id = self.next_callback_id();
+        let timeout = self.callback_timeout;
+
+        try!(self.send(&RequestNodeNeighborUpdate::new(node_id, callback_id)));
+
+        loop {
+            let update = try!(self.wait_for_callback::<NeighborUpdate>(callback_id, timeout));
+
+            match update.status() {
+                Some(NeighborUpdateStatus::Started) => (),
+                Some(NeighborUpdateStatus::Done) => return Ok(()),
+                Some(NeighborUpdateStatus::Failed) => return Err(Error::new(ErrorKind::Failed)),
+                None => return Err(Error::new(ErrorKind::Protocol)),
+            }
+        }
+    }
+
+    /// Tells a node how to reach the destination node.
+    pub fn assign_return_route(&mut self, node_id: NodeId, destination: NodeId) -> core::Result<TransmitStatus> {
+        let (_, callback) = try!(self.request_with_callback(AssignReturnRoute::new(node_id, destination, 0)));
+
+        match callback.transmit_status() {
+            Some(status) => Ok(status),
+            None => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
+
+    /// Makes a node forget all of its return routes.
+    pub fn delete_return_routes(&mut self, node_id: NodeId) -> core::Result<TransmitStatus> {
+        let (_, callback) = try!(self.request_with_callback(DeleteReturnRoute::new(node_id, 0)));
+
+        match callback.transmit_status() {
+            Some(status) => Ok(status),
+            None => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
+
+    /// Tells a node how to reach the SUC.
+    pub fn assign_suc_return_route(&mut self, node_id: NodeId) -> core::Result<TransmitStatus> {
+        let (_, callback) = try!(self.request_with_callback(AssignSucReturnRoute::new(node_id, 0)));
+
+        match callback.transmit_status() {
+            Some(status) => Ok(status),
+            None => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
+
+    /// Makes a node forget its return route to the SUC.
+    pub fn delete_suc_return_route(&mut self, node_id: NodeId) -> core::Result<TransmitStatus> {
+        let (_, callback) = try!(self.request_with_callback(DeleteSucReturnRoute::new(node_id, 0)));
+
+        match callback.transmit_status() {
+            Some(status) => Ok(status),
+            None => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
+
+    /// Returns the node ID of the network's Static Update Controller (SUC), if there is one.
+    pub fn suc_node_id(&mut self) -> core::Result<Option<NodeId>> {
+        let response = try!(self.request(GetSucNodeId::new()));
+
+        Ok(response.node_id())
+    }
+
+    /// Enables or disables the controller's own SUC functionality, optionally with the node ID
+    /// server (SIS).
+    pub fn enable_suc(&mut self, enable: bool, sis: bool) -> core::Result<()> {
+        let result = try!(self.request(EnableSuc::new(enable, sis)));
+
+        if !result.accepted() {
+            return Err(Error::new(ErrorKind::Rejected));
+        }
+
+        Ok(())
+    }
+
+    /// Makes a controller the network's SUC, optionally with SIS functionality.
+    pub fn set_suc_node_id(&mut self, node_id: NodeId, enable: bool, sis: bool) -> core::Result<()> {
+        // the controller doesn't call back when it's made the SUC itself
+        if node_id == try!(self.own_node_id()) {
+            let result = try!(self.request(SetSucNodeId::new(node_id, enable, sis, 0)));
+
+            if !result.accepted() {
+                return Err(Error::new(ErrorKind::Rejected));
+            }
+
+            return Ok(());
+        }
+
+        let (_, callback) = try!(self.request_with_callback(SetSucNodeId::new(node_id, enable, sis, 0)));
+
+        match callback.status() {
+            Some(SetSucStatus::Succeeded) => Ok(()),
+            Some(SetSucStatus::Failed) => Err(Error::new(ErrorKind::Failed)),
+            None => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
+
+    /// Makes the controller the network's SIS unless the network already has one. Returns the node
+    /// ID of the SIS either way, or a `Conflict` error if another node is the SUC without being
+    /// the SIS.
+    pub fn ensure_sis(&mut self) -> core::Result<NodeId> {
+        let suc_node_id = try!(self.suc_node_id());
+
+        if let Some(node_id) = suc_node_id {
+            let capabilities = try!(self.request(GetControllerCapabilities::new()));
+
+            if capabilities.sis_present() {
+                return Ok(node_id);
+            }
+        }
+
+        let own_node_id = try!(self.own_node_id());
+
+        // a network only has one SUC, so only this controller can be turned into the SIS
+        if suc_node_id.map_or(false, |node_id| node_id != own_node_id) {
+            return Err(Error::new(ErrorKind::Conflict));
+        }
+
+        try!(self.enable_suc(true, true));
+        try!(self.set_suc_node_id(own_node_id, true, true));
+
+        Ok(own_node_id)
+    }
+
+    /// Rebuilds the routes of every listening node, one node at a time: the node looks for its
+    /// neighbors and then gets a new return route to the controller. Listeners are told about each
+    /// node as soon as it's done, and the results for all nodes are returned at the end.
+    pub fn heal(&mut self) -> core::Result<Vec<(NodeId, core::Result<()>)>> {
+        let own_node_id = try!(self.own_node_id());
+
+        let node_ids = try!(self.nodes()).into_iter().filter(|node| {
+            node.node_id() != own_node_id && node.protocol_info().is_listening()
+        }).map(|node| node.node_id()).collect::<Vec<_>>();
+
+        let total = node_ids.len();
+        let mut results = Vec::<(NodeId, core::Result<()>)>::with_capacity(total);
+
+        for (index, node_id) in node_ids.into_iter().enumerate() {
+            let result = self.heal_node(node_id, own_node_id);
+
+            self.state.notify(Event::HealProgress {
+                node_id: node_id,
+                completed: index + 1,
+                total: total,
+                result: result,
+            });
+
+            results.push((node_id, result));
+        }
+
+        Ok(results)
+    }
+
+    fn heal_node(&mut self, node_id: NodeId, controller_node_id: NodeId) -> core::Result<()> {
+        try!(self.update_neighbors(node_id));
+
+        match try!(self.assign_return_route(node_id, controller_node_id)) {
+            TransmitStatus::Ok => Ok(()),
+            _ => Err(Error::new(ErrorKind::Failed)),
+        }
+    }
+
+    /// Copies the controller's NVM into a file, reading it twice to compare checksums. Restoring it
+    /// with `restore_nvm()` makes the same controller, or a replacement of the same series, take
+    /// over the network.
+    pub fn backup_nvm<P: AsRef<Path>>(&mut self, path: P) -> core::Result<()> {
+        let image = try!(self.read_nvm(NvmTransfer::Backup));
+        let verified = try!(self.read_nvm(NvmTransfer::Verify));
+
+        if nvm::checksum(&verified) != nvm::checksum(&image) {
+            return Err(Error::new(ErrorKind::Corrupt));
+        }
+
+        let mut file = try!(File::create(path));
+        try!(file.write_all(&image));
+
+        Ok(())
+    }
+
+    /// Writes an NVM image from `backup_nvm()` to the controller, reads it back to compare
+    /// checksums and restarts the controller so that it picks up the restored network.
+    pub fn restore_nvm<P: AsRef<Path>>(&mut self, path: P) -> core::Result<()> {
+        let mut image = Vec::new();
+
+        let mut file = try!(File::open(path));
+        try!(file.read_to_end(&mut image));
+
+        try!(self.write_nvm(&image));
+
+        let written = try!(self.read_nvm(NvmTransfer::Verify));
+
+        if nvm::checksum(&written) != nvm::checksum(&image) {
+            return Err(Error::new(ErrorKind::Corrupt));
+        }
+
+        self.soft_reset()
+    }
+
+    fn nvm_api(&mut self) -> core::Result<NvmApi> {
+        if self.identity.is_none() {
+            try!(self.identify());
+        }
+
+        let identity = self.identity.as_ref().unwrap();
+
+        if identity.supports(FunctionId::NvmBackupRestore) {
+            Ok(NvmApi::BackupRestore)
+        }
+        else if identity.supports(FunctionId::ExtNvmReadLongBuffer) && identity.supports(FunctionId::ExtNvmWriteLongBuffer) {
+            Ok(NvmApi::Extended)
+        }
+        else {
+            Err(Error::new(ErrorKind::Unsupported))
+        }
+    }
+
+    fn nvm_size(&mut self, api: NvmApi) -> core::Result<usize> {
+        match api {
+            NvmApi::BackupRestore => {
+                let response = try!(self.request(NvmBackupRestore::open()));
+
+                match response.status() {
+                    Some(NvmStatus::Ok) => Ok(response.offset() as usize),
+                    _ => Err(Error::new(ErrorKind::Failed)),
+                }
+            },
+            NvmApi::Extended => {
+                match try!(self.request(GetNvmId::new())).size() {
+                    Some(size) => Ok(size),
+                    None => Err(Error::new(ErrorKind::Unsupported)),
+                }
+            },
+        }
+    }
+
+    fn read_nvm(&mut self, transfer: NvmTransfer) -> core::Result<Vec<u8>> {
+        let api = try!(self.nvm_api());
+        let size = try!(self.nvm_size(api));
+
+        let result = self.read_nvm_chunks(api, size, transfer);
+
+        if api == NvmApi::BackupRestore {
+            try!(self.request(NvmBackupRestore::close()));
+        }
+
+        result
+    }
+
+    fn read_nvm_chunks(&mut self, api: NvmApi, size: usize, transfer: NvmTransfer) -> core::Result<Vec<u8>> {
+        let mut image = Vec::with_capacity(size);
+
+        while image.len() < size {
+            let offset = image.len();
+            let length = cmp::min(NVM_CHUNK_SIZE, size - offset);
+
+            let end_of_file = match api {
+                NvmApi::BackupRestore => {
+                    let response = try!(self.request(NvmBackupRestore::read(offset as u16, length as u8)));
+
+                    match response.status() {
+                        Some(NvmStatus::Ok) | Some(NvmStatus::EndOfFile) if !response.data().is_empty() => (),
+                        _ => return Err(Error::new(ErrorKind::Failed)),
+                    }
+
+                    image.extend_from_slice(response.data());
+
+                    response.status() == Some(NvmStatus::EndOfFile)
+                },
+                NvmApi::Extended => {
+                    let response = try!(self.request(ExtNvmReadLongBuffer::new(offset as u32, length as u16)));
+
+                    if response.data().len() != length {
+                        return Err(Error::new(ErrorKind::Protocol));
+                    }
+
+                    image.extend_from_slice(response.data());
+
+                    false
+                },
+            };
+
+            self.state.notify(Event::NvmProgress {
+                transfer: transfer,
+                completed: image.len(),
+                total: size,
+            });
+
+            if end_of_file {
+                break;
+            }
+        }
+
+        Ok(image)
+    }
+
+    fn write_nvm(&mut self, image: &[u8]) -> core::Result<()> {
+        let api = try!(self.nvm_api());
+        let size = try!(self.nvm_size(api));
+
+        // an image from a different kind of controller would corrupt the NVM
+        let result = if image.len() != size {
+            Err(Error::new(ErrorKind::Corrupt))
+        }
+        else {
+            self.write_nvm_chunks(api, image)
+        };
+
+        if api == NvmApi::BackupRestore {
+            try!(self.request(NvmBackupRestore::close()));
+        }
+
+        result
+    }
+
+    fn write_nvm_chunks(&mut self, api: NvmApi, image: &[u8]) -> core::Result<()> {
+        let mut offset = 0;
+
+        for chunk in image.chunks(NVM_CHUNK_SIZE) {
+            let written = match api {
+                NvmApi::BackupRestore => {
+                    let response = try!(self.request(NvmBackupRestore::write(offset as u16, chunk.to_vec())));
+
+                    match response.status() {
+                        Some(NvmStatus::Ok) | Some(NvmStatus::EndOfFile) => true,
+                        _ => false,
+                    }
+                },
+                NvmApi::Extended => {
+                    try!(self.request(ExtNvmWriteLongBuffer::new(offset as u32, chunk.to_vec()))).succeeded()
+                },
+            };
+
+            if !written {
+                return Err(Error::new(ErrorKind::Failed));
+            }
+
+            offset += chunk.len();
+
+            self.state.notify(Event::NvmProgress {
+                transfer: NvmTransfer::Restore,
+                completed: offset,
+                total: image.len(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the radio region that the controller is configured for.
+    pub fn get_rf_region(&mut self) -> core::Result<RfRegion> {
+        match try!(self.setup(SerialApiSetup::GetRfRegion)) {
+            SerialApiSetupResult::RfRegion(region) => Ok(region),
+            _ => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
+
+    /// Configures the controller for another radio region and restarts it, because it only
+    /// switches frequencies when it starts up.
+    pub fn set_rf_region(&mut self, region: RfRegion) -> core::Result<()> {
+        try!(self.apply_setup(SerialApiSetup::SetRfRegion(region)));
+
+        self.soft_reset()
+    }
+
+    pub fn get_tx_power(&mut self) -> core::Result<TxPower> {
+        match try!(self.setup(SerialApiSetup::GetTxPower)) {
+            SerialApiSetupResult::TxPower(tx_power) => Ok(tx_power),
+            _ => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
+
+    pub fn set_tx_power(&mut self, tx_power: TxPower) -> core::Result<()> {
+        self.apply_setup(SerialApiSetup::SetTxPower(tx_power))
+    }
+
+    /// Returns the highest power that the controller transmits at for Long Range, in tenths of a
+    /// dBm.
+    pub fn get_long_range_max_tx_power(&mut self) -> core::Result<i16> {
+        match try!(self.setup(SerialApiSetup::GetLongRangeMaxTxPower)) {
+            SerialApiSetupResult::LongRangeMaxTxPower(power) => Ok(power),
+            _ => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
+
+    pub fn set_long_range_max_tx_power(&mut self, power: i16) -> core::Result<()> {
+        self.apply_setup(SerialApiSetup::SetLongRangeMaxTxPower(power))
+    }
+
+    /// Switches between one-byte and two-byte node IDs in the controller's frames.
+    pub fn set_node_id_type(&mut self, node_id_type: NodeIdType) -> core::Result<()> {
+        try!(self.apply_setup(SerialApiSetup::SetNodeIdType(node_id_type)));
+
+        self.switch_node_id_type(node_id_type);
+
+        Ok(())
+    }
+
+    /// Returns whether node IDs take one byte or two in the controller's frames.
+    pub fn node_id_type(&self) -> NodeIdType {
+        self.node_id_type
+    }
+
+    fn use_long_node_ids(&mut self) -> core::Result<()> {
+        if self.node_id_type == NodeIdType::Long {
+            return Ok(());
+        }
+
+        self.set_node_id_type(NodeIdType::Long)
+    }
+
+    fn switch_node_id_type(&mut self, node_id_type: NodeIdType) {
+        self.state.driver.lock().unwrap().set_node_id_type(node_id_type);
+        self.node_id_type = node_id_type;
+    }
+
+    fn setup(&mut self, request: SerialApiSetup) -> core::Result<SerialApiSetupResult> {
+        match try!(self.request(request)) {
+            SerialApiSetupResult::Unsupported(_) => Err(Error::new(ErrorKind::Unsupported)),
+            response => Ok(response),
+        }
+    }
+
+    fn apply_setup(&mut self, request: SerialApiSetup) -> core::Result<()> {
+        match try!(self.setup(request)) {
+            SerialApiSetupResult::Set(_, true) => Ok(()),
+            SerialApiSetupResult::Set(_, false) => Err(Error::new(ErrorKind::Rejected)),
+            _ => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
+
+    /// Sends a command to a node and waits for it to acknowledge. Returns an `Aborted` error if the
+    /// transmission is aborted through an `AbortHandle` first.
+    pub fn send_data<C: Command>(&mut self, node_id: NodeId, command: C) -> core::Result<TransmitStatus> {
+        // forget about aborts that came in while nothing was being sent
+        self.state.aborted.store(false, Ordering::Relaxed);
+
+        let request = if node_id.is_broadcast() {
+            SendData::broadcast(command, 0)
+        }
+        else {
+            SendData::new(node_id, command, 0)
+        };
+
+        self.abortable.set(true);
+        let result = self.request_with_callback(request);
+        self.abortable.set(false);
+
+        let (_, callback) = match result {
+            Err(ref err) if err.kind() == ErrorKind::Aborted => {
+                try!(self.send(&SendDataAbort::new()));
+                return Err(Error::new(ErrorKind::Aborted));
+            },
+            result => try!(result),
+        };
+
+        match callback.transmit_status() {
+            Some(status) => Ok(status),
+            None => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
+
+    /// Sends a command to every node in the network.
+    pub fn broadcast<C: Command>(&mut self, command: C) -> core::Result<BroadcastStatus> {
+        let (_, callback) = try!(self.request_with_callback(SendData::broadcast(command, 0)));
+
+        match callback.transmit_status() {
+            Some(TransmitStatus::Ok) => Ok(BroadcastStatus::Transmitted),
+            Some(status) => Ok(BroadcastStatus::NotTransmitted(status)),
+            None => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
+
+    /// Sends a command to several nodes at once. The nodes don't acknowledge multicast frames, so
+    /// the status only tells whether the frame went out.
+    pub fn send_data_multi<C: Command>(&mut self, node_ids: &[NodeId], command: C) -> core::Result<TransmitStatus> {
+        let (_, callback) = try!(self.request_with_callback(SendDataMulti::new(node_ids.to_vec(), command, 0)));
+
+        match callback.transmit_status() {
+            Some(status) => Ok(status),
+            None => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
+
+    /// Sends a command to several nodes at once like `send_data_multi`, then to each node in
+    /// `confirm` on its own, so that those nodes acknowledge it. Returns the multicast status along
+    /// with the status for each confirmed node.
+    pub fn send_data_multi_confirmed<C: Command + Clone>(&mut self, node_ids: &[NodeId], command: C, confirm: &[NodeId]) -> core::Result<(TransmitStatus, Vec<(NodeId, core::Result<TransmitStatus>)>)> {
+        let status = try!(self.send_data_multi(node_ids, command.clone()));
+
+        let results = confirm.iter().map(|&node_id| {
+            (node_id, self.send_data(node_id, command.clone()))
+        }).collect();
+
+        Ok((status, results))
+    }
+
+    /// Sends a request and returns the controller's response to it.
+    pub fn request<R: Request>(&mut self, request: R) -> core::Result<R::Response> {
+        try!(self.reinitialize_if_restarted());
+
+        let driver = self.state.driver.lock().unwrap();
+
+        let (driver, sent) = self.send_frame(driver, &request);
+        try!(sent);
+
+        let (_driver, response) = self.wait_for_frame::<R::Response, _>(driver, self.response_timeout, |_| true);
+
+        Ok(*try!(response))
+    }
+
+    /// Sends a request with a newly allocated callback ID and returns the controller's response to
+    /// it along with the callback that reports its outcome.
+    pub fn request_with_callback<R: CallbackRequest>(&mut self, mut request: R) -> core::Result<(R::Response, R::Callback)> {
+        try!(self.reinitialize_if_restarted());
+
+        let callback_id = self.next_callback_id();
+        request.set_callback_id(callback_id);
+
+        let driver = self.state.driver.lock().unwrap();
+
+        let (driver, sent) = self.send_frame(driver, &request);
+        try!(sent);
+
+        let (driver, response) = self.wait_for_frame::<R::Response, _>(driver, self.response_timeout, |_| true);
+        let response = try!(response);
+
+        if !R::accepted(&response) {
+            return Err(Error::new(ErrorKind::Rejected));
+        }
+
+        let (_driver, callback) = self.wait_for_frame::<R::Callback, _>(driver, self.callback_timeout, |callback| {
+            callback.callback_id() == callback_id
+        });
+
+        Ok((*response, *try!(callback)))
+    }
+
+    /// Sends a frame that the controller doesn't respond to.
+    fn send<F: Frame>(&mut self, frame: &F) -> core::Result<()> {
+        try!(self.reinitialize_if_restarted());
+
+        let driver = self.state.driver.lock().unwrap();
+
+        let (_driver, sent) = self.send_frame(driver, frame);
+
+        sent
+    }
+
+    /// Waits for the callback with the given ID.
+    fn wait_for_callback<C: Callback>(&self, callback_id: u8, timeout: Duration) -> core::Result<C> {
+        let driver = self.state.driver.lock().unwrap();
+
+        let (_driver, callback) = self.wait_for_frame::<C, _>(driver, timeout, |callback| {
+            callback.callback_id() == callback_id
+        });
+
+        Ok(*try!(callback))
+    }
+
+    fn own_node_id(&mut self) -> core::Result<NodeId> {
+        match self.identity {
+            Some(ref identity) => Ok(identity.node_id()),
+            None => Ok(try!(self.request(MemoryGetId::new())).node_id()),
+        }
+    }
+
+    /// Identifies the controller again after it restarted unexpectedly, since what it reported
+    /// before may no longer hold.
+    fn reinitialize_if_restarted(&mut self) -> core::Result<()> {
+        if self.state.restarted.swap(false, Ordering::Relaxed) {
+            self.reinitialize()
+        }
+        else {
+            Ok(())
+        }
+    }
+
+    fn reinitialize(&mut self) -> core::Result<()> {
+        self.state.restarted.store(false, Ordering::Relaxed);
+
+        // the controller starts up with one-byte node IDs
+        if self.node_id_type == NodeIdType::Long {
+            self.switch_node_id_type(NodeIdType::Short);
+            try!(self.set_node_id_type(NodeIdType::Long));
+        }
+
+        if self.identity.is_some() {
+            try!(self.identify());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the next callback ID, skipping zero since it tells the controller not to call back.
+    fn next_callback_id(&mut self) -> u8 {
+        self.callback_id = self.callback_id.wrapping_add(1);
+
+        if self.callback_id == 0 {
+            self.callback_id = 1;
+        }
+
+        self.callback_id
+    }
+
+    /// Sends a frame and waits for the controller to acknowledge it, retransmitting it according to
+    /// the retry policy. Frames for functions that the controller is known not to support are refused
+    /// without being sent.
+    fn send_frame<'a, F: Frame>(&self, mut driver: MutexGuard<'a, D>, frame: &F) -> (MutexGuard<'a, D>, core::Result<()>) {
+        if let Some(ref identity) = self.identity {
+            if !identity.supports(frame.function_id()) {
+                return (driver, Err(Error::new(ErrorKind::Unsupported)));
+            }
+        }
+
+        let mut retransmission = 0;
+
+        loop {
+            self.attempts.set(retransmission + 1);
+
+            // clear missed replies from previous messages
+            while self.replies.try_recv().is_ok() { }
+
+            // don't transmit again what was aborted during the backoff
+            if self.abortable.get() && self.state.aborted.swap(false, Ordering::Relaxed) {
+                return (driver, Err(Error::new(ErrorKind::Aborted)));
+            }
+
+            if let Err(err) = driver.send(frame) {
+                return (driver, Err(err));
+            }
+
+            let (guard, result) = self.wait_for_reply(driver, self.retry_policy.ack_timeout(), |reply| {
+                match reply {
+                    Reply::Ack => Some(Ok(())),
+                    Reply::Nack => Some(Err(Error::new(ErrorKind::Nack))),
+                    Reply::Cancel => Some(Err(Error::new(ErrorKind::Cancel))),
+                    Reply::Frame(_) => None,
+                }
+            });
+
+            driver = guard;
+
+            if result.is_ok() || retransmission >= self.retry_policy.retransmissions() {
+                return (driver, result);
+            }
+
+            driver = self.sleep(driver, self.retry_policy.backoff(retransmission));
+            retransmission += 1;
+        }
+    }
+
+    /// Waits for the given duration without blocking the reader thread. Returns early when an
+    /// abortable transmission is aborted.
+    fn sleep<'a>(&self, mut driver: MutexGuard<'a, D>, duration: Duration) -> MutexGuard<'a, D> {
+        let deadline = Instant::now() + duration;
+
+        loop {
+            let now = Instant::now();
+
+            if now >= deadline || (self.abortable.get() && self.state.aborted.load(Ordering::Relaxed)) {
+                return driver;
+            }
+
+            let (guard, _) = self.state.reply.wait_timeout(driver, deadline - now).unwrap();
+
+            driver = guard;
+        }
+    }
+
+    /// Waits for a frame of type `F` that satisfies `predicate`.
+    fn wait_for_frame<'a, F, P>(&self, driver: MutexGuard<'a, D>, timeout: Duration, predicate: P) -> (MutexGuard<'a, D>, core::Result<Box<F>>)
+        where F: Frame,
+              P: Fn(&F) -> bool
+    {
+        self.wait_for_reply(driver, timeout, |reply| {
+            match reply {
+                Reply::Frame(message) => {
+                    match message.downcast::<F>() {
+                        Ok(frame) => {
+                            if predicate(&frame) {
+                                Some(Ok(frame))
+                            }
+                            else {
+                                None
+                            }
+                        },
+                        Err(_) => None,
+                    }
+                },
+                _ => None,
+            }
+        })
+    }
+
+    /// Waits until `accept` returns a result for one of the replies received by the reader thread
+    /// or the timeout elapses. Replies that `accept` passes on are discarded.
+    fn wait_for_reply<'a, T, F>(&self, mut driver: MutexGuard<'a, D>, timeout: Duration, mut accept: F) -> (MutexGuard<'a, D>, core::Result<T>)
+        where F: FnMut(Reply) -> Option<core::Result<T>>
+    {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            if self.abortable.get() && self.state.aborted.swap(false, Ordering::Relaxed) {
+                return (driver, Err(Error::new(ErrorKind::Aborted)));
+            }
+
+            loop {
+                match self.replies.try_recv() {
+                    Ok(reply) => {
+                        if let Some(result) = accept(reply) {
+                            return (driver, result);
+                        }
+                    },
+                    Err(TryRecvError::Disconnected) => return (driver, Err(Error::new(ErrorKind::Timeout))),
+                    Err(TryRecvError::Empty) => break,
+                }
+            }
+
+            // could be spurious wakeup
+            let now = Instant::now();
+
+            if now >= deadline {
+                return (driver, Err(Error::new(ErrorKind::Timeout)));
+            }
+
+            let (guard, _) = self.state.reply.wait_timeout(driver, deadline - now).unwrap();
+
+            driver = guard;
+        }
+    }
 }
 
-pub struct Controller<D: Driver> {
+/// Aborts a `Controller::send_data()` call that's in progress on another thread.
+pub struct AbortHandle<D: Driver> {
     state: Arc<SharedState<D>>,
-    replies: Receiver<Reply>,
-    thread: thread::JoinHandle<()>,
 }
 
-impl<D: Driver> Controller<D> {
-    pub fn new(driver: D) -> Self {
-        let state = Arc::new(SharedState {
-            driver: Mutex::new(driver),
-            running: AtomicBool::new(true),
-            reply: Condvar::new(),
-        });
+impl<D: Driver> AbortHandle<D> {
+    /// Makes the pending `send_data()` call tell the controller to stop transmitting and return an
+    /// `Aborted` error. Does nothing if no call is pending.
+    pub fn abort(&self) {
+        // the waiting thread checks the flag while holding the lock, so it can't miss the wakeup
+        let _driver = self.state.driver.lock().unwrap();
 
+        self.state.aborted.store(true, Ordering::Relaxed);
+        self.state.reply.notify_all();
+    }
+}
 
-        let (tx, rx) = channel::<Reply>();
-        let thread = Reader::start(state.clone(), tx);
+impl<D: Driver> Clone for AbortHandle<D> {
+    fn clone(&self) -> Self {
+        AbortHandle {
+            state: self.state.clone(),
+        }
+    }
+}
 
-        Controller {
-            state: state,
-            replies: rx,
-            thread: thread,
+/// A session started by `Controller::add_node()`. The controller leaves inclusion mode when the
+/// session is finished, canceled or dropped.
+pub struct Inclusion<'a, D: Driver + 'a> {
+    controller: &'a mut Controller<D>,
+    callback_id: u8,
+    node: Option<(NodeId, NodeInfo)>,
+    active: bool,
+}
+
+impl<'a, D: Driver + 'a> Inclusion<'a, D> {
+    /// Waits for the controller to report the next step of the inclusion.
+    pub fn next_status(&mut self, timeout: Duration) -> core::Result<AddNodeStatus> {
+        let update = try!(self.controller.wait_for_callback::<AddNodeUpdate>(self.callback_id, timeout));
+
+        if let Some(node_info) = update.node_info() {
+            self.node = Some((update.node_id(), node_info.clone()));
+        }
+
+        match update.status() {
+            Some(status) => Ok(status),
+            None => Err(Error::new(ErrorKind::Protocol)),
         }
     }
 
-    pub fn stop(self) {
-        self.state.running.store(false, Ordering::Relaxed);
-        self.thread.join().unwrap();
+    /// Returns the ID of the node being added once the controller has reported it.
+    pub fn node_id(&self) -> Option<NodeId> {
+        self.node.as_ref().map(|&(node_id, _)| node_id)
+    }
+
+    /// Returns the node information frame of the node being added once the controller has
+    /// reported it.
+    pub fn node_info(&self) -> Option<&NodeInfo> {
+        self.node.as_ref().map(|&(_, ref node_info)| node_info)
+    }
+
+    /// Waits for a node to be added and returns its ID and node information frame. Gives up and
+    /// leaves inclusion mode if no node is added within `timeout`.
+    pub fn wait(mut self, timeout: Duration) -> core::Result<(NodeId, NodeInfo)> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let now = Instant::now();
+
+            if now >= deadline {
+                return Err(Error::new(ErrorKind::Timeout));
+            }
+
+            match try!(self.next_status(deadline - now)) {
+                AddNodeStatus::ProtocolDone => return self.finish(),
+                AddNodeStatus::Done => return self.finished(),
+                AddNodeStatus::Failed => return Err(Error::new(ErrorKind::Failed)),
+                _ => (),
+            }
+        }
+    }
+
+    /// Takes the controller out of inclusion mode without waiting for a node.
+    pub fn cancel(mut self) -> core::Result<()> {
+        self.stop()
+    }
+
+    /// Tells the controller to complete the inclusion after the protocol part is done.
+    fn finish(&mut self) -> core::Result<(NodeId, NodeInfo)> {
+        let timeout = self.controller.callback_timeout;
+
+        try!(self.controller.send(&AddNodeToNetwork::new(AddNodeMode::Stop, self.callback_id)));
+
+        loop {
+            match try!(self.next_status(timeout)) {
+                AddNodeStatus::Done => return self.finished(),
+                AddNodeStatus::Failed => return Err(Error::new(ErrorKind::Failed)),
+                _ => (),
+            }
+        }
+    }
+
+    fn finished(&mut self) -> core::Result<(NodeId, NodeInfo)> {
+        let node = self.node.take();
+
+        // The controller stays in inclusion mode until it's told to stop, even after it's done.
+        // The node is in the network by now, so it's reported even if stopping fails.
+        let _ = self.stop();
+
+        match node {
+            Some(node) => Ok(node),
+            None => Err(Error::new(ErrorKind::Protocol)),
+        }
     }
 
-    pub fn send_data<C: Command>(&mut self, node_id: NodeId, command: C) -> core::Result<()> {
-        let message = SendData::new(node_id, command, 0x11);
+    fn stop(&mut self) -> core::Result<()> {
+        self.active = false;
+        self.controller.send(&AddNodeToNetwork::new(AddNodeMode::Stop, 0))
+    }
+}
+
+impl<'a, D: Driver + 'a> Drop for Inclusion<'a, D> {
+    fn drop(&mut self) {
+        if self.active {
+            let _ = self.stop();
+        }
+    }
+}
+
+/// A session started by `Controller::remove_node()`. The controller leaves exclusion mode when the
+/// session is finished, canceled or dropped.
+pub struct Exclusion<'a, D: Driver + 'a> {
+    controller: &'a mut Controller<D>,
+    callback_id: u8,
+    node_id: Option<NodeId>,
+    active: bool,
+}
 
-        let mut driver = self.state.driver.lock().unwrap();
+impl<'a, D: Driver + 'a> Exclusion<'a, D> {
+    /// Waits for the controller to report the next step of the exclusion.
+    pub fn next_status(&mut self, timeout: Duration) -> core::Result<RemoveNodeStatus> {
+        let update = try!(self.controller.wait_for_callback::<RemoveNodeUpdate>(self.callback_id, timeout));
+        let status = update.status();
 
-        // clear missed replies from previous messages
-        while self.replies.try_recv().is_ok() { }
+        match status {
+            Some(RemoveNodeStatus::RemovingSlave) | Some(RemoveNodeStatus::RemovingController) => {
+                self.node_id = Some(update.node_id());
+            },
+            _ => (),
+        }
+
+        match status {
+            Some(status) => Ok(status),
+            None => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
 
-        try!(driver.send(&message));
+    /// Returns the ID of the node being removed once the controller has reported it.
+    pub fn node_id(&self) -> Option<NodeId> {
+        self.node_id
+    }
 
-        let mut timeout = Duration::from_millis(REPLY_TIMEOUT_MS);
+    /// Waits for a node to be removed and returns its ID. Gives up and leaves exclusion mode if no
+    /// node is removed within `timeout`.
+    pub fn wait(mut self, timeout: Duration) -> core::Result<NodeId> {
         let deadline = Instant::now() + timeout;
 
         loop {
-            let (guard, wait) = self.state.reply.wait_timeout(driver, timeout).unwrap();
+            let now = Instant::now();
 
-            driver = guard;
+            if now >= deadline {
+                return Err(Error::new(ErrorKind::Timeout));
+            }
 
-            match self.replies.try_recv() {
-                Ok(Reply::Ack) => return Ok(()),
-                Ok(Reply::Nack) => return Err(Error::new(ErrorKind::Nack)),
-                Ok(Reply::Cancel) => return Err(Error::new(ErrorKind::Cancel)),
-                Err(TryRecvError::Disconnected) => return Err(Error::new(ErrorKind::Timeout)),
-                Err(TryRecvError::Empty) => {
-                    if wait.timed_out() {
-                        return Err(Error::new(ErrorKind::Timeout));
-                    }
+            match try!(self.next_status(deadline - now)) {
+                RemoveNodeStatus::Done => break,
+                RemoveNodeStatus::Failed => return Err(Error::new(ErrorKind::Failed)),
+                _ => (),
+            }
+        }
 
-                    // could be spurious wakeup
-                    let now = Instant::now();
+        let node_id = self.node_id;
 
-                    if now > deadline {
-                        return Err(Error::new(ErrorKind::Timeout));
-                    }
+        // the node is gone from the network by now, so it's reported even if stopping fails
+        let _ = self.stop();
 
-                    timeout = deadline - now;
-                },
+        // nodes from other networks are reported as node zero
+        match node_id {
+            Some(node_id) => Ok(node_id),
+            None => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
+
+    /// Takes the controller out of exclusion mode without waiting for a node.
+    pub fn cancel(mut self) -> core::Result<()> {
+        self.stop()
+    }
+
+    fn stop(&mut self) -> core::Result<()> {
+        self.active = false;
+        self.controller.send(&RemoveNodeFromNetwork::new(RemoveNodeMode::Stop, 0))
+    }
+}
+
+impl<'a, D: Driver + 'a> Drop for Exclusion<'a, D> {
+    fn drop(&mut self) {
+        if self.active {
+            let _ = self.stop();
+        }
+    }
+}
+
+/// A session started by `Controller::learn_mode()`. The controller leaves learn mode when the
+/// session is finished, canceled or dropped.
+pub struct Learning<'a, D: Driver + 'a> {
+    controller: &'a mut Controller<D>,
+    callback_id: u8,
+    active: bool,
+}
+
+impl<'a, D: Driver + 'a> Learning<'a, D> {
+    /// Waits for the controller to report the next step of learn mode.
+    pub fn next_status(&mut self, timeout: Duration) -> core::Result<LearnModeStatus> {
+        let update = try!(self.controller.wait_for_callback::<LearnModeUpdate>(self.callback_id, timeout));
+
+        match update.status() {
+            Some(status) => Ok(status),
+            None => Err(Error::new(ErrorKind::Protocol)),
+        }
+    }
+
+    /// Waits for another controller to add or remove this one and returns the resulting home ID
+    /// and node ID. Gives up and leaves learn mode if nothing happens within `timeout`.
+    pub fn wait(mut self, timeout: Duration) -> core::Result<(HomeId, NodeId)> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let now = Instant::now();
+
+            if now >= deadline {
+                return Err(Error::new(ErrorKind::Timeout));
+            }
+
+            match try!(self.next_status(deadline - now)) {
+                LearnModeStatus::Done => break,
+                LearnModeStatus::Failed => return Err(Error::new(ErrorKind::Failed)),
+                _ => (),
             }
         }
+
+        // The controller stays in learn mode until it's told to stop, even after it's done. It has
+        // joined the network by now, so that's reported even if stopping fails.
+        let _ = self.stop();
+
+        // the controller now belongs to a different network, which may have changed more than its IDs
+        if self.controller.identity.is_some() {
+            let identity = try!(self.controller.identify());
+            return Ok((identity.home_id(), identity.node_id()));
+        }
+
+        let memory_id = try!(self.controller.request(MemoryGetId::new()));
+        Ok((memory_id.home_id(), memory_id.node_id()))
+    }
+
+    /// Takes the controller out of learn mode.
+    pub fn cancel(mut self) -> core::Result<()> {
+        self.stop()
+    }
+
+    fn stop(&mut self) -> core::Result<()> {
+        self.active = false;
+        self.controller.request(SetLearnMode::new(LearnMode::Disable, 0)).map(|_| ())
+    }
+}
+
+impl<'a, D: Driver + 'a> Drop for Learning<'a, D> {
+    fn drop(&mut self) {
+        if self.active {
+            let _ = self.stop();
+        }
     }
 }
 
@@ -116,36 +1627,98 @@ impl<D: Driver> Reader<D> {
     }
 
     fn run(&self) {
+        // whether the previous receive failed in a way that's likely to persist, e.g., the port is gone
+        let mut failing = false;
+
         while self.state.running.load(Ordering::Relaxed) {
             let mut driver = self.state.driver.lock().unwrap();
+            let mut back_off = false;
 
             match driver.receive() {
                 Ok(message) => {
+                    failing = false;
+
                     match message.preamble_id() {
-                        PreambleId::Ack => {
-                            self.replies.send(Reply::Ack).unwrap();
-                            self.state.reply.notify_one();
-                        }
-                        PreambleId::Nack => {
-                            self.replies.send(Reply::Nack).unwrap();
-                            self.state.reply.notify_one();
-                        }
-                        PreambleId::Cancel => {
-                            self.replies.send(Reply::Cancel).unwrap();
-                            self.state.reply.notify_one();
-                        }
+                        PreambleId::Ack => self.reply(Reply::Ack),
+                        PreambleId::Nack => self.reply(Reply::Nack),
+                        PreambleId::Cancel => self.reply(Reply::Cancel),
                         PreambleId::Frame => {
-                            // TODO: handle error and packet
-                            driver.send(&Ack::new()).unwrap();
+                            if let Err(err) = driver.send(&Ack::new()) {
+                                self.state.notify(Event::Error(err));
+                            }
+
+                            self.dispatch(message);
                         },
                     }
                 },
                 Err(err) => {
-                    if err.kind() != ErrorKind::Timeout {
-                        panic!("error = {:?}", err);
+                    match err.kind() {
+                        ErrorKind::Timeout => failing = false,
+                        ErrorKind::Corrupt | ErrorKind::ShortRead => {
+                            // ask the controller to send the frame again
+                            if let Err(err) = driver.send(&Nack::new()) {
+                                self.state.notify(Event::Error(err));
+                            }
+
+                            self.state.notify(Event::Error(err));
+                        },
+                        ErrorKind::Protocol => {
+                            // the frame arrived intact, but there's no way to make sense of it
+                            if let Err(err) = driver.send(&Ack::new()) {
+                                self.state.notify(Event::Error(err));
+                            }
+
+                            self.state.notify(Event::Error(err));
+                        },
+                        _ => {
+                            // report the error once rather than every time it repeats
+                            if !failing {
+                                self.state.notify(Event::Error(err));
+                            }
+
+                            failing = true;
+                            back_off = true;
+                        },
                     }
                 },
             }
+
+            if back_off {
+                // let the controller have the driver while waiting for the error to go away
+                drop(driver);
+                thread::sleep(Duration::from_millis(RECEIVE_ERROR_BACKOFF_MS));
+            }
+        }
+    }
+
+    /// Passes unsolicited frames on to the listeners and everything else to the controller. The
+    /// controller also learns about restarts so that it can wait for them.
+    fn dispatch(&self, message: AnyMessage) {
+        let message = match message.downcast::<ApplicationCommandHandler>() {
+            Ok(frame) => return self.state.notify(Event::ApplicationCommand(*frame)),
+            Err(message) => message,
+        };
+
+        let message = match message.downcast::<ApplicationUpdate>() {
+            Ok(frame) => return self.state.notify(Event::ApplicationUpdate(*frame)),
+            Err(message) => message,
+        };
+
+        if let Some(frame) = message.downcast_ref::<SerialApiStarted>() {
+            self.state.restarted.store(true, Ordering::Relaxed);
+            self.state.notify(Event::SerialApiStarted(frame.clone()));
+        }
+
+        self.reply(Reply::Frame(message));
+    }
+
+    fn reply(&self, reply: Reply) {
+        // the controller was dropped without being stopped, so nobody is left to read for
+        if self.replies.send(reply).is_err() {
+            self.state.running.store(false, Ordering::Relaxed);
+            return;
         }
+
+        self.state.reply.notify_one();
     }
 }
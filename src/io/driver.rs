@@ -3,12 +3,16 @@ use std::time::Duration;
 use serial::{self, SerialPort};
 
 use core;
+use protocol::bits::NodeIdType;
 use protocol::message::{MessageSerializer, Message, AnyMessage};
 use protocol::serialization::Reader;
 
 pub trait Driver: Send + 'static {
     fn send(&mut self, message: &Message) -> core::Result<()>;
     fn receive(&mut self) -> core::Result<AnyMessage>;
+
+    /// Switches the width of node IDs in frames after the controller was set up for it.
+    fn set_node_id_type(&mut self, _node_id_type: NodeIdType) {}
 }
 
 const SETTINGS: serial::PortSettings = serial::PortSettings {
@@ -56,4 +60,9 @@ impl<S: SerialPort+Send+'static> Driver for SerialDriver<S> {
 
         Ok(response)
     }
+
+    fn set_node_id_type(&mut self, node_id_type: NodeIdType) {
+        self.request.set_node_id_type(node_id_type);
+        self.response.set_node_id_type(node_id_type);
+    }
 }
@@ -4,4 +4,6 @@ extern crate serial;
 
 pub mod core;
 pub mod io;
+pub mod network;
+pub mod nvm;
 pub mod protocol;
@@ -0,0 +1,122 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use core::NodeId;
+use protocol::message::NodeProtocolInfo;
+
+/// How a node takes part in the network, which determines how it's drawn.
+#[derive(Debug,Clone,Copy,Hash,PartialEq,Eq)]
+pub enum NodeRole {
+    Controller,
+    Listening,
+    Sleeping,
+}
+
+impl NodeRole {
+    pub fn of(protocol_info: &NodeProtocolInfo) -> NodeRole {
+        if protocol_info.is_controller() {
+            NodeRole::Controller
+        }
+        else if protocol_info.is_listening() {
+            NodeRole::Listening
+        }
+        else {
+            NodeRole::Sleeping
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            NodeRole::Controller => "controller",
+            NodeRole::Listening => "listening",
+            NodeRole::Sleeping => "sleeping",
+        }
+    }
+
+    fn dot_attributes(&self) -> &'static str {
+        match *self {
+            NodeRole::Controller => "shape=doublecircle, style=filled, fillcolor=lightblue",
+            NodeRole::Listening => "shape=circle, style=filled, fillcolor=palegreen",
+            NodeRole::Sleeping => "shape=circle, style=dashed",
+        }
+    }
+}
+
+/// The nodes in a network and which of them can reach each other directly.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct NetworkMap {
+    nodes: BTreeMap<NodeId, (NodeRole, Vec<NodeId>)>,
+}
+
+impl NetworkMap {
+    pub fn new() -> Self {
+        NetworkMap {
+            nodes: BTreeMap::new(),
+        }
+    }
+
+    pub fn add_node(&mut self, node_id: NodeId, role: NodeRole, neighbors: Vec<NodeId>) {
+        self.nodes.insert(node_id, (role, neighbors));
+    }
+
+    /// Returns the IDs of all nodes in ascending order.
+    pub fn node_ids(&self) -> Vec<NodeId> {
+        self.nodes.keys().cloned().collect()
+    }
+
+    pub fn role(&self, node_id: NodeId) -> Option<NodeRole> {
+        self.nodes.get(&node_id).map(|&(role, _)| role)
+    }
+
+    pub fn neighbors(&self, node_id: NodeId) -> Option<&[NodeId]> {
+        self.nodes.get(&node_id).map(|&(_, ref neighbors)| &neighbors[..])
+    }
+
+    /// Returns each pair of neighbors once, lower node ID first, even if only one of them reported
+    /// the other.
+    pub fn links(&self) -> Vec<(NodeId, NodeId)> {
+        let mut links = BTreeSet::<(NodeId, NodeId)>::new();
+
+        for (&node_id, &(_, ref neighbors)) in self.nodes.iter() {
+            for &neighbor in neighbors {
+                if neighbor < node_id {
+                    links.insert((neighbor, node_id));
+                }
+                else if neighbor > node_id {
+                    links.insert((node_id, neighbor));
+                }
+            }
+        }
+
+        links.into_iter().collect()
+    }
+
+    /// Renders the map as an undirected Graphviz graph.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("graph zwave {\n");
+
+        for (node_id, &(role, _)) in self.nodes.iter() {
+            write!(dot, "    {} [{}];\n", node_id.value(), role.dot_attributes()).unwrap();
+        }
+
+        for (a, b) in self.links() {
+            write!(dot, "    {} -- {};\n", a.value(), b.value()).unwrap();
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders the map as a JSON object with a list of nodes and a list of links.
+    pub fn to_json(&self) -> String {
+        let nodes = self.nodes.iter().map(|(node_id, &(role, _))| {
+            format!("{{\"id\":{},\"role\":\"{}\"}}", node_id.value(), role.name())
+        }).collect::<Vec<_>>();
+
+        let links = self.links().into_iter().map(|(a, b)| {
+            format!("{{\"source\":{},\"target\":{}}}", a.value(), b.value())
+        }).collect::<Vec<_>>();
+
+        format!("{{\"nodes\":[{}],\"links\":[{}]}}", nodes.join(","), links.join(","))
+    }
+}
@@ -0,0 +1,3 @@
+pub use self::map::{NetworkMap, NodeRole};
+
+mod map;
@@ -0,0 +1,286 @@
+use core::{self, HomeId, NodeId};
+use protocol::message::NodeProtocolInfo;
+
+mod series500;
+mod series700;
+
+const MAX_NODES: usize = 232;
+const NODE_MASK_LENGTH: usize = 29;
+const NODE_INFO_LENGTH: usize = 5;
+const ROUTE_LENGTH: usize = 5;
+const MAX_REPEATERS: usize = 4;
+
+const CAPABILITY_ROUTING: u8 = 0x40;
+const SECURITY_CONTROLLER: u8 = 0x02;
+
+const BASIC_CLASS_STATIC_CONTROLLER: u8 = 0x02;
+const BASIC_CLASS_SLAVE: u8 = 0x03;
+const BASIC_CLASS_ROUTING_SLAVE: u8 = 0x04;
+
+/// Returns the CRC-16/CCITT checksum of an NVM image, which tells whether two images are the same.
+pub fn checksum(image: &[u8]) -> u16 {
+    let mut crc = 0xFFFFu16;
+
+    for &byte in image {
+        crc ^= (byte as u16) << 8;
+
+        for _ in 0..8 {
+            if crc & 0x8000 != 0 {
+                crc = crc << 1 ^ 0x1021;
+            }
+            else {
+                crc <<= 1;
+            }
+        }
+    }
+
+    crc
+}
+
+/// How a controller lays out its network in NVM, which differs between chip generations.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Layout {
+    /// A flat table of fixed-size entries, as used by 500-series controllers.
+    Series500,
+
+    /// NVM3 objects in 2 KB pages behind the application area, as used by 700-series controllers.
+    Series700,
+
+    /// NVM3 objects in 8 KB pages, as used by 800-series controllers.
+    Series800,
+}
+
+impl Layout {
+    /// Tells which layout an image uses by looking for an NVM3 page header.
+    pub fn detect(image: &[u8]) -> Layout {
+        for &layout in [Layout::Series700, Layout::Series800].iter() {
+            if series700::is_formatted(image, layout) {
+                return layout;
+            }
+        }
+
+        Layout::Series500
+    }
+}
+
+/// A stored route to a node: up to four repeaters and the speed it was last used at.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Route {
+    repeaters: Vec<NodeId>,
+    conf: u8,
+}
+
+impl Route {
+    pub fn new(repeaters: Vec<NodeId>, conf: u8) -> Self {
+        Route {
+            repeaters: repeaters,
+            conf: conf,
+        }
+    }
+
+    pub fn repeaters(&self) -> &[NodeId] {
+        &self.repeaters
+    }
+
+    /// Returns the raw route configuration byte, which holds the speed and beaming flags.
+    pub fn conf(&self) -> u8 {
+        self.conf
+    }
+
+    fn decode(buffer: &[u8]) -> Option<Route> {
+        if buffer.iter().all(|&byte| byte == 0x00) {
+            return None;
+        }
+
+        let repeaters = buffer[..MAX_REPEATERS].iter()
+            .take_while(|&&byte| byte != 0x00)
+            .map(|&byte| NodeId(byte as u16))
+            .collect();
+
+        Some(Route::new(repeaters, buffer[MAX_REPEATERS]))
+    }
+
+    fn encode(route: Option<&Route>, buffer: &mut Vec<u8>) {
+        let mut entry = [0u8; ROUTE_LENGTH];
+
+        if let Some(route) = route {
+            for (index, node_id) in route.repeaters.iter().take(MAX_REPEATERS).enumerate() {
+                entry[index] = node_id.value() as u8;
+            }
+
+            entry[MAX_REPEATERS] = route.conf;
+        }
+
+        buffer.extend_from_slice(&entry);
+    }
+}
+
+/// What the controller stores about one node.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct NodeData {
+    node_id: NodeId,
+    protocol_info: NodeProtocolInfo,
+    neighbors: Vec<NodeId>,
+    route: Option<Route>,
+}
+
+impl NodeData {
+    pub fn new(node_id: NodeId, protocol_info: NodeProtocolInfo, neighbors: Vec<NodeId>, route: Option<Route>) -> Self {
+        NodeData {
+            node_id: node_id,
+            protocol_info: protocol_info,
+            neighbors: neighbors,
+            route: route,
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn protocol_info(&self) -> &NodeProtocolInfo {
+        &self.protocol_info
+    }
+
+    pub fn neighbors(&self) -> &[NodeId] {
+        &self.neighbors
+    }
+
+    /// Returns the last working route to the node, if the controller has cached one.
+    pub fn route(&self) -> Option<&Route> {
+        self.route.as_ref()
+    }
+
+    fn decode(node_id: NodeId, buffer: &[u8], neighbors: Vec<NodeId>, route: Option<Route>) -> NodeData {
+        let (capability, security, reserved, generic_class, specific_class) = (buffer[0], buffer[1], buffer[2], buffer[3], buffer[4]);
+
+        // NVM doesn't keep the basic class, so it's derived the way the controller reports it.
+        let basic_class = if security & SECURITY_CONTROLLER != 0 {
+            BASIC_CLASS_STATIC_CONTROLLER
+        }
+        else if capability & CAPABILITY_ROUTING != 0 {
+            BASIC_CLASS_ROUTING_SLAVE
+        }
+        else {
+            BASIC_CLASS_SLAVE
+        };
+
+        let protocol_info = NodeProtocolInfo::new(capability, security, reserved, basic_class, generic_class, specific_class);
+        NodeData::new(node_id, protocol_info, neighbors, route)
+    }
+
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        let info = &self.protocol_info;
+        buffer.extend_from_slice(&[info.capability(), info.security(), info.reserved(), info.generic_class(), info.specific_class()]);
+    }
+}
+
+/// The network as stored in a controller's NVM, independent of the layout it was read from.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct NetworkData {
+    home_id: HomeId,
+    node_id: NodeId,
+    suc_node_id: Option<NodeId>,
+    nodes: Vec<NodeData>,
+}
+
+impl NetworkData {
+    pub fn new(home_id: HomeId, node_id: NodeId, suc_node_id: Option<NodeId>, nodes: Vec<NodeData>) -> Self {
+        NetworkData {
+            home_id: home_id,
+            node_id: node_id,
+            suc_node_id: suc_node_id,
+            nodes: nodes,
+        }
+    }
+
+    pub fn home_id(&self) -> HomeId {
+        self.home_id
+    }
+
+    /// Returns the controller's own node ID.
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn suc_node_id(&self) -> Option<NodeId> {
+        self.suc_node_id
+    }
+
+    /// Returns the nodes in the network, ordered by node ID.
+    pub fn nodes(&self) -> &[NodeData] {
+        &self.nodes
+    }
+
+    fn max_node_id(&self) -> u8 {
+        self.nodes.iter().map(|node| node.node_id.value() as u8).max().unwrap_or(0)
+    }
+
+    /// Fails if any node ID doesn't fit into the layouts, e.g., that of a Long Range node.
+    fn validate(&self) -> core::Result<()> {
+        let mut node_ids = vec![self.node_id];
+        node_ids.extend(self.suc_node_id);
+
+        for node in &self.nodes {
+            node_ids.push(node.node_id);
+            node_ids.extend_from_slice(&node.neighbors);
+
+            if let Some(ref route) = node.route {
+                node_ids.extend_from_slice(&route.repeaters);
+            }
+        }
+
+        if node_ids.iter().any(|node_id| node_id.value() == 0 || node_id.value() as usize > MAX_NODES) {
+            return Err(core::Error::new(core::ErrorKind::Unsupported));
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the network out of an NVM image in any layout.
+pub fn read(image: &[u8]) -> core::Result<NetworkData> {
+    match Layout::detect(image) {
+        Layout::Series500 => series500::read(image),
+        layout => series700::read(image, layout),
+    }
+}
+
+/// Writes the network into a freshly formatted NVM image of the given layout and size.
+pub fn write(network: &NetworkData, layout: Layout, size: usize) -> core::Result<Vec<u8>> {
+    match layout {
+        Layout::Series500 => series500::write(network, size),
+        layout => series700::write(network, layout, size),
+    }
+}
+
+/// Converts an NVM backup into the layout of another controller, e.g., to move a network from a
+/// 500-series stick to a 700-series one. The image is sized like the target controller's NVM.
+pub fn convert(image: &[u8], layout: Layout, size: usize) -> core::Result<Vec<u8>> {
+    let network = try!(read(image));
+    write(&network, layout, size)
+}
+
+fn serialize_node_mask(node_ids: &[NodeId], buffer: &mut Vec<u8>) {
+    let offset = buffer.len();
+    buffer.extend_from_slice(&[0u8; NODE_MASK_LENGTH]);
+
+    for node_id in node_ids {
+        let bit = node_id.value() as usize - 1;
+        buffer[offset + bit / 8] |= 1 << (bit % 8);
+    }
+}
+
+fn deserialize_node_mask(buffer: &[u8]) -> Vec<NodeId> {
+    let mut node_ids = Vec::<NodeId>::new();
+
+    for (index, byte) in buffer.iter().take(NODE_MASK_LENGTH).enumerate() {
+        for bit in 0..8 {
+            if byte & (1 << bit) != 0 && index * 8 + bit < MAX_NODES {
+                node_ids.push(NodeId((index * 8 + bit + 1) as u16));
+            }
+        }
+    }
+
+    node_ids
+}
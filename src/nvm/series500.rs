@@ -0,0 +1,210 @@
+use core::{self, HomeId, NodeId};
+
+use super::{NetworkData, NodeData, Route};
+use super::{MAX_NODES, NODE_MASK_LENGTH, NODE_INFO_LENGTH, ROUTE_LENGTH};
+use super::{serialize_node_mask, deserialize_node_mask};
+
+const SUC_UPDATE_ENTRY_LENGTH: usize = 22;
+const SUC_MAX_UPDATES: usize = 64;
+
+const CONFIGURATION_VALID_0: u8 = 0x54;
+const CONFIGURATION_VALID_1: u8 = 0xA5;
+
+/// The protocol's entries at the start of a static controller's NVM.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+enum Entry {
+    TotalEnd,
+    ModuleSize,
+    Reserved1,
+    ExHomeId,
+    Reserved2,
+    HomeId,
+    NodeId,
+    ConfigurationValid,
+    ConfigurationReallyValid,
+    Reserved3,
+    PreferredRepeaters,
+    PendingDiscovery,
+    NodeTable,
+    RoutingTable,
+    SucNodeList,
+    SucControllerList,
+    SucActive,
+    SucLastIndex,
+    SucRoutingSlaveList,
+    ZensorTable,
+    ControllerConfiguration,
+    MaxNodeId,
+    ReservedId,
+    LastUsedNodeId,
+    StaticControllerNodeId,
+    PendingUpdate,
+    RouteCache,
+    RouteCacheNlwrSr,
+    RouteCacheAppLock,
+    RouteCacheMagic,
+    ModuleSizeEndMarker,
+}
+
+/// Entry, size of one item and number of items, in the order they're stored.
+const ENTRIES: &'static [(Entry, usize, usize)] = &[
+    (Entry::TotalEnd, 2, 1),
+    (Entry::ModuleSize, 2, 1),
+    (Entry::Reserved1, 1, 4),
+    (Entry::ExHomeId, 4, 1),
+    (Entry::Reserved2, 1, 4),
+    (Entry::HomeId, 4, 1),
+    (Entry::NodeId, 1, 1),
+    (Entry::ConfigurationValid, 1, 1),
+    (Entry::ConfigurationReallyValid, 1, 1),
+    (Entry::Reserved3, 1, 1),
+    (Entry::PreferredRepeaters, NODE_MASK_LENGTH, 1),
+    (Entry::PendingDiscovery, NODE_MASK_LENGTH, 1),
+    (Entry::NodeTable, NODE_INFO_LENGTH, MAX_NODES),
+    (Entry::RoutingTable, NODE_MASK_LENGTH, MAX_NODES),
+    (Entry::SucNodeList, SUC_UPDATE_ENTRY_LENGTH, SUC_MAX_UPDATES),
+    (Entry::SucControllerList, 1, MAX_NODES),
+    (Entry::SucActive, 1, 1),
+    (Entry::SucLastIndex, 1, 1),
+    (Entry::SucRoutingSlaveList, NODE_MASK_LENGTH, 1),
+    (Entry::ZensorTable, NODE_MASK_LENGTH, 1),
+    (Entry::ControllerConfiguration, 1, 1),
+    (Entry::MaxNodeId, 1, 1),
+    (Entry::ReservedId, 1, 1),
+    (Entry::LastUsedNodeId, 1, 1),
+    (Entry::StaticControllerNodeId, 1, 1),
+    (Entry::PendingUpdate, NODE_MASK_LENGTH, 1),
+    (Entry::RouteCache, ROUTE_LENGTH, MAX_NODES),
+    (Entry::RouteCacheNlwrSr, ROUTE_LENGTH, MAX_NODES),
+    (Entry::RouteCacheAppLock, NODE_MASK_LENGTH, 1),
+    (Entry::RouteCacheMagic, 1, 1),
+    (Entry::ModuleSizeEndMarker, 2, 1),
+];
+
+fn offset(entry: Entry) -> usize {
+    ENTRIES.iter()
+        .take_while(|&&(e, _, _)| e != entry)
+        .map(|&(_, size, count)| size * count)
+        .sum()
+}
+
+fn item_offset(entry: Entry, index: usize) -> usize {
+    let &(_, size, _) = ENTRIES.iter().find(|&&(e, _, _)| e == entry).unwrap();
+    offset(entry) + index * size
+}
+
+fn protocol_size() -> usize {
+    ENTRIES.iter().map(|&(_, size, count)| size * count).sum()
+}
+
+fn u32_at(buffer: &[u8], offset: usize) -> u32 {
+    (buffer[offset] as u32) << 24 | (buffer[offset + 1] as u32) << 16 | (buffer[offset + 2] as u32) << 8 | buffer[offset + 3] as u32
+}
+
+fn put_u16(buffer: &mut [u8], offset: usize, value: u16) {
+    buffer[offset] = (value >> 8) as u8;
+    buffer[offset + 1] = value as u8;
+}
+
+fn put_u32(buffer: &mut [u8], offset: usize, value: u32) {
+    put_u16(buffer, offset, (value >> 16) as u16);
+    put_u16(buffer, offset + 2, value as u16);
+}
+
+pub fn read(image: &[u8]) -> core::Result<NetworkData> {
+    if image.len() < protocol_size() {
+        return Err(core::Error::new(core::ErrorKind::Corrupt));
+    }
+
+    // The protocol writes these markers last, so anything else means it never finished setting up
+    // the network.
+    if image[offset(Entry::ConfigurationValid)] != CONFIGURATION_VALID_0 || image[offset(Entry::ConfigurationReallyValid)] != CONFIGURATION_VALID_1 {
+        return Err(core::Error::new(core::ErrorKind::Corrupt));
+    }
+
+    let home_id = HomeId(u32_at(image, offset(Entry::HomeId)));
+    let node_id = image[offset(Entry::NodeId)];
+
+    if home_id.value() == 0 || home_id.value() == 0xFFFFFFFF || node_id == 0 || node_id as usize > MAX_NODES {
+        return Err(core::Error::new(core::ErrorKind::Corrupt));
+    }
+
+    let suc_node_id = match image[offset(Entry::StaticControllerNodeId)] {
+        0 => None,
+        id => Some(NodeId(id as u16)),
+    };
+
+    let mut nodes = Vec::<NodeData>::new();
+
+    for index in 0..MAX_NODES {
+        let info_offset = item_offset(Entry::NodeTable, index);
+        let info = &image[info_offset..info_offset + NODE_INFO_LENGTH];
+
+        // Unused entries are cleared, and a node always has a generic device class.
+        if info[3] == 0x00 {
+            continue;
+        }
+
+        let mask_offset = item_offset(Entry::RoutingTable, index);
+        let neighbors = deserialize_node_mask(&image[mask_offset..mask_offset + NODE_MASK_LENGTH]);
+
+        let route_offset = item_offset(Entry::RouteCache, index);
+        let route = Route::decode(&image[route_offset..route_offset + ROUTE_LENGTH]);
+
+        nodes.push(NodeData::decode(NodeId(index as u16 + 1), info, neighbors, route));
+    }
+
+    Ok(NetworkData::new(home_id, NodeId(node_id as u16), suc_node_id, nodes))
+}
+
+pub fn write(network: &NetworkData, size: usize) -> core::Result<Vec<u8>> {
+    let protocol_size = protocol_size();
+
+    if size < protocol_size || size > 0x10000 {
+        return Err(core::Error::new(core::ErrorKind::Unsupported));
+    }
+
+    try!(network.validate());
+
+    // Everything past the protocol's entries belongs to the application and is left erased.
+    let mut image = vec![0xFFu8; size];
+
+    for byte in &mut image[..protocol_size] {
+        *byte = 0x00;
+    }
+
+    put_u16(&mut image, offset(Entry::TotalEnd), (size - 1) as u16);
+    put_u16(&mut image, offset(Entry::ModuleSize), protocol_size as u16);
+    put_u16(&mut image, offset(Entry::ModuleSizeEndMarker), protocol_size as u16);
+    put_u32(&mut image, offset(Entry::ExHomeId), network.home_id().value());
+    put_u32(&mut image, offset(Entry::HomeId), network.home_id().value());
+    image[offset(Entry::NodeId)] = network.node_id().value() as u8;
+    image[offset(Entry::ConfigurationValid)] = CONFIGURATION_VALID_0;
+    image[offset(Entry::ConfigurationReallyValid)] = CONFIGURATION_VALID_1;
+    image[offset(Entry::MaxNodeId)] = network.max_node_id();
+    image[offset(Entry::LastUsedNodeId)] = network.max_node_id();
+    image[offset(Entry::StaticControllerNodeId)] = network.suc_node_id().map_or(0, |node_id| node_id.value() as u8);
+
+    for node in network.nodes() {
+        let index = node.node_id().value() as usize - 1;
+        let mut buffer = Vec::<u8>::new();
+
+        node.encode(&mut buffer);
+        serialize_node_mask(node.neighbors(), &mut buffer);
+        Route::encode(node.route(), &mut buffer);
+
+        let (info, rest) = buffer.split_at(NODE_INFO_LENGTH);
+        let (mask, route) = rest.split_at(NODE_MASK_LENGTH);
+
+        let info_offset = item_offset(Entry::NodeTable, index);
+        image[info_offset..info_offset + NODE_INFO_LENGTH].copy_from_slice(info);
+
+        let mask_offset = item_offset(Entry::RoutingTable, index);
+        image[mask_offset..mask_offset + NODE_MASK_LENGTH].copy_from_slice(mask);
+
+        let route_offset = item_offset(Entry::RouteCache, index);
+        image[route_offset..route_offset + ROUTE_LENGTH].copy_from_slice(route);
+    }
+
+    Ok(image)
+}
@@ -0,0 +1,372 @@
+use std::collections::BTreeMap;
+
+use core::{self, HomeId, NodeId};
+
+use super::{Layout, NetworkData, NodeData, Route};
+use super::{NODE_MASK_LENGTH, NODE_INFO_LENGTH, ROUTE_LENGTH};
+use super::{serialize_node_mask, deserialize_node_mask};
+
+const PAGE_HEADER_LENGTH: usize = 20;
+const PAGE_VERSION: u16 = 0x0001;
+const PAGE_MAGIC: u16 = 0xB29A;
+const ERASE_COUNT_MASK: u32 = 0x07FFFFFF;
+const DEVICE_FAMILY_UNKNOWN: u16 = 0x07FF;
+const DEVICE_MEMORY_MAPPED: u16 = 0x1000;
+
+const OBJECT_TYPE_DATA_LARGE: u32 = 0;
+const OBJECT_TYPE_COUNTER_LARGE: u32 = 1;
+const OBJECT_TYPE_COUNTER_SMALL: u32 = 2;
+const OBJECT_TYPE_DELETED: u32 = 3;
+const OBJECT_TYPE_DATA_SMALL: u32 = 7;
+const MAX_SMALL_DATA_LENGTH: usize = 120;
+const COUNTER_LENGTH: usize = 204;
+
+const FRAGMENT_NONE: u32 = 0;
+const FRAGMENT_FIRST: u32 = 1;
+const FRAGMENT_LAST: u32 = 3;
+
+const FILE_PROTOCOL_VERSION: u32 = 0x50000;
+const FILE_CONTROLLER_INFO: u32 = 0x50004;
+const FILE_NODE_LIST: u32 = 0x50005;
+const FILE_ROUTE_CACHE_EXISTS: u32 = 0x5000B;
+const FILE_NODE_INFO: u32 = 0x50100;
+const FILE_ROUTE_CACHE: u32 = 0x51400;
+
+const PROTOCOL_MAJOR_VERSION: u8 = 7;
+const PROTOCOL_FILE_FORMAT: u8 = 0;
+
+/// The protocol files are written in their original format, which newer firmware migrates on boot.
+const PROTOCOL_VERSION: [u8; 4] = [0x00, 0x00, PROTOCOL_MAJOR_VERSION, PROTOCOL_FILE_FORMAT];
+
+const CONTROLLER_INFO_LENGTH: usize = 13;
+const NODE_INFO_FILE_LENGTH: usize = NODE_INFO_LENGTH + NODE_MASK_LENGTH + 1;
+
+fn page_size(layout: Layout) -> usize {
+    match layout {
+        Layout::Series800 => 0x2000,
+        _ => 0x800,
+    }
+}
+
+/// Returns how much of the image belongs to the application's NVM3 area, which precedes the
+/// protocol's.
+fn application_area_size(layout: Layout) -> usize {
+    match layout {
+        Layout::Series800 => 0x6000,
+        _ => 0x3000,
+    }
+}
+
+fn u16_le_at(buffer: &[u8], offset: usize) -> u16 {
+    buffer[offset] as u16 | (buffer[offset + 1] as u16) << 8
+}
+
+fn u32_le_at(buffer: &[u8], offset: usize) -> u32 {
+    u16_le_at(buffer, offset) as u32 | (u16_le_at(buffer, offset + 2) as u32) << 16
+}
+
+fn put_u16_le(buffer: &mut [u8], offset: usize, value: u16) {
+    buffer[offset] = value as u8;
+    buffer[offset + 1] = (value >> 8) as u8;
+}
+
+fn put_u32_le(buffer: &mut [u8], offset: usize, value: u32) {
+    put_u16_le(buffer, offset, value as u16);
+    put_u16_le(buffer, offset + 2, (value >> 16) as u16);
+}
+
+/// Returns the number of zero bits in the lowest `bits` bits of `value`, which NVM3 stores next
+/// to headers to detect bits that were never programmed.
+fn berger_code(value: u32, bits: u32) -> u32 {
+    let mask = if bits >= 32 { 0xFFFFFFFF } else { (1 << bits) - 1 };
+    bits - (value & mask).count_ones()
+}
+
+fn align(length: usize) -> usize {
+    (length + 3) & !3
+}
+
+pub fn is_formatted(image: &[u8], layout: Layout) -> bool {
+    let page_size = page_size(layout);
+
+    image.len() >= page_size && image.len() % page_size == 0 &&
+        u16_le_at(image, 0) == PAGE_VERSION && u16_le_at(image, 2) == PAGE_MAGIC &&
+        512 << (u16_le_at(image, 16) >> 13) == page_size
+}
+
+fn format_page(page: &mut [u8]) {
+    let page_size_bits = (page.len() / 512).trailing_zeros() as u16;
+    let erase_count = 0u32;
+    let inverted_erase_count = !erase_count & ERASE_COUNT_MASK;
+
+    put_u16_le(page, 0, PAGE_VERSION);
+    put_u16_le(page, 2, PAGE_MAGIC);
+    put_u32_le(page, 4, erase_count | berger_code(erase_count, 27) << 27);
+    put_u32_le(page, 8, inverted_erase_count | berger_code(inverted_erase_count, 27) << 27);
+    put_u16_le(page, 16, DEVICE_FAMILY_UNKNOWN | DEVICE_MEMORY_MAPPED | page_size_bits << 13);
+}
+
+/// Collects the current contents of every object in the image, keyed by file ID.
+fn read_objects(image: &[u8], page_size: usize) -> core::Result<BTreeMap<u32, Vec<u8>>> {
+    let mut objects = BTreeMap::<u32, Vec<u8>>::new();
+    let mut fragments = Vec::<u8>::new();
+
+    for page in image.chunks(page_size) {
+        if page.iter().all(|&byte| byte == 0xFF) {
+            continue;
+        }
+
+        if page.len() < PAGE_HEADER_LENGTH || u16_le_at(page, 0) != PAGE_VERSION || u16_le_at(page, 2) != PAGE_MAGIC {
+            return Err(core::Error::new(core::ErrorKind::Corrupt));
+        }
+
+        let mut offset = PAGE_HEADER_LENGTH;
+
+        while offset + 4 <= page.len() {
+            let header = u32_le_at(page, offset);
+
+            if header == 0xFFFFFFFF {
+                break;
+            }
+
+            let object_type = header & 0x7F;
+            let key = (header >> 7) & 0xFFFFF;
+
+            let (header_length, length, fragment) = if object_type == OBJECT_TYPE_DATA_LARGE || object_type == OBJECT_TYPE_COUNTER_LARGE {
+                if offset + 8 > page.len() {
+                    return Err(core::Error::new(core::ErrorKind::Corrupt));
+                }
+
+                let header2 = u32_le_at(page, offset + 4);
+
+                if berger_code(header, 29) + berger_code(header2, 26) != header2 >> 26 {
+                    return Err(core::Error::new(core::ErrorKind::Corrupt));
+                }
+
+                (8, (header2 & 0x03FFFFFF) as usize, (header >> 27) & 0x03)
+            }
+            else {
+                if berger_code(header, 27) != header >> 27 {
+                    return Err(core::Error::new(core::ErrorKind::Corrupt));
+                }
+
+                let length = match object_type {
+                    OBJECT_TYPE_COUNTER_SMALL => COUNTER_LENGTH,
+                    OBJECT_TYPE_DELETED => 0,
+                    _ if object_type >= OBJECT_TYPE_DATA_SMALL => (object_type - OBJECT_TYPE_DATA_SMALL) as usize,
+                    _ => return Err(core::Error::new(core::ErrorKind::Corrupt)),
+                };
+
+                (4, length, FRAGMENT_NONE)
+            };
+
+            let start = offset + header_length;
+
+            if start + length > page.len() {
+                return Err(core::Error::new(core::ErrorKind::Corrupt));
+            }
+
+            let data = &page[start..start + length];
+
+            match object_type {
+                OBJECT_TYPE_DELETED => {
+                    objects.remove(&key);
+                },
+                OBJECT_TYPE_COUNTER_SMALL | OBJECT_TYPE_COUNTER_LARGE => (),
+                _ => match fragment {
+                    FRAGMENT_NONE => {
+                        objects.insert(key, data.to_vec());
+                    },
+                    FRAGMENT_FIRST => {
+                        fragments = data.to_vec();
+                    },
+                    _ => {
+                        fragments.extend_from_slice(data);
+
+                        if fragment == FRAGMENT_LAST {
+                            objects.insert(key, fragments.split_off(0));
+                        }
+                    },
+                },
+            }
+
+            offset = start + align(length);
+        }
+    }
+
+    Ok(objects)
+}
+
+/// Appends small data objects to the formatted pages of an image.
+struct ObjectWriter<'a> {
+    image: &'a mut [u8],
+    page_size: usize,
+    page: usize,
+    offset: usize,
+}
+
+impl<'a> ObjectWriter<'a> {
+    fn new(image: &'a mut [u8], page_size: usize, first_page: usize) -> Self {
+        ObjectWriter {
+            image: image,
+            page_size: page_size,
+            page: first_page,
+            offset: PAGE_HEADER_LENGTH,
+        }
+    }
+
+    fn write(&mut self, key: u32, data: &[u8]) -> core::Result<()> {
+        if data.len() > MAX_SMALL_DATA_LENGTH {
+            return Err(core::Error::new(core::ErrorKind::Unsupported));
+        }
+
+        let length = 4 + align(data.len());
+
+        if self.offset + length > self.page_size {
+            self.page += 1;
+            self.offset = PAGE_HEADER_LENGTH;
+        }
+
+        // NVM3 always keeps one page free so it can move live objects out of a page it erases.
+        if (self.page + 2) * self.page_size > self.image.len() {
+            return Err(core::Error::new(core::ErrorKind::Unsupported));
+        }
+
+        let header = (OBJECT_TYPE_DATA_SMALL + data.len() as u32) | key << 7;
+        let offset = self.page * self.page_size + self.offset;
+
+        put_u32_le(self.image, offset, header | berger_code(header, 27) << 27);
+        self.image[offset + 4..offset + 4 + data.len()].copy_from_slice(data);
+        self.offset += length;
+
+        Ok(())
+    }
+}
+
+pub fn read(image: &[u8], layout: Layout) -> core::Result<NetworkData> {
+    let objects = try!(read_objects(image, page_size(layout)));
+
+    // Later file formats pack several nodes into each file, which this doesn't read.
+    match objects.get(&FILE_PROTOCOL_VERSION) {
+        Some(data) if data.len() >= PROTOCOL_VERSION.len() => {
+            if data[2] != PROTOCOL_MAJOR_VERSION || data[3] != PROTOCOL_FILE_FORMAT {
+                return Err(core::Error::new(core::ErrorKind::Unsupported));
+            }
+        },
+        _ => return Err(core::Error::new(core::ErrorKind::Corrupt)),
+    }
+
+    let controller_info = match objects.get(&FILE_CONTROLLER_INFO) {
+        Some(data) if data.len() >= CONTROLLER_INFO_LENGTH => data,
+        _ => return Err(core::Error::new(core::ErrorKind::Corrupt)),
+    };
+
+    let node_list = match objects.get(&FILE_NODE_LIST) {
+        Some(data) => deserialize_node_mask(data),
+        None => return Err(core::Error::new(core::ErrorKind::Corrupt)),
+    };
+
+    let home_id = HomeId((controller_info[0] as u32) << 24 | (controller_info[1] as u32) << 16 | (controller_info[2] as u32) << 8 | controller_info[3] as u32);
+    let node_id = NodeId(controller_info[4] as u16);
+
+    let suc_node_id = match controller_info[6] {
+        0 => None,
+        id => Some(NodeId(id as u16)),
+    };
+
+    let mut nodes = Vec::<NodeData>::new();
+
+    for node_id in node_list {
+        let index = node_id.value() as u32 - 1;
+
+        let info = match objects.get(&(FILE_NODE_INFO + index)) {
+            Some(data) if data.len() >= NODE_INFO_LENGTH + NODE_MASK_LENGTH => data,
+            _ => return Err(core::Error::new(core::ErrorKind::Corrupt)),
+        };
+
+        let neighbors = deserialize_node_mask(&info[NODE_INFO_LENGTH..NODE_INFO_LENGTH + NODE_MASK_LENGTH]);
+
+        let route = match objects.get(&(FILE_ROUTE_CACHE + index)) {
+            Some(data) if data.len() >= ROUTE_LENGTH => Route::decode(&data[..ROUTE_LENGTH]),
+            _ => None,
+        };
+
+        nodes.push(NodeData::decode(node_id, info, neighbors, route));
+    }
+
+    Ok(NetworkData::new(home_id, node_id, suc_node_id, nodes))
+}
+
+pub fn write(network: &NetworkData, layout: Layout, size: usize) -> core::Result<Vec<u8>> {
+    let page_size = page_size(layout);
+    let application_area_size = application_area_size(layout);
+
+    if size % page_size != 0 || size <= application_area_size {
+        return Err(core::Error::new(core::ErrorKind::Unsupported));
+    }
+
+    let mut image = vec![0xFFu8; size];
+
+    for page in image.chunks_mut(page_size) {
+        format_page(page);
+    }
+
+    try!(network.validate());
+
+    let node_ids = network.nodes().iter().map(|node| node.node_id()).collect::<Vec<_>>();
+    let routed_node_ids = network.nodes().iter().filter(|node| node.route().is_some()).map(|node| node.node_id()).collect::<Vec<_>>();
+    let home_id = network.home_id().value();
+
+    let controller_info = [
+        (home_id >> 24) as u8,
+        (home_id >> 16) as u8,
+        (home_id >> 8) as u8,
+        home_id as u8,
+        network.node_id().value() as u8,
+        network.max_node_id(),
+        network.suc_node_id().map_or(0, |node_id| node_id.value() as u8),
+        0x00, // SUC last index
+        0x00, // controller configuration
+        0x00, // SUC awareness push needed
+        network.max_node_id(),
+        0x00, // reserved ID
+        0x00, // system state
+    ];
+
+    let mut node_list = Vec::<u8>::new();
+    serialize_node_mask(&node_ids, &mut node_list);
+
+    let mut route_cache_exists = Vec::<u8>::new();
+    serialize_node_mask(&routed_node_ids, &mut route_cache_exists);
+
+    {
+        let mut writer = ObjectWriter::new(&mut image, page_size, application_area_size / page_size);
+
+        try!(writer.write(FILE_PROTOCOL_VERSION, &PROTOCOL_VERSION));
+        try!(writer.write(FILE_CONTROLLER_INFO, &controller_info));
+        try!(writer.write(FILE_NODE_LIST, &node_list));
+        try!(writer.write(FILE_ROUTE_CACHE_EXISTS, &route_cache_exists));
+
+        for node in network.nodes() {
+            let index = node.node_id().value() as u32 - 1;
+
+            let mut info = Vec::<u8>::with_capacity(NODE_INFO_FILE_LENGTH);
+            node.encode(&mut info);
+            serialize_node_mask(node.neighbors(), &mut info);
+            info.push(0x00); // SUC update index
+
+            try!(writer.write(FILE_NODE_INFO + index, &info));
+
+            if node.route().is_some() {
+                // The last working route goes first; the second slot is for the next to last.
+                let mut routes = Vec::<u8>::with_capacity(2 * ROUTE_LENGTH);
+                Route::encode(node.route(), &mut routes);
+                Route::encode(None, &mut routes);
+
+                try!(writer.write(FILE_ROUTE_CACHE + index, &routes));
+            }
+        }
+    }
+
+    Ok(image)
+}
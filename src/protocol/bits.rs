@@ -41,13 +41,492 @@ impl MessageTypeId {
 #[derive(Debug,Hash,PartialEq,Eq)]
 #[repr(u8)]
 pub enum FunctionId {
+    SerialApiGetInitData = 0x02,
+    ApplicationCommandHandler = 0x04,
+    GetControllerCapabilities = 0x05,
+    SerialApiGetCapabilities = 0x07,
+    SoftReset = 0x08,
+    SerialApiStarted = 0x0A,
+    SerialApiSetup = 0x0B,
     SendData = 0x13,
+    SendDataMulti = 0x14,
+    GetVersion = 0x15,
+    SendDataAbort = 0x16,
+    MemoryGetId = 0x20,
+    GetNvmId = 0x29,
+    ExtNvmReadLongBuffer = 0x2A,
+    ExtNvmWriteLongBuffer = 0x2B,
+    NvmBackupRestore = 0x2E,
+    GetNodeProtocolInfo = 0x41,
+    SetDefault = 0x42,
+    AssignReturnRoute = 0x46,
+    DeleteReturnRoute = 0x47,
+    RequestNodeNeighborUpdate = 0x48,
+    ApplicationUpdate = 0x49,
+    AddNodeToNetwork = 0x4A,
+    RemoveNodeFromNetwork = 0x4B,
+    SetLearnMode = 0x50,
+    AssignSucReturnRoute = 0x51,
+    EnableSuc = 0x52,
+    SetSucNodeId = 0x54,
+    DeleteSucReturnRoute = 0x55,
+    GetSucNodeId = 0x56,
+    RemoveFailedNode = 0x61,
+    IsFailedNode = 0x62,
+    ReplaceFailedNode = 0x63,
+    GetRoutingInfo = 0x80,
+    GetLongRangeNodes = 0xDA,
 }
 
 impl FunctionId {
     pub fn from_u8(value: u8) -> Option<FunctionId> {
         match value {
+            0x02 => Some(FunctionId::SerialApiGetInitData),
+            0x04 => Some(FunctionId::ApplicationCommandHandler),
+            0x05 => Some(FunctionId::GetControllerCapabilities),
+            0x07 => Some(FunctionId::SerialApiGetCapabilities),
+            0x08 => Some(FunctionId::SoftReset),
+            0x0A => Some(FunctionId::SerialApiStarted),
+            0x0B => Some(FunctionId::SerialApiSetup),
             0x13 => Some(FunctionId::SendData),
+            0x14 => Some(FunctionId::SendDataMulti),
+            0x15 => Some(FunctionId::GetVersion),
+            0x16 => Some(FunctionId::SendDataAbort),
+            0x20 => Some(FunctionId::MemoryGetId),
+            0x29 => Some(FunctionId::GetNvmId),
+            0x2A => Some(FunctionId::ExtNvmReadLongBuffer),
+            0x2B => Some(FunctionId::ExtNvmWriteLongBuffer),
+            0x2E => Some(FunctionId::NvmBackupRestore),
+            0x41 => Some(FunctionId::GetNodeProtocolInfo),
+            0x42 => Some(FunctionId::SetDefault),
+            0x46 => Some(FunctionId::AssignReturnRoute),
+            0x47 => Some(FunctionId::DeleteReturnRoute),
+            0x48 => Some(FunctionId::RequestNodeNeighborUpdate),
+            0x49 => Some(FunctionId::ApplicationUpdate),
+            0x4A => Some(FunctionId::AddNodeToNetwork),
+            0x4B => Some(FunctionId::RemoveNodeFromNetwork),
+            0x50 => Some(FunctionId::SetLearnMode),
+            0x51 => Some(FunctionId::AssignSucReturnRoute),
+            0x52 => Some(FunctionId::EnableSuc),
+            0x54 => Some(FunctionId::SetSucNodeId),
+            0x55 => Some(FunctionId::DeleteSucReturnRoute),
+            0x56 => Some(FunctionId::GetSucNodeId),
+            0x61 => Some(FunctionId::RemoveFailedNode),
+            0x62 => Some(FunctionId::IsFailedNode),
+            0x63 => Some(FunctionId::ReplaceFailedNode),
+            0x80 => Some(FunctionId::GetRoutingInfo),
+            0xDA => Some(FunctionId::GetLongRangeNodes),
+
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug,Clone,Copy,Hash,PartialEq,Eq)]
+#[repr(u8)]
+pub enum TransmitStatus {
+    Ok = 0x00,
+    NoAck = 0x01,
+    Fail = 0x02,
+    RoutingNotIdle = 0x03,
+    NoRoute = 0x04,
+}
+
+impl TransmitStatus {
+    pub fn from_u8(value: u8) -> Option<TransmitStatus> {
+        match value {
+            0x00 => Some(TransmitStatus::Ok),
+            0x01 => Some(TransmitStatus::NoAck),
+            0x02 => Some(TransmitStatus::Fail),
+            0x03 => Some(TransmitStatus::RoutingNotIdle),
+            0x04 => Some(TransmitStatus::NoRoute),
+
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug,Clone,Copy,Hash,PartialEq,Eq)]
+#[repr(u8)]
+pub enum AddNodeMode {
+    Any = 0x01,
+    Controller = 0x02,
+    Slave = 0x03,
+    Existing = 0x04,
+    Stop = 0x05,
+    StopFailed = 0x06,
+    SmartStartDsk = 0x08,
+    SmartStartListen = 0x09,
+}
+
+impl AddNodeMode {
+    pub fn from_u8(value: u8) -> Option<AddNodeMode> {
+        match value {
+            0x01 => Some(AddNodeMode::Any),
+            0x02 => Some(AddNodeMode::Controller),
+            0x03 => Some(AddNodeMode::Slave),
+            0x04 => Some(AddNodeMode::Existing),
+            0x05 => Some(AddNodeMode::Stop),
+            0x06 => Some(AddNodeMode::StopFailed),
+            0x08 => Some(AddNodeMode::SmartStartDsk),
+            0x09 => Some(AddNodeMode::SmartStartListen),
+
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug,Clone,Copy,Hash,PartialEq,Eq)]
+#[repr(u8)]
+pub enum AddNodeStatus {
+    LearnReady = 0x01,
+    NodeFound = 0x02,
+    AddingSlave = 0x03,
+    AddingController = 0x04,
+    ProtocolDone = 0x05,
+    Done = 0x06,
+    Failed = 0x07,
+}
+
+impl AddNodeStatus {
+    pub fn from_u8(value: u8) -> Option<AddNodeStatus> {
+        match value {
+            0x01 => Some(AddNodeStatus::LearnReady),
+            0x02 => Some(AddNodeStatus::NodeFound),
+            0x03 => Some(AddNodeStatus::AddingSlave),
+            0x04 => Some(AddNodeStatus::AddingController),
+            0x05 => Some(AddNodeStatus::ProtocolDone),
+            0x06 => Some(AddNodeStatus::Done),
+            0x07 => Some(AddNodeStatus::Failed),
+
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug,Clone,Copy,Hash,PartialEq,Eq)]
+#[repr(u8)]
+pub enum RemoveNodeMode {
+    Any = 0x01,
+    Controller = 0x02,
+    Slave = 0x03,
+    Stop = 0x05,
+}
+
+impl RemoveNodeMode {
+    pub fn from_u8(value: u8) -> Option<RemoveNodeMode> {
+        match value {
+            0x01 => Some(RemoveNodeMode::Any),
+            0x02 => Some(RemoveNodeMode::Controller),
+            0x03 => Some(RemoveNodeMode::Slave),
+            0x05 => Some(RemoveNodeMode::Stop),
+
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug,Clone,Copy,Hash,PartialEq,Eq)]
+#[repr(u8)]
+pub enum RemoveNodeStatus {
+    LearnReady = 0x01,
+    NodeFound = 0x02,
+    RemovingSlave = 0x03,
+    RemovingController = 0x04,
+    Done = 0x06,
+    Failed = 0x07,
+}
+
+impl RemoveNodeStatus {
+    pub fn from_u8(value: u8) -> Option<RemoveNodeStatus> {
+        match value {
+            0x01 => Some(RemoveNodeStatus::LearnReady),
+            0x02 => Some(RemoveNodeStatus::NodeFound),
+            0x03 => Some(RemoveNodeStatus::RemovingSlave),
+            0x04 => Some(RemoveNodeStatus::RemovingController),
+            0x06 => Some(RemoveNodeStatus::Done),
+            0x07 => Some(RemoveNodeStatus::Failed),
+
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug,Clone,Copy,Hash,PartialEq,Eq)]
+#[repr(u8)]
+pub enum LearnMode {
+    Disable = 0x00,
+    Classic = 0x01,
+    NetworkWide = 0x02,
+}
+
+impl LearnMode {
+    pub fn from_u8(value: u8) -> Option<LearnMode> {
+        match value {
+            0x00 => Some(LearnMode::Disable),
+            0x01 => Some(LearnMode::Classic),
+            0x02 => Some(LearnMode::NetworkWide),
+
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug,Clone,Copy,Hash,PartialEq,Eq)]
+#[repr(u8)]
+pub enum LearnModeStatus {
+    Started = 0x01,
+    Done = 0x06,
+    Failed = 0x07,
+}
+
+impl LearnModeStatus {
+    pub fn from_u8(value: u8) -> Option<LearnModeStatus> {
+        match value {
+            0x01 => Some(LearnModeStatus::Started),
+            0x06 => Some(LearnModeStatus::Done),
+            0x07 => Some(LearnModeStatus::Failed),
+
+            _ => None,
+        }
+    }
+}
+
+/// Progress reported by callbacks for `RemoveFailedNode` and `ReplaceFailedNode`.
+#[derive(Debug,Clone,Copy,Hash,PartialEq,Eq)]
+#[repr(u8)]
+pub enum FailedNodeStatus {
+    NodeOk = 0x00,
+    Removed = 0x01,
+    NotRemoved = 0x02,
+    Replace = 0x03,
+    ReplaceDone = 0x04,
+    ReplaceFailed = 0x05,
+}
+
+impl FailedNodeStatus {
+    pub fn from_u8(value: u8) -> Option<FailedNodeStatus> {
+        match value {
+            0x00 => Some(FailedNodeStatus::NodeOk),
+            0x01 => Some(FailedNodeStatus::Removed),
+            0x02 => Some(FailedNodeStatus::NotRemoved),
+            0x03 => Some(FailedNodeStatus::Replace),
+            0x04 => Some(FailedNodeStatus::ReplaceDone),
+            0x05 => Some(FailedNodeStatus::ReplaceFailed),
+
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug,Clone,Copy,Hash,PartialEq,Eq)]
+#[repr(u8)]
+pub enum NeighborUpdateStatus {
+    Started = 0x21,
+    Done = 0x22,
+    Failed = 0x23,
+}
+
+impl NeighborUpdateStatus {
+    pub fn from_u8(value: u8) -> Option<NeighborUpdateStatus> {
+        match value {
+            0x21 => Some(NeighborUpdateStatus::Started),
+            0x22 => Some(NeighborUpdateStatus::Done),
+            0x23 => Some(NeighborUpdateStatus::Failed),
+
+            _ => None,
+        }
+    }
+}
+
+/// Outcome reported by the callback for `SetSucNodeId`.
+#[derive(Debug,Clone,Copy,Hash,PartialEq,Eq)]
+#[repr(u8)]
+pub enum SetSucStatus {
+    Succeeded = 0x05,
+    Failed = 0x06,
+}
+
+impl SetSucStatus {
+    pub fn from_u8(value: u8) -> Option<SetSucStatus> {
+        match value {
+            0x05 => Some(SetSucStatus::Succeeded),
+            0x06 => Some(SetSucStatus::Failed),
+
+            _ => None,
+        }
+    }
+}
+
+/// What `NvmBackupRestore` does with the NVM.
+#[derive(Debug,Clone,Copy,Hash,PartialEq,Eq)]
+#[repr(u8)]
+pub enum NvmOperation {
+    Open = 0x00,
+    Read = 0x01,
+    Write = 0x02,
+    Close = 0x03,
+}
+
+impl NvmOperation {
+    pub fn from_u8(value: u8) -> Option<NvmOperation> {
+        match value {
+            0x00 => Some(NvmOperation::Open),
+            0x01 => Some(NvmOperation::Read),
+            0x02 => Some(NvmOperation::Write),
+            0x03 => Some(NvmOperation::Close),
+
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug,Clone,Copy,Hash,PartialEq,Eq)]
+#[repr(u8)]
+pub enum NvmStatus {
+    Ok = 0x00,
+    Error = 0x01,
+    OperationMismatch = 0x02,
+    OperationInterference = 0x03,
+    EndOfFile = 0xFF,
+}
+
+impl NvmStatus {
+    pub fn from_u8(value: u8) -> Option<NvmStatus> {
+        match value {
+            0x00 => Some(NvmStatus::Ok),
+            0x01 => Some(NvmStatus::Error),
+            0x02 => Some(NvmStatus::OperationMismatch),
+            0x03 => Some(NvmStatus::OperationInterference),
+            0xFF => Some(NvmStatus::EndOfFile),
+
+            _ => None,
+        }
+    }
+}
+
+/// The subcommands of `SerialApiSetup`, which configure the controller's Serial API and radio.
+#[derive(Debug,Clone,Copy,Hash,PartialEq,Eq)]
+#[repr(u8)]
+pub enum SerialApiSetupCommand {
+    Unsupported = 0x00,
+    GetSupportedCommands = 0x01,
+    SetLongRangeMaxTxPower = 0x03,
+    SetTxPower = 0x04,
+    GetLongRangeMaxTxPower = 0x05,
+    GetTxPower = 0x08,
+    GetRfRegion = 0x20,
+    SetRfRegion = 0x40,
+    SetNodeIdType = 0x80,
+}
+
+impl SerialApiSetupCommand {
+    pub fn from_u8(value: u8) -> Option<SerialApiSetupCommand> {
+        match value {
+            0x00 => Some(SerialApiSetupCommand::Unsupported),
+            0x01 => Some(SerialApiSetupCommand::GetSupportedCommands),
+            0x03 => Some(SerialApiSetupCommand::SetLongRangeMaxTxPower),
+            0x04 => Some(SerialApiSetupCommand::SetTxPower),
+            0x05 => Some(SerialApiSetupCommand::GetLongRangeMaxTxPower),
+            0x08 => Some(SerialApiSetupCommand::GetTxPower),
+            0x20 => Some(SerialApiSetupCommand::GetRfRegion),
+            0x40 => Some(SerialApiSetupCommand::SetRfRegion),
+            0x80 => Some(SerialApiSetupCommand::SetNodeIdType),
+
+            _ => None,
+        }
+    }
+}
+
+/// The radio regulations that the controller follows, which determine its frequencies.
+#[derive(Debug,Clone,Copy,Hash,PartialEq,Eq)]
+#[repr(u8)]
+pub enum RfRegion {
+    Europe = 0x00,
+    Usa = 0x01,
+    AustraliaNewZealand = 0x02,
+    HongKong = 0x03,
+    India = 0x05,
+    Israel = 0x06,
+    Russia = 0x07,
+    China = 0x08,
+    UsaLongRange = 0x09,
+    UsaLongRangeEndDevice = 0x0A,
+    EuropeLongRange = 0x0B,
+    Japan = 0x20,
+    Korea = 0x21,
+    Unknown = 0xFE,
+    Default = 0xFF,
+}
+
+impl RfRegion {
+    pub fn from_u8(value: u8) -> Option<RfRegion> {
+        match value {
+            0x00 => Some(RfRegion::Europe),
+            0x01 => Some(RfRegion::Usa),
+            0x02 => Some(RfRegion::AustraliaNewZealand),
+            0x03 => Some(RfRegion::HongKong),
+            0x05 => Some(RfRegion::India),
+            0x06 => Some(RfRegion::Israel),
+            0x07 => Some(RfRegion::Russia),
+            0x08 => Some(RfRegion::China),
+            0x09 => Some(RfRegion::UsaLongRange),
+            0x0A => Some(RfRegion::UsaLongRangeEndDevice),
+            0x0B => Some(RfRegion::EuropeLongRange),
+            0x20 => Some(RfRegion::Japan),
+            0x21 => Some(RfRegion::Korea),
+            0xFE => Some(RfRegion::Unknown),
+            0xFF => Some(RfRegion::Default),
+
+            _ => None,
+        }
+    }
+}
+
+/// Whether node IDs take one byte or two in Serial API frames. Two are needed for Long Range
+/// node IDs, which go past 255.
+#[derive(Debug,Clone,Copy,Hash,PartialEq,Eq)]
+#[repr(u8)]
+pub enum NodeIdType {
+    Short = 0x01,
+    Long = 0x02,
+}
+
+impl NodeIdType {
+    pub fn from_u8(value: u8) -> Option<NodeIdType> {
+        match value {
+            0x01 => Some(NodeIdType::Short),
+            0x02 => Some(NodeIdType::Long),
+
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug,Clone,Copy,Hash,PartialEq,Eq)]
+#[repr(u8)]
+pub enum LibraryType {
+    StaticController = 0x01,
+    PortableController = 0x02,
+    EnhancedSlave = 0x03,
+    Slave = 0x04,
+    Installer = 0x05,
+    RoutingSlave = 0x06,
+    BridgeController = 0x07,
+    DeviceUnderTest = 0x08,
+}
+
+impl LibraryType {
+    pub fn from_u8(value: u8) -> Option<LibraryType> {
+        match value {
+            0x01 => Some(LibraryType::StaticController),
+            0x02 => Some(LibraryType::PortableController),
+            0x03 => Some(LibraryType::EnhancedSlave),
+            0x04 => Some(LibraryType::Slave),
+            0x05 => Some(LibraryType::Installer),
+            0x06 => Some(LibraryType::RoutingSlave),
+            0x07 => Some(LibraryType::BridgeController),
+            0x08 => Some(LibraryType::DeviceUnderTest),
 
             _ => None,
         }
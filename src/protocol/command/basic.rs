@@ -46,6 +46,15 @@ impl Report {
     pub fn new(value: u8) -> Self {
         Report { value: value }
     }
+
+    pub fn value(&self) -> u8 {
+        self.value
+    }
+}
+
+impl Command for Report {
+    const COMMAND_CLASS_ID: CommandClassId = COMMAND_CLASS_ID;
+    const COMMAND_ID: CommandId = 0x03;
 }
 
 pub mod serialization {
@@ -102,4 +111,32 @@ pub mod serialization {
             Ok(AnyCommand::new(super::GetValue::new()))
         }
     }
+
+    pub struct ReportSerializer;
+
+    impl Serialize for ReportSerializer {
+        fn type_id(&self) -> TypeId {
+            TypeId::of::<super::Report>()
+        }
+
+        fn key(&self) -> (CommandClassId, CommandId) {
+            (super::Report::COMMAND_CLASS_ID, super::Report::COMMAND_ID)
+        }
+
+        fn serialize(&self, command: &Command, buffer: &mut Vec<u8>) -> core::Result<()> {
+            let report = command.downcast_ref::<super::Report>().unwrap();
+            buffer.push(report.value());
+            Ok(())
+        }
+
+        fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyCommand> {
+            // version 2 reports append target value and duration
+            if buffer.len() >= 1 {
+                Ok(AnyCommand::new(super::Report::new(buffer[0])))
+            }
+            else {
+                Err(core::Error::new(core::ErrorKind::ShortRead))
+            }
+        }
+    }
 }
@@ -65,6 +65,7 @@ impl CommandSerializer {
 
         serializer.register(basic::serialization::SetValueSerializer);
         serializer.register(basic::serialization::GetValueSerializer);
+        serializer.register(basic::serialization::ReportSerializer);
 
         serializer
     }
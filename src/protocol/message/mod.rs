@@ -4,8 +4,11 @@ use std::marker::Reflect;
 use std::mem;
 use std::raw::TraitObject;
 
-use core::NodeId;
-use protocol::bits::{PreambleId, MessageTypeId, FunctionId};
+use core::{NodeId, HomeId};
+use protocol::bits::{PreambleId, MessageTypeId, FunctionId, TransmitStatus, LibraryType, CommandClassId};
+use protocol::bits::{AddNodeMode, AddNodeStatus, RemoveNodeMode, RemoveNodeStatus, LearnMode, LearnModeStatus};
+use protocol::bits::{FailedNodeStatus, NeighborUpdateStatus, SetSucStatus, NvmOperation, NvmStatus};
+use protocol::bits::{SerialApiSetupCommand, RfRegion, NodeIdType};
 use protocol::command::{Command, AnyCommand};
 
 pub use self::serialization::MessageSerializer;
@@ -74,6 +77,29 @@ impl<F: Frame> Message for F {
     const PREAMBLE_ID: PreambleId = PreambleId::Frame;
 }
 
+/// A frame sent by the host that the controller answers with a response frame.
+pub trait Request: Frame {
+    type Response: Frame;
+}
+
+/// A request that the controller reports back on later with a callback frame carrying the request's
+/// callback ID.
+pub trait CallbackRequest: Request {
+    type Callback: Callback;
+
+    fn set_callback_id(&mut self, callback_id: u8);
+
+    /// Returns whether the controller accepted the request, i.e., whether a callback will follow
+    /// the response.
+    fn accepted(_response: &Self::Response) -> bool {
+        true
+    }
+}
+
+pub trait Callback: Frame {
+    fn callback_id(&self) -> u8;
+}
+
 def_any!(AnyMessage: Message);
 
 #[derive(Debug)]
@@ -128,6 +154,11 @@ impl SendData {
         SendData::with_options(destination, command, callback_id, 0x05)
     }
 
+    /// Sends a command to every node. Nodes don't acknowledge broadcasts, so they aren't asked to.
+    pub fn broadcast<C: Command>(command: C, callback_id: u8) -> Self {
+        SendData::with_options(NodeId::BROADCAST, command, callback_id, 0x00)
+    }
+
     pub fn with_options<C: Command>(destination: NodeId, command: C, callback_id: u8, packet_options: u8) -> Self {
         SendData {
             destination: destination,
@@ -159,6 +190,22 @@ impl Frame for SendData {
     const FUNCTION_ID: FunctionId = FunctionId::SendData;
 }
 
+impl Request for SendData {
+    type Response = MessageTransmitted;
+}
+
+impl CallbackRequest for SendData {
+    type Callback = MessageReceived;
+
+    fn set_callback_id(&mut self, callback_id: u8) {
+        self.callback_id = callback_id;
+    }
+
+    fn accepted(response: &MessageTransmitted) -> bool {
+        response.flags() != 0
+    }
+}
+
 
 #[derive(Debug)]
 pub struct MessageTransmitted {
@@ -203,9 +250,2680 @@ impl MessageReceived {
     pub fn flags(&self) -> u8 {
         self.flags
     }
+
+    pub fn transmit_status(&self) -> Option<TransmitStatus> {
+        TransmitStatus::from_u8(self.flags)
+    }
 }
 
 impl Frame for MessageReceived {
     const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
     const FUNCTION_ID: FunctionId = FunctionId::SendData;
 }
+
+impl Callback for MessageReceived {
+    fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+/// Sends a command to several nodes in one multicast frame. The nodes don't acknowledge multicast
+/// frames, so the callback only tells whether the frame went out.
+#[derive(Debug)]
+pub struct SendDataMulti {
+    destinations: Vec<NodeId>,
+    command: AnyCommand,
+    callback_id: u8,
+    packet_options: u8,
+}
+
+impl SendDataMulti {
+    pub fn new<C: Command>(destinations: Vec<NodeId>, command: C, callback_id: u8) -> Self {
+        SendDataMulti::with_options(destinations, command, callback_id, 0x05)
+    }
+
+    pub fn with_options<C: Command>(destinations: Vec<NodeId>, command: C, callback_id: u8, packet_options: u8) -> Self {
+        SendDataMulti {
+            destinations: destinations,
+            command: AnyCommand::new(command),
+            callback_id: callback_id,
+            packet_options: packet_options,
+        }
+    }
+
+    pub fn destinations(&self) -> &[NodeId] {
+        &self.destinations
+    }
+
+    pub fn command(&self) -> &AnyCommand {
+        &self.command
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+
+    pub fn packet_options(&self) -> u8 {
+        self.packet_options
+    }
+}
+
+impl Frame for SendDataMulti {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::SendDataMulti;
+}
+
+impl Request for SendDataMulti {
+    type Response = MulticastTransmitted;
+}
+
+impl CallbackRequest for SendDataMulti {
+    type Callback = MulticastCompleted;
+
+    fn set_callback_id(&mut self, callback_id: u8) {
+        self.callback_id = callback_id;
+    }
+
+    fn accepted(response: &MulticastTransmitted) -> bool {
+        response.flags() != 0
+    }
+}
+
+#[derive(Debug)]
+pub struct MulticastTransmitted {
+    flags: u8,
+}
+
+impl MulticastTransmitted {
+    pub fn new(flags: u8) -> Self {
+        MulticastTransmitted {
+            flags: flags,
+        }
+    }
+
+    pub fn flags(&self) -> u8 {
+        self.flags
+    }
+}
+
+impl Frame for MulticastTransmitted {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::SendDataMulti;
+}
+
+#[derive(Debug)]
+pub struct MulticastCompleted {
+    callback_id: u8,
+    flags: u8,
+}
+
+impl MulticastCompleted {
+    pub fn new(callback_id: u8, flags: u8) -> Self {
+        MulticastCompleted {
+            callback_id: callback_id,
+            flags: flags,
+        }
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+
+    pub fn flags(&self) -> u8 {
+        self.flags
+    }
+
+    pub fn transmit_status(&self) -> Option<TransmitStatus> {
+        TransmitStatus::from_u8(self.flags)
+    }
+}
+
+impl Frame for MulticastCompleted {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::SendDataMulti;
+}
+
+impl Callback for MulticastCompleted {
+    fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+/// Stops the transmission that `SendData` started. The controller still reports the outcome
+/// through the `SendData` callback.
+#[derive(Debug)]
+pub struct SendDataAbort { }
+
+impl SendDataAbort {
+    pub fn new() -> Self {
+        SendDataAbort { }
+    }
+}
+
+impl Frame for SendDataAbort {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::SendDataAbort;
+}
+
+/// A command that a node sent to the controller without being asked.
+#[derive(Debug)]
+pub struct ApplicationCommandHandler {
+    status: u8,
+    source: NodeId,
+    command: AnyCommand,
+}
+
+impl ApplicationCommandHandler {
+    pub fn new<C: Command>(status: u8, source: NodeId, command: C) -> Self {
+        ApplicationCommandHandler {
+            status: status,
+            source: source,
+            command: AnyCommand::new(command),
+        }
+    }
+
+    /// Returns the receive status flags, e.g., whether the frame was broadcast or routed.
+    pub fn status(&self) -> u8 {
+        self.status
+    }
+
+    pub fn source(&self) -> NodeId {
+        self.source
+    }
+
+    pub fn command(&self) -> &AnyCommand {
+        &self.command
+    }
+}
+
+impl Frame for ApplicationCommandHandler {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::ApplicationCommandHandler;
+}
+
+/// The device classes and command classes that a node announces in its node information frame.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct NodeInfo {
+    basic_class: u8,
+    generic_class: u8,
+    specific_class: u8,
+    command_classes: Vec<CommandClassId>,
+    controlled_command_classes: Vec<CommandClassId>,
+}
+
+impl NodeInfo {
+    pub fn new(basic_class: u8, generic_class: u8, specific_class: u8, command_classes: Vec<CommandClassId>, controlled_command_classes: Vec<CommandClassId>) -> Self {
+        NodeInfo {
+            basic_class: basic_class,
+            generic_class: generic_class,
+            specific_class: specific_class,
+            command_classes: command_classes,
+            controlled_command_classes: controlled_command_classes,
+        }
+    }
+
+    pub fn basic_class(&self) -> u8 {
+        self.basic_class
+    }
+
+    pub fn generic_class(&self) -> u8 {
+        self.generic_class
+    }
+
+    pub fn specific_class(&self) -> u8 {
+        self.specific_class
+    }
+
+    /// Returns the command classes that the node supports.
+    pub fn command_classes(&self) -> &[CommandClassId] {
+        &self.command_classes
+    }
+
+    /// Returns the command classes that the node can send to other nodes.
+    pub fn controlled_command_classes(&self) -> &[CommandClassId] {
+        &self.controlled_command_classes
+    }
+}
+
+/// A change in the network that the controller reports without being asked.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub enum ApplicationUpdate {
+    NodeInfoReceived(NodeId, NodeInfo),
+    NodeInfoRequestDone(NodeId),
+    NodeInfoRequestFailed,
+    RoutingPending(NodeId),
+    NewIdAssigned(NodeId),
+    DeleteDone(NodeId),
+    SucIdChanged(NodeId),
+}
+
+impl Frame for ApplicationUpdate {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::ApplicationUpdate;
+}
+
+#[derive(Debug)]
+pub struct GetVersion { }
+
+impl GetVersion {
+    pub fn new() -> Self {
+        GetVersion { }
+    }
+}
+
+impl Frame for GetVersion {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::GetVersion;
+}
+
+impl Request for GetVersion {
+    type Response = Version;
+}
+
+#[derive(Debug)]
+pub struct Version {
+    library_version: String,
+    library_type: u8,
+}
+
+impl Version {
+    pub fn new(library_version: &str, library_type: u8) -> Self {
+        Version {
+            library_version: library_version.to_string(),
+            library_type: library_type,
+        }
+    }
+
+    /// Returns the Z-Wave library version, e.g., `"Z-Wave 4.05"`.
+    pub fn library_version(&self) -> &str {
+        &self.library_version
+    }
+
+    pub fn library_type(&self) -> Option<LibraryType> {
+        LibraryType::from_u8(self.library_type)
+    }
+}
+
+impl Frame for Version {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::GetVersion;
+}
+
+#[derive(Debug)]
+pub struct MemoryGetId { }
+
+impl MemoryGetId {
+    pub fn new() -> Self {
+        MemoryGetId { }
+    }
+}
+
+impl Frame for MemoryGetId {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::MemoryGetId;
+}
+
+impl Request for MemoryGetId {
+    type Response = MemoryId;
+}
+
+#[derive(Debug)]
+pub struct MemoryId {
+    home_id: HomeId,
+    node_id: NodeId,
+}
+
+impl MemoryId {
+    pub fn new(home_id: HomeId, node_id: NodeId) -> Self {
+        MemoryId {
+            home_id: home_id,
+            node_id: node_id,
+        }
+    }
+
+    pub fn home_id(&self) -> HomeId {
+        self.home_id
+    }
+
+    /// Returns the controller's own node ID.
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Frame for MemoryId {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::MemoryGetId;
+}
+
+#[derive(Debug)]
+pub struct SerialApiGetCapabilities { }
+
+impl SerialApiGetCapabilities {
+    pub fn new() -> Self {
+        SerialApiGetCapabilities { }
+    }
+}
+
+impl Frame for SerialApiGetCapabilities {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::SerialApiGetCapabilities;
+}
+
+impl Request for SerialApiGetCapabilities {
+    type Response = SerialApiCapabilities;
+}
+
+#[derive(Debug)]
+pub struct SerialApiCapabilities {
+    application_version: u8,
+    application_revision: u8,
+    manufacturer_id: u16,
+    product_type: u16,
+    product_id: u16,
+    function_mask: [u8; 32],
+}
+
+impl SerialApiCapabilities {
+    pub fn new(application_version: u8, application_revision: u8, manufacturer_id: u16, product_type: u16, product_id: u16, function_mask: [u8; 32]) -> Self {
+        SerialApiCapabilities {
+            application_version: application_version,
+            application_revision: application_revision,
+            manufacturer_id: manufacturer_id,
+            product_type: product_type,
+            product_id: product_id,
+            function_mask: function_mask,
+        }
+    }
+
+    pub fn application_version(&self) -> u8 {
+        self.application_version
+    }
+
+    pub fn application_revision(&self) -> u8 {
+        self.application_revision
+    }
+
+    pub fn manufacturer_id(&self) -> u16 {
+        self.manufacturer_id
+    }
+
+    pub fn product_type(&self) -> u16 {
+        self.product_type
+    }
+
+    pub fn product_id(&self) -> u16 {
+        self.product_id
+    }
+
+    /// Returns the bitmask of supported Serial API functions, where bit `n - 1` is set if function
+    /// `n` is supported.
+    pub fn function_mask(&self) -> &[u8; 32] {
+        &self.function_mask
+    }
+}
+
+impl Frame for SerialApiCapabilities {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::SerialApiGetCapabilities;
+}
+
+#[derive(Debug)]
+pub struct SerialApiGetInitData { }
+
+impl SerialApiGetInitData {
+    pub fn new() -> Self {
+        SerialApiGetInitData { }
+    }
+}
+
+impl Frame for SerialApiGetInitData {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::SerialApiGetInitData;
+}
+
+impl Request for SerialApiGetInitData {
+    type Response = SerialApiInitData;
+}
+
+#[derive(Debug)]
+pub struct SerialApiInitData {
+    api_version: u8,
+    capabilities: u8,
+    node_ids: Vec<NodeId>,
+    chip_type: u8,
+    chip_version: u8,
+}
+
+impl SerialApiInitData {
+    pub fn new(api_version: u8, capabilities: u8, node_ids: Vec<NodeId>, chip_type: u8, chip_version: u8) -> Self {
+        SerialApiInitData {
+            api_version: api_version,
+            capabilities: capabilities,
+            node_ids: node_ids,
+            chip_type: chip_type,
+            chip_version: chip_version,
+        }
+    }
+
+    pub fn api_version(&self) -> u8 {
+        self.api_version
+    }
+
+    /// Returns the Serial API capability flags, e.g., whether the controller is a secondary
+    /// controller or the SUC.
+    pub fn capabilities(&self) -> u8 {
+        self.capabilities
+    }
+
+    /// Returns the IDs of all nodes in the network, in ascending order.
+    pub fn node_ids(&self) -> &[NodeId] {
+        &self.node_ids
+    }
+
+    pub fn chip_type(&self) -> u8 {
+        self.chip_type
+    }
+
+    pub fn chip_version(&self) -> u8 {
+        self.chip_version
+    }
+}
+
+impl Frame for SerialApiInitData {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::SerialApiGetInitData;
+}
+
+/// Asks for one segment of the Long Range node list. Each segment covers 1024 node IDs.
+#[derive(Debug)]
+pub struct GetLongRangeNodes {
+    segment: u8,
+}
+
+impl GetLongRangeNodes {
+    /// The number of segments it takes to cover every Long Range node ID.
+    pub const SEGMENTS: u8 = 4;
+
+    pub fn new(segment: u8) -> Self {
+        GetLongRangeNodes {
+            segment: segment,
+        }
+    }
+
+    pub fn segment(&self) -> u8 {
+        self.segment
+    }
+}
+
+impl Frame for GetLongRangeNodes {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::GetLongRangeNodes;
+}
+
+impl Request for GetLongRangeNodes {
+    type Response = LongRangeNodes;
+}
+
+#[derive(Debug)]
+pub struct LongRangeNodes {
+    more: bool,
+    segment: u8,
+    node_ids: Vec<NodeId>,
+}
+
+impl LongRangeNodes {
+    pub fn new(more: bool, segment: u8, node_ids: Vec<NodeId>) -> Self {
+        LongRangeNodes {
+            more: more,
+            segment: segment,
+            node_ids: node_ids,
+        }
+    }
+
+    /// Returns whether there are segments after this one.
+    pub fn more(&self) -> bool {
+        self.more
+    }
+
+    pub fn segment(&self) -> u8 {
+        self.segment
+    }
+
+    /// Returns the IDs of the Long Range nodes in this segment, in ascending order.
+    pub fn node_ids(&self) -> &[NodeId] {
+        &self.node_ids
+    }
+}
+
+impl Frame for LongRangeNodes {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::GetLongRangeNodes;
+}
+
+#[derive(Debug)]
+pub struct GetNodeProtocolInfo {
+    node_id: NodeId,
+}
+
+impl GetNodeProtocolInfo {
+    pub fn new(node_id: NodeId) -> Self {
+        GetNodeProtocolInfo {
+            node_id: node_id,
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Frame for GetNodeProtocolInfo {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::GetNodeProtocolInfo;
+}
+
+impl Request for GetNodeProtocolInfo {
+    type Response = NodeProtocolInfo;
+}
+
+const CAPABILITY_LISTENING: u8 = 0x80;
+const CAPABILITY_ROUTING: u8 = 0x40;
+const CAPABILITY_BAUD_RATE_MASK: u8 = 0x38;
+const CAPABILITY_BAUD_RATE_40K: u8 = 0x10;
+const SECURITY_SENSOR_1000MS: u8 = 0x40;
+const SECURITY_SENSOR_250MS: u8 = 0x20;
+const SECURITY_CONTROLLER: u8 = 0x02;
+const SECURITY_SECURE: u8 = 0x01;
+const RESERVED_SPEED_100K: u8 = 0x01;
+
+/// What the controller knows about a node from its inclusion: how it can be reached and which
+/// device classes it belongs to.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct NodeProtocolInfo {
+    capability: u8,
+    security: u8,
+    reserved: u8,
+    basic_class: u8,
+    generic_class: u8,
+    specific_class: u8,
+}
+
+impl NodeProtocolInfo {
+    pub fn new(capability: u8, security: u8, reserved: u8, basic_class: u8, generic_class: u8, specific_class: u8) -> Self {
+        NodeProtocolInfo {
+            capability: capability,
+            security: security,
+            reserved: reserved,
+            basic_class: basic_class,
+            generic_class: generic_class,
+            specific_class: specific_class,
+        }
+    }
+
+    pub fn capability(&self) -> u8 {
+        self.capability
+    }
+
+    pub fn security(&self) -> u8 {
+        self.security
+    }
+
+    pub fn reserved(&self) -> u8 {
+        self.reserved
+    }
+
+    /// Returns whether the node keeps its receiver on, i.e., can be reached at any time.
+    pub fn is_listening(&self) -> bool {
+        self.capability & CAPABILITY_LISTENING != 0
+    }
+
+    /// Returns whether the node wakes up periodically to listen for beams (FLiRS).
+    pub fn is_frequently_listening(&self) -> bool {
+        self.security & (SECURITY_SENSOR_1000MS | SECURITY_SENSOR_250MS) != 0
+    }
+
+    /// Returns whether the node can forward frames for other nodes.
+    pub fn is_routing(&self) -> bool {
+        self.capability & CAPABILITY_ROUTING != 0
+    }
+
+    pub fn is_controller(&self) -> bool {
+        self.security & SECURITY_CONTROLLER != 0
+    }
+
+    pub fn supports_security(&self) -> bool {
+        self.security & SECURITY_SECURE != 0
+    }
+
+    /// Returns the highest data rate that the node supports in bits per second.
+    pub fn max_baud_rate(&self) -> u32 {
+        if self.reserved & RESERVED_SPEED_100K != 0 {
+            100_000
+        }
+        else if self.capability & CAPABILITY_BAUD_RATE_MASK == CAPABILITY_BAUD_RATE_40K {
+            40_000
+        }
+        else {
+            9_600
+        }
+    }
+
+    pub fn basic_class(&self) -> u8 {
+        self.basic_class
+    }
+
+    pub fn generic_class(&self) -> u8 {
+        self.generic_class
+    }
+
+    pub fn specific_class(&self) -> u8 {
+        self.specific_class
+    }
+}
+
+impl Frame for NodeProtocolInfo {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::GetNodeProtocolInfo;
+}
+
+/// Puts the controller into or takes it out of inclusion mode. The controller doesn't respond but
+/// reports progress through `AddNodeUpdate` callbacks.
+#[derive(Debug)]
+pub struct AddNodeToNetwork {
+    mode: AddNodeMode,
+    high_power: bool,
+    network_wide: bool,
+    long_range: bool,
+    home_ids: Option<(HomeId, HomeId)>,
+    callback_id: u8,
+}
+
+impl AddNodeToNetwork {
+    pub fn new(mode: AddNodeMode, callback_id: u8) -> Self {
+        AddNodeToNetwork::with_options(mode, callback_id, true, true)
+    }
+
+    pub fn with_options(mode: AddNodeMode, callback_id: u8, high_power: bool, network_wide: bool) -> Self {
+        AddNodeToNetwork {
+            mode: mode,
+            high_power: high_power,
+            network_wide: network_wide,
+            long_range: false,
+            home_ids: None,
+            callback_id: callback_id,
+        }
+    }
+
+    /// Includes the node with the given S2 DSK through SmartStart, which is the only way to include
+    /// a node over Long Range.
+    pub fn smart_start(dsk: &[u8; 16], callback_id: u8, long_range: bool) -> Self {
+        let home_id = |offset: usize| {
+            (dsk[offset] as u32) << 24 | (dsk[offset + 1] as u32) << 16 | (dsk[offset + 2] as u32) << 8 | dsk[offset + 3] as u32
+        };
+
+        // the top two bits tell the home IDs apart from regular ones, the lowest bit from each other
+        let nwi_home_id = (home_id(8) | 0xC0000000) & !0x00000001;
+        let auth_home_id = (home_id(12) & 0x3FFFFFFF) | 0x00000001;
+
+        AddNodeToNetwork::with_home_ids(callback_id, long_range, HomeId(nwi_home_id), HomeId(auth_home_id))
+    }
+
+    pub fn with_home_ids(callback_id: u8, long_range: bool, nwi_home_id: HomeId, auth_home_id: HomeId) -> Self {
+        AddNodeToNetwork {
+            mode: AddNodeMode::SmartStartDsk,
+            high_power: true,
+            network_wide: true,
+            long_range: long_range,
+            home_ids: Some((nwi_home_id, auth_home_id)),
+            callback_id: callback_id,
+        }
+    }
+
+    pub fn mode(&self) -> AddNodeMode {
+        self.mode
+    }
+
+    /// Returns whether the controller transmits at full power rather than only reaching nodes
+    /// nearby.
+    pub fn high_power(&self) -> bool {
+        self.high_power
+    }
+
+    /// Returns whether the controller accepts nodes that are only reachable through other nodes.
+    pub fn network_wide(&self) -> bool {
+        self.network_wide
+    }
+
+    /// Returns whether the node is included over Long Range rather than into the mesh.
+    pub fn long_range(&self) -> bool {
+        self.long_range
+    }
+
+    /// Returns the home ID that the node listens on before it's included, for SmartStart.
+    pub fn nwi_home_id(&self) -> Option<HomeId> {
+        self.home_ids.map(|(nwi_home_id, _)| nwi_home_id)
+    }
+
+    /// Returns the home ID that the node uses to authenticate itself, for SmartStart.
+    pub fn auth_home_id(&self) -> Option<HomeId> {
+        self.home_ids.map(|(_, auth_home_id)| auth_home_id)
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+impl Frame for AddNodeToNetwork {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::AddNodeToNetwork;
+}
+
+#[derive(Debug)]
+pub struct AddNodeUpdate {
+    callback_id: u8,
+    status: u8,
+    node_id: NodeId,
+    node_info: Option<NodeInfo>,
+}
+
+impl AddNodeUpdate {
+    pub fn new(callback_id: u8, status: AddNodeStatus, node_id: NodeId, node_info: Option<NodeInfo>) -> Self {
+        AddNodeUpdate::with_status(callback_id, status as u8, node_id, node_info)
+    }
+
+    pub fn with_status(callback_id: u8, status: u8, node_id: NodeId, node_info: Option<NodeInfo>) -> Self {
+        AddNodeUpdate {
+            callback_id: callback_id,
+            status: status,
+            node_id: node_id,
+            node_info: node_info,
+        }
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+
+    pub fn status(&self) -> Option<AddNodeStatus> {
+        AddNodeStatus::from_u8(self.status)
+    }
+
+    pub fn raw_status(&self) -> u8 {
+        self.status
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    /// Returns the node information frame of the node being added, which is only reported along
+    /// with the `AddingSlave` and `AddingController` states.
+    pub fn node_info(&self) -> Option<&NodeInfo> {
+        self.node_info.as_ref()
+    }
+}
+
+impl Frame for AddNodeUpdate {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::AddNodeToNetwork;
+}
+
+impl Callback for AddNodeUpdate {
+    fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+/// Puts the controller into or takes it out of exclusion mode. The controller doesn't respond but
+/// reports progress through `RemoveNodeUpdate` callbacks.
+#[derive(Debug)]
+pub struct RemoveNodeFromNetwork {
+    mode: RemoveNodeMode,
+    high_power: bool,
+    network_wide: bool,
+    callback_id: u8,
+}
+
+impl RemoveNodeFromNetwork {
+    pub fn new(mode: RemoveNodeMode, callback_id: u8) -> Self {
+        RemoveNodeFromNetwork::with_options(mode, callback_id, true, true)
+    }
+
+    pub fn with_options(mode: RemoveNodeMode, callback_id: u8, high_power: bool, network_wide: bool) -> Self {
+        RemoveNodeFromNetwork {
+            mode: mode,
+            high_power: high_power,
+            network_wide: network_wide,
+            callback_id: callback_id,
+        }
+    }
+
+    pub fn mode(&self) -> RemoveNodeMode {
+        self.mode
+    }
+
+    pub fn high_power(&self) -> bool {
+        self.high_power
+    }
+
+    pub fn network_wide(&self) -> bool {
+        self.network_wide
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+impl Frame for RemoveNodeFromNetwork {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::RemoveNodeFromNetwork;
+}
+
+#[derive(Debug)]
+pub struct RemoveNodeUpdate {
+    callback_id: u8,
+    status: u8,
+    node_id: NodeId,
+}
+
+impl RemoveNodeUpdate {
+    pub fn new(callback_id: u8, status: RemoveNodeStatus, node_id: NodeId) -> Self {
+        RemoveNodeUpdate::with_status(callback_id, status as u8, node_id)
+    }
+
+    pub fn with_status(callback_id: u8, status: u8, node_id: NodeId) -> Self {
+        RemoveNodeUpdate {
+            callback_id: callback_id,
+            status: status,
+            node_id: node_id,
+        }
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+
+    pub fn status(&self) -> Option<RemoveNodeStatus> {
+        RemoveNodeStatus::from_u8(self.status)
+    }
+
+    pub fn raw_status(&self) -> u8 {
+        self.status
+    }
+
+    /// Returns the ID of the node being removed, which is only reported along with the
+    /// `RemovingSlave` and `RemovingController` states.
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Frame for RemoveNodeUpdate {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::RemoveNodeFromNetwork;
+}
+
+impl Callback for RemoveNodeUpdate {
+    fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+/// Lets the controller join another controller's network or leave it again. Progress is reported
+/// through `LearnModeUpdate` callbacks.
+#[derive(Debug)]
+pub struct SetLearnMode {
+    mode: LearnMode,
+    callback_id: u8,
+}
+
+impl SetLearnMode {
+    pub fn new(mode: LearnMode, callback_id: u8) -> Self {
+        SetLearnMode {
+            mode: mode,
+            callback_id: callback_id,
+        }
+    }
+
+    pub fn mode(&self) -> LearnMode {
+        self.mode
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+impl Frame for SetLearnMode {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::SetLearnMode;
+}
+
+impl Request for SetLearnMode {
+    type Response = LearnModeResult;
+}
+
+#[derive(Debug)]
+pub struct LearnModeResult {
+    result: u8,
+}
+
+impl LearnModeResult {
+    pub fn new(result: u8) -> Self {
+        LearnModeResult {
+            result: result,
+        }
+    }
+
+    pub fn result(&self) -> u8 {
+        self.result
+    }
+
+    pub fn accepted(&self) -> bool {
+        self.result != 0
+    }
+}
+
+impl Frame for LearnModeResult {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::SetLearnMode;
+}
+
+#[derive(Debug)]
+pub struct LearnModeUpdate {
+    callback_id: u8,
+    status: u8,
+    node_id: NodeId,
+}
+
+impl LearnModeUpdate {
+    pub fn new(callback_id: u8, status: LearnModeStatus, node_id: NodeId) -> Self {
+        LearnModeUpdate::with_status(callback_id, status as u8, node_id)
+    }
+
+    pub fn with_status(callback_id: u8, status: u8, node_id: NodeId) -> Self {
+        LearnModeUpdate {
+            callback_id: callback_id,
+            status: status,
+            node_id: node_id,
+        }
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+
+    pub fn status(&self) -> Option<LearnModeStatus> {
+        LearnModeStatus::from_u8(self.status)
+    }
+
+    pub fn raw_status(&self) -> u8 {
+        self.status
+    }
+
+    /// Returns the node ID that the controller was assigned in the new network.
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Frame for LearnModeUpdate {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::SetLearnMode;
+}
+
+impl Callback for LearnModeUpdate {
+    fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+/// Resets the controller to its factory defaults, which erases the network and assigns a new home
+/// ID.
+#[derive(Debug)]
+pub struct SetDefault {
+    callback_id: u8,
+}
+
+impl SetDefault {
+    pub fn new(callback_id: u8) -> Self {
+        SetDefault {
+            callback_id: callback_id,
+        }
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+impl Frame for SetDefault {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::SetDefault;
+}
+
+#[derive(Debug)]
+pub struct DefaultSet {
+    callback_id: u8,
+}
+
+impl DefaultSet {
+    pub fn new(callback_id: u8) -> Self {
+        DefaultSet {
+            callback_id: callback_id,
+        }
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+impl Frame for DefaultSet {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::SetDefault;
+}
+
+impl Callback for DefaultSet {
+    fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+/// Restarts the controller without changing its network.
+#[derive(Debug)]
+pub struct SoftReset { }
+
+impl SoftReset {
+    pub fn new() -> Self {
+        SoftReset { }
+    }
+}
+
+impl Frame for SoftReset {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::SoftReset;
+}
+
+/// Sent by the controller (700 series and later) when it has started, whether it was asked to
+/// restart or not.
+#[derive(Debug,Clone,PartialEq)]
+pub struct SerialApiStarted {
+    wake_up_reason: u8,
+    watchdog_started: bool,
+    device_options: u8,
+    generic_class: u8,
+    specific_class: u8,
+    command_classes: Vec<CommandClassId>,
+    supports_long_range: bool,
+}
+
+impl SerialApiStarted {
+    pub fn new(wake_up_reason: u8, watchdog_started: bool, device_options: u8, generic_class: u8, specific_class: u8, command_classes: Vec<CommandClassId>, supports_long_range: bool) -> Self {
+        SerialApiStarted {
+            wake_up_reason: wake_up_reason,
+            watchdog_started: watchdog_started,
+            device_options: device_options,
+            generic_class: generic_class,
+            specific_class: specific_class,
+            command_classes: command_classes,
+            supports_long_range: supports_long_range,
+        }
+    }
+
+    /// Returns why the controller started, e.g., 0x00 for a reset or 0x07 for a soft reset.
+    pub fn wake_up_reason(&self) -> u8 {
+        self.wake_up_reason
+    }
+
+    pub fn watchdog_started(&self) -> bool {
+        self.watchdog_started
+    }
+
+    pub fn device_options(&self) -> u8 {
+        self.device_options
+    }
+
+    pub fn generic_class(&self) -> u8 {
+        self.generic_class
+    }
+
+    pub fn specific_class(&self) -> u8 {
+        self.specific_class
+    }
+
+    pub fn command_classes(&self) -> &[CommandClassId] {
+        &self.command_classes
+    }
+
+    pub fn supports_long_range(&self) -> bool {
+        self.supports_long_range
+    }
+}
+
+impl Frame for SerialApiStarted {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::SerialApiStarted;
+}
+
+/// Asks whether the controller considers a node to have failed, i.e., it stopped responding.
+#[derive(Debug)]
+pub struct IsFailedNode {
+    node_id: NodeId,
+}
+
+impl IsFailedNode {
+    pub fn new(node_id: NodeId) -> Self {
+        IsFailedNode {
+            node_id: node_id,
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Frame for IsFailedNode {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::IsFailedNode;
+}
+
+impl Request for IsFailedNode {
+    type Response = IsFailedNodeResult;
+}
+
+#[derive(Debug)]
+pub struct IsFailedNodeResult {
+    failed: bool,
+}
+
+impl IsFailedNodeResult {
+    pub fn new(failed: bool) -> Self {
+        IsFailedNodeResult {
+            failed: failed,
+        }
+    }
+
+    pub fn failed(&self) -> bool {
+        self.failed
+    }
+}
+
+impl Frame for IsFailedNodeResult {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::IsFailedNode;
+}
+
+/// Removes a node that the controller considers failed from the network.
+#[derive(Debug)]
+pub struct RemoveFailedNode {
+    node_id: NodeId,
+    callback_id: u8,
+}
+
+impl RemoveFailedNode {
+    pub fn new(node_id: NodeId, callback_id: u8) -> Self {
+        RemoveFailedNode {
+            node_id: node_id,
+            callback_id: callback_id,
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+impl Frame for RemoveFailedNode {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::RemoveFailedNode;
+}
+
+impl Request for RemoveFailedNode {
+    type Response = RemoveFailedNodeResult;
+}
+
+impl CallbackRequest for RemoveFailedNode {
+    type Callback = RemoveFailedNodeUpdate;
+
+    fn set_callback_id(&mut self, callback_id: u8) {
+        self.callback_id = callback_id;
+    }
+
+    fn accepted(response: &RemoveFailedNodeResult) -> bool {
+        response.started()
+    }
+}
+
+#[derive(Debug)]
+pub struct RemoveFailedNodeResult {
+    flags: u8,
+}
+
+impl RemoveFailedNodeResult {
+    pub fn new(flags: u8) -> Self {
+        RemoveFailedNodeResult {
+            flags: flags,
+        }
+    }
+
+    /// Returns why the controller refused to start, e.g., because it isn't the primary
+    /// controller or the node isn't on its list of failed nodes.
+    pub fn flags(&self) -> u8 {
+        self.flags
+    }
+
+    pub fn started(&self) -> bool {
+        self.flags == 0
+    }
+}
+
+impl Frame for RemoveFailedNodeResult {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::RemoveFailedNode;
+}
+
+#[derive(Debug)]
+pub struct RemoveFailedNodeUpdate {
+    callback_id: u8,
+    status: u8,
+}
+
+impl RemoveFailedNodeUpdate {
+    pub fn new(callback_id: u8, status: FailedNodeStatus) -> Self {
+        RemoveFailedNodeUpdate::with_status(callback_id, status as u8)
+    }
+
+    pub fn with_status(callback_id: u8, status: u8) -> Self {
+        RemoveFailedNodeUpdate {
+            callback_id: callback_id,
+            status: status,
+        }
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+
+    pub fn status(&self) -> Option<FailedNodeStatus> {
+        FailedNodeStatus::from_u8(self.status)
+    }
+
+    pub fn raw_status(&self) -> u8 {
+        self.status
+    }
+}
+
+impl Frame for RemoveFailedNodeUpdate {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::RemoveFailedNode;
+}
+
+impl Callback for RemoveFailedNodeUpdate {
+    fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+/// Gives the ID of a node that the controller considers failed to the next node that's included.
+#[derive(Debug)]
+pub struct ReplaceFailedNode {
+    node_id: NodeId,
+    callback_id: u8,
+}
+
+impl ReplaceFailedNode {
+    pub fn new(node_id: NodeId, callback_id: u8) -> Self {
+        ReplaceFailedNode {
+            node_id: node_id,
+            callback_id: callback_id,
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+impl Frame for ReplaceFailedNode {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::ReplaceFailedNode;
+}
+
+impl Request for ReplaceFailedNode {
+    type Response = ReplaceFailedNodeResult;
+}
+
+#[derive(Debug)]
+pub struct ReplaceFailedNodeResult {
+    flags: u8,
+}
+
+impl ReplaceFailedNodeResult {
+    pub fn new(flags: u8) -> Self {
+        ReplaceFailedNodeResult {
+            flags: flags,
+        }
+    }
+
+    /// Returns why the controller refused to start, e.g., because it isn't the primary
+    /// controller or the node isn't on its list of failed nodes.
+    pub fn flags(&self) -> u8 {
+        self.flags
+    }
+
+    pub fn started(&self) -> bool {
+        self.flags == 0
+    }
+}
+
+impl Frame for ReplaceFailedNodeResult {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::ReplaceFailedNode;
+}
+
+#[derive(Debug)]
+pub struct ReplaceFailedNodeUpdate {
+    callback_id: u8,
+    status: u8,
+}
+
+impl ReplaceFailedNodeUpdate {
+    pub fn new(callback_id: u8, status: FailedNodeStatus) -> Self {
+        ReplaceFailedNodeUpdate::with_status(callback_id, status as u8)
+    }
+
+    pub fn with_status(callback_id: u8, status: u8) -> Self {
+        ReplaceFailedNodeUpdate {
+            callback_id: callback_id,
+            status: status,
+        }
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+
+    pub fn status(&self) -> Option<FailedNodeStatus> {
+        FailedNodeStatus::from_u8(self.status)
+    }
+
+    pub fn raw_status(&self) -> u8 {
+        self.status
+    }
+}
+
+impl Frame for ReplaceFailedNodeUpdate {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::ReplaceFailedNode;
+}
+
+impl Callback for ReplaceFailedNodeUpdate {
+    fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+/// Asks the controller which nodes a node can reach directly.
+#[derive(Debug)]
+pub struct GetRoutingInfo {
+    node_id: NodeId,
+    remove_bad: bool,
+    remove_non_repeaters: bool,
+}
+
+impl GetRoutingInfo {
+    pub fn new(node_id: NodeId) -> Self {
+        GetRoutingInfo::with_options(node_id, false, false)
+    }
+
+    pub fn with_options(node_id: NodeId, remove_bad: bool, remove_non_repeaters: bool) -> Self {
+        GetRoutingInfo {
+            node_id: node_id,
+            remove_bad: remove_bad,
+            remove_non_repeaters: remove_non_repeaters,
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    /// Returns whether neighbors that the controller knows to be unreachable are left out.
+    pub fn remove_bad(&self) -> bool {
+        self.remove_bad
+    }
+
+    /// Returns whether neighbors that can't forward frames are left out.
+    pub fn remove_non_repeaters(&self) -> bool {
+        self.remove_non_repeaters
+    }
+}
+
+impl Frame for GetRoutingInfo {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::GetRoutingInfo;
+}
+
+impl Request for GetRoutingInfo {
+    type Response = RoutingInfo;
+}
+
+#[derive(Debug)]
+pub struct RoutingInfo {
+    neighbors: Vec<NodeId>,
+}
+
+impl RoutingInfo {
+    pub fn new(neighbors: Vec<NodeId>) -> Self {
+        RoutingInfo {
+            neighbors: neighbors,
+        }
+    }
+
+    /// Returns the IDs of the node's neighbors in ascending order.
+    pub fn neighbors(&self) -> &[NodeId] {
+        &self.neighbors
+    }
+}
+
+impl Frame for RoutingInfo {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::GetRoutingInfo;
+}
+
+/// Asks a node to find its neighbors and report them to the controller.
+#[derive(Debug)]
+pub struct RequestNodeNeighborUpdate {
+    node_id: NodeId,
+    callback_id: u8,
+}
+
+impl RequestNodeNeighborUpdate {
+    pub fn new(node_id: NodeId, callback_id: u8) -> Self {
+        RequestNodeNeighborUpdate {
+            node_id: node_id,
+            callback_id: callback_id,
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+impl Frame for RequestNodeNeighborUpdate {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::RequestNodeNeighborUpdate;
+}
+
+#[derive(Debug)]
+pub struct NeighborUpdate {
+    callback_id: u8,
+    status: u8,
+}
+
+impl NeighborUpdate {
+    pub fn new(callback_id: u8, status: NeighborUpdateStatus) -> Self {
+        NeighborUpdate::with_status(callback_id, status as u8)
+    }
+
+    pub fn with_status(callback_id: u8, status: u8) -> Self {
+        NeighborUpdate {
+            callback_id: callback_id,
+            status: status,
+        }
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+
+    pub fn status(&self) -> Option<NeighborUpdateStatus> {
+        NeighborUpdateStatus::from_u8(self.status)
+    }
+
+    pub fn raw_status(&self) -> u8 {
+        self.status
+    }
+}
+
+impl Frame for NeighborUpdate {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::RequestNodeNeighborUpdate;
+}
+
+impl Callback for NeighborUpdate {
+    fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+/// Tells a node which route to use for reaching the destination node.
+#[derive(Debug)]
+pub struct AssignReturnRoute {
+    node_id: NodeId,
+    destination: NodeId,
+    callback_id: u8,
+}
+
+impl AssignReturnRoute {
+    pub fn new(node_id: NodeId, destination: NodeId, callback_id: u8) -> Self {
+        AssignReturnRoute {
+            node_id: node_id,
+            destination: destination,
+            callback_id: callback_id,
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn destination(&self) -> NodeId {
+        self.destination
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+impl Frame for AssignReturnRoute {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::AssignReturnRoute;
+}
+
+impl Request for AssignReturnRoute {
+    type Response = AssignReturnRouteResult;
+}
+
+impl CallbackRequest for AssignReturnRoute {
+    type Callback = ReturnRouteAssigned;
+
+    fn set_callback_id(&mut self, callback_id: u8) {
+        self.callback_id = callback_id;
+    }
+
+    fn accepted(response: &AssignReturnRouteResult) -> bool {
+        response.accepted()
+    }
+}
+
+#[derive(Debug)]
+pub struct AssignReturnRouteResult {
+    result: u8,
+}
+
+impl AssignReturnRouteResult {
+    pub fn new(result: u8) -> Self {
+        AssignReturnRouteResult {
+            result: result,
+        }
+    }
+
+    pub fn result(&self) -> u8 {
+        self.result
+    }
+
+    pub fn accepted(&self) -> bool {
+        self.result != 0
+    }
+}
+
+impl Frame for AssignReturnRouteResult {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::AssignReturnRoute;
+}
+
+#[derive(Debug)]
+pub struct ReturnRouteAssigned {
+    callback_id: u8,
+    status: u8,
+}
+
+impl ReturnRouteAssigned {
+    pub fn new(callback_id: u8, status: u8) -> Self {
+        ReturnRouteAssigned {
+            callback_id: callback_id,
+            status: status,
+        }
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+
+    pub fn status(&self) -> u8 {
+        self.status
+    }
+
+    pub fn transmit_status(&self) -> Option<TransmitStatus> {
+        TransmitStatus::from_u8(self.status)
+    }
+}
+
+impl Frame for ReturnRouteAssigned {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::AssignReturnRoute;
+}
+
+impl Callback for ReturnRouteAssigned {
+    fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+/// Makes a node forget all of its return routes.
+#[derive(Debug)]
+pub struct DeleteReturnRoute {
+    node_id: NodeId,
+    callback_id: u8,
+}
+
+impl DeleteReturnRoute {
+    pub fn new(node_id: NodeId, callback_id: u8) -> Self {
+        DeleteReturnRoute {
+            node_id: node_id,
+            callback_id: callback_id,
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+impl Frame for DeleteReturnRoute {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::DeleteReturnRoute;
+}
+
+impl Request for DeleteReturnRoute {
+    type Response = DeleteReturnRouteResult;
+}
+
+impl CallbackRequest for DeleteReturnRoute {
+    type Callback = ReturnRouteDeleted;
+
+    fn set_callback_id(&mut self, callback_id: u8) {
+        self.callback_id = callback_id;
+    }
+
+    fn accepted(response: &DeleteReturnRouteResult) -> bool {
+        response.accepted()
+    }
+}
+
+#[derive(Debug)]
+pub struct DeleteReturnRouteResult {
+    result: u8,
+}
+
+impl DeleteReturnRouteResult {
+    pub fn new(result: u8) -> Self {
+        DeleteReturnRouteResult {
+            result: result,
+        }
+    }
+
+    pub fn result(&self) -> u8 {
+        self.result
+    }
+
+    pub fn accepted(&self) -> bool {
+        self.result != 0
+    }
+}
+
+impl Frame for DeleteReturnRouteResult {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::DeleteReturnRoute;
+}
+
+#[derive(Debug)]
+pub struct ReturnRouteDeleted {
+    callback_id: u8,
+    status: u8,
+}
+
+impl ReturnRouteDeleted {
+    pub fn new(callback_id: u8, status: u8) -> Self {
+        ReturnRouteDeleted {
+            callback_id: callback_id,
+            status: status,
+        }
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+
+    pub fn status(&self) -> u8 {
+        self.status
+    }
+
+    pub fn transmit_status(&self) -> Option<TransmitStatus> {
+        TransmitStatus::from_u8(self.status)
+    }
+}
+
+impl Frame for ReturnRouteDeleted {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::DeleteReturnRoute;
+}
+
+impl Callback for ReturnRouteDeleted {
+    fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+/// Tells a node which route to use for reaching the SUC.
+#[derive(Debug)]
+pub struct AssignSucReturnRoute {
+    node_id: NodeId,
+    callback_id: u8,
+}
+
+impl AssignSucReturnRoute {
+    pub fn new(node_id: NodeId, callback_id: u8) -> Self {
+        AssignSucReturnRoute {
+            node_id: node_id,
+            callback_id: callback_id,
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+impl Frame for AssignSucReturnRoute {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::AssignSucReturnRoute;
+}
+
+impl Request for AssignSucReturnRoute {
+    type Response = AssignSucReturnRouteResult;
+}
+
+impl CallbackRequest for AssignSucReturnRoute {
+    type Callback = SucReturnRouteAssigned;
+
+    fn set_callback_id(&mut self, callback_id: u8) {
+        self.callback_id = callback_id;
+    }
+
+    fn accepted(response: &AssignSucReturnRouteResult) -> bool {
+        response.accepted()
+    }
+}
+
+#[derive(Debug)]
+pub struct AssignSucReturnRouteResult {
+    result: u8,
+}
+
+impl AssignSucReturnRouteResult {
+    pub fn new(result: u8) -> Self {
+        AssignSucReturnRouteResult {
+            result: result,
+        }
+    }
+
+    pub fn result(&self) -> u8 {
+        self.result
+    }
+
+    pub fn accepted(&self) -> bool {
+        self.result != 0
+    }
+}
+
+impl Frame for AssignSucReturnRouteResult {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::AssignSucReturnRoute;
+}
+
+#[derive(Debug)]
+pub struct SucReturnRouteAssigned {
+    callback_id: u8,
+    status: u8,
+}
+
+impl SucReturnRouteAssigned {
+    pub fn new(callback_id: u8, status: u8) -> Self {
+        SucReturnRouteAssigned {
+            callback_id: callback_id,
+            status: status,
+        }
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+
+    pub fn status(&self) -> u8 {
+        self.status
+    }
+
+    pub fn transmit_status(&self) -> Option<TransmitStatus> {
+        TransmitStatus::from_u8(self.status)
+    }
+}
+
+impl Frame for SucReturnRouteAssigned {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::AssignSucReturnRoute;
+}
+
+impl Callback for SucReturnRouteAssigned {
+    fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+/// Makes a node forget its return route to the SUC.
+#[derive(Debug)]
+pub struct DeleteSucReturnRoute {
+    node_id: NodeId,
+    callback_id: u8,
+}
+
+impl DeleteSucReturnRoute {
+    pub fn new(node_id: NodeId, callback_id: u8) -> Self {
+        DeleteSucReturnRoute {
+            node_id: node_id,
+            callback_id: callback_id,
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+impl Frame for DeleteSucReturnRoute {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::DeleteSucReturnRoute;
+}
+
+impl Request for DeleteSucReturnRoute {
+    type Response = DeleteSucReturnRouteResult;
+}
+
+impl CallbackRequest for DeleteSucReturnRoute {
+    type Callback = SucReturnRouteDeleted;
+
+    fn set_callback_id(&mut self, callback_id: u8) {
+        self.callback_id = callback_id;
+    }
+
+    fn accepted(response: &DeleteSucReturnRouteResult) -> bool {
+        response.accepted()
+    }
+}
+
+#[derive(Debug)]
+pub struct DeleteSucReturnRouteResult {
+    result: u8,
+}
+
+impl DeleteSucReturnRouteResult {
+    pub fn new(result: u8) -> Self {
+        DeleteSucReturnRouteResult {
+            result: result,
+        }
+    }
+
+    pub fn result(&self) -> u8 {
+        self.result
+    }
+
+    pub fn accepted(&self) -> bool {
+        self.result != 0
+    }
+}
+
+impl Frame for DeleteSucReturnRouteResult {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::DeleteSucReturnRoute;
+}
+
+#[derive(Debug)]
+pub struct SucReturnRouteDeleted {
+    callback_id: u8,
+    status: u8,
+}
+
+impl SucReturnRouteDeleted {
+    pub fn new(callback_id: u8, status: u8) -> Self {
+        SucReturnRouteDeleted {
+            callback_id: callback_id,
+            status: status,
+        }
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+
+    pub fn status(&self) -> u8 {
+        self.status
+    }
+
+    pub fn transmit_status(&self) -> Option<TransmitStatus> {
+        TransmitStatus::from_u8(self.status)
+    }
+}
+
+impl Frame for SucReturnRouteDeleted {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::DeleteSucReturnRoute;
+}
+
+impl Callback for SucReturnRouteDeleted {
+    fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+/// Asks the controller about its role in the network.
+#[derive(Debug)]
+pub struct GetControllerCapabilities { }
+
+impl GetControllerCapabilities {
+    pub fn new() -> Self {
+        GetControllerCapabilities { }
+    }
+}
+
+impl Frame for GetControllerCapabilities {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::GetControllerCapabilities;
+}
+
+impl Request for GetControllerCapabilities {
+    type Response = ControllerCapabilities;
+}
+
+const CONTROLLER_IS_SECONDARY: u8 = 0x01;
+const CONTROLLER_ON_OTHER_NETWORK: u8 = 0x02;
+const CONTROLLER_SIS_PRESENT: u8 = 0x04;
+const CONTROLLER_IS_REAL_PRIMARY: u8 = 0x08;
+const CONTROLLER_IS_SUC: u8 = 0x10;
+
+#[derive(Debug)]
+pub struct ControllerCapabilities {
+    capabilities: u8,
+}
+
+impl ControllerCapabilities {
+    pub fn new(capabilities: u8) -> Self {
+        ControllerCapabilities {
+            capabilities: capabilities,
+        }
+    }
+
+    /// Returns the raw capability flags.
+    pub fn capabilities(&self) -> u8 {
+        self.capabilities
+    }
+
+    pub fn is_secondary(&self) -> bool {
+        self.capabilities & CONTROLLER_IS_SECONDARY != 0
+    }
+
+    /// Returns whether the controller was included into another network than the one it started.
+    pub fn is_on_other_network(&self) -> bool {
+        self.capabilities & CONTROLLER_ON_OTHER_NETWORK != 0
+    }
+
+    /// Returns whether the network has a SUC with the node ID server (SIS) enabled.
+    pub fn sis_present(&self) -> bool {
+        self.capabilities & CONTROLLER_SIS_PRESENT != 0
+    }
+
+    pub fn is_real_primary(&self) -> bool {
+        self.capabilities & CONTROLLER_IS_REAL_PRIMARY != 0
+    }
+
+    pub fn is_suc(&self) -> bool {
+        self.capabilities & CONTROLLER_IS_SUC != 0
+    }
+}
+
+impl Frame for ControllerCapabilities {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::GetControllerCapabilities;
+}
+
+/// Asks the controller which node is the network's Static Update Controller (SUC).
+#[derive(Debug)]
+pub struct GetSucNodeId { }
+
+impl GetSucNodeId {
+    pub fn new() -> Self {
+        GetSucNodeId { }
+    }
+}
+
+impl Frame for GetSucNodeId {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::GetSucNodeId;
+}
+
+impl Request for GetSucNodeId {
+    type Response = SucNodeId;
+}
+
+#[derive(Debug)]
+pub struct SucNodeId {
+    node_id: Option<NodeId>,
+}
+
+impl SucNodeId {
+    pub fn new(node_id: Option<NodeId>) -> Self {
+        SucNodeId {
+            node_id: node_id,
+        }
+    }
+
+    /// Returns the SUC's node ID, or `None` if the network doesn't have one.
+    pub fn node_id(&self) -> Option<NodeId> {
+        self.node_id
+    }
+}
+
+impl Frame for SucNodeId {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::GetSucNodeId;
+}
+
+/// Enables or disables the controller's SUC functionality, optionally including the node ID server
+/// (SIS) that lets other controllers include nodes.
+#[derive(Debug)]
+pub struct EnableSuc {
+    enable: bool,
+    sis: bool,
+}
+
+impl EnableSuc {
+    pub fn new(enable: bool, sis: bool) -> Self {
+        EnableSuc {
+            enable: enable,
+            sis: sis,
+        }
+    }
+
+    pub fn enable(&self) -> bool {
+        self.enable
+    }
+
+    pub fn sis(&self) -> bool {
+        self.sis
+    }
+}
+
+impl Frame for EnableSuc {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::EnableSuc;
+}
+
+impl Request for EnableSuc {
+    type Response = EnableSucResult;
+}
+
+#[derive(Debug)]
+pub struct EnableSucResult {
+    result: u8,
+}
+
+impl EnableSucResult {
+    pub fn new(result: u8) -> Self {
+        EnableSucResult {
+            result: result,
+        }
+    }
+
+    pub fn result(&self) -> u8 {
+        self.result
+    }
+
+    pub fn accepted(&self) -> bool {
+        self.result != 0
+    }
+}
+
+impl Frame for EnableSucResult {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::EnableSuc;
+}
+
+/// Makes a controller the network's SUC, optionally with SIS functionality. The controller only
+/// calls back when the node is another controller.
+#[derive(Debug)]
+pub struct SetSucNodeId {
+    node_id: NodeId,
+    enable: bool,
+    sis: bool,
+    callback_id: u8,
+}
+
+impl SetSucNodeId {
+    pub fn new(node_id: NodeId, enable: bool, sis: bool, callback_id: u8) -> Self {
+        SetSucNodeId {
+            node_id: node_id,
+            enable: enable,
+            sis: sis,
+            callback_id: callback_id,
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn enable(&self) -> bool {
+        self.enable
+    }
+
+    pub fn sis(&self) -> bool {
+        self.sis
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+impl Frame for SetSucNodeId {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::SetSucNodeId;
+}
+
+impl Request for SetSucNodeId {
+    type Response = SetSucNodeIdResult;
+}
+
+impl CallbackRequest for SetSucNodeId {
+    type Callback = SucNodeIdSet;
+
+    fn set_callback_id(&mut self, callback_id: u8) {
+        self.callback_id = callback_id;
+    }
+
+    fn accepted(response: &SetSucNodeIdResult) -> bool {
+        response.accepted()
+    }
+}
+
+#[derive(Debug)]
+pub struct SetSucNodeIdResult {
+    result: u8,
+}
+
+impl SetSucNodeIdResult {
+    pub fn new(result: u8) -> Self {
+        SetSucNodeIdResult {
+            result: result,
+        }
+    }
+
+    pub fn result(&self) -> u8 {
+        self.result
+    }
+
+    pub fn accepted(&self) -> bool {
+        self.result != 0
+    }
+}
+
+impl Frame for SetSucNodeIdResult {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::SetSucNodeId;
+}
+
+#[derive(Debug)]
+pub struct SucNodeIdSet {
+    callback_id: u8,
+    status: u8,
+}
+
+impl SucNodeIdSet {
+    pub fn new(callback_id: u8, status: SetSucStatus) -> Self {
+        SucNodeIdSet::with_status(callback_id, status as u8)
+    }
+
+    pub fn with_status(callback_id: u8, status: u8) -> Self {
+        SucNodeIdSet {
+            callback_id: callback_id,
+            status: status,
+        }
+    }
+
+    pub fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+
+    pub fn status(&self) -> Option<SetSucStatus> {
+        SetSucStatus::from_u8(self.status)
+    }
+
+    pub fn raw_status(&self) -> u8 {
+        self.status
+    }
+}
+
+impl Frame for SucNodeIdSet {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::SetSucNodeId;
+}
+
+impl Callback for SucNodeIdSet {
+    fn callback_id(&self) -> u8 {
+        self.callback_id
+    }
+}
+
+/// Asks a 500 series controller about its NVM chip.
+#[derive(Debug)]
+pub struct GetNvmId { }
+
+impl GetNvmId {
+    pub fn new() -> Self {
+        GetNvmId { }
+    }
+}
+
+impl Frame for GetNvmId {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::GetNvmId;
+}
+
+impl Request for GetNvmId {
+    type Response = NvmId;
+}
+
+#[derive(Debug)]
+pub struct NvmId {
+    manufacturer_id: u8,
+    memory_type: u8,
+    memory_size: u8,
+}
+
+impl NvmId {
+    pub fn new(manufacturer_id: u8, memory_type: u8, memory_size: u8) -> Self {
+        NvmId {
+            manufacturer_id: manufacturer_id,
+            memory_type: memory_type,
+            memory_size: memory_size,
+        }
+    }
+
+    pub fn manufacturer_id(&self) -> u8 {
+        self.manufacturer_id
+    }
+
+    pub fn memory_type(&self) -> u8 {
+        self.memory_type
+    }
+
+    /// Returns the NVM's size as a power of two.
+    pub fn memory_size(&self) -> u8 {
+        self.memory_size
+    }
+
+    /// Returns the NVM's size in bytes, or `None` if the controller doesn't know it.
+    pub fn size(&self) -> Option<usize> {
+        // unknown sizes are reported as 0xFF
+        if self.memory_size < 32 {
+            Some(1 << self.memory_size)
+        }
+        else {
+            None
+        }
+    }
+}
+
+impl Frame for NvmId {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::GetNvmId;
+}
+
+/// Reads from the NVM of a 500 series controller.
+#[derive(Debug)]
+pub struct ExtNvmReadLongBuffer {
+    offset: u32,
+    length: u16,
+}
+
+impl ExtNvmReadLongBuffer {
+    pub fn new(offset: u32, length: u16) -> Self {
+        ExtNvmReadLongBuffer {
+            offset: offset,
+            length: length,
+        }
+    }
+
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    pub fn length(&self) -> u16 {
+        self.length
+    }
+}
+
+impl Frame for ExtNvmReadLongBuffer {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::ExtNvmReadLongBuffer;
+}
+
+impl Request for ExtNvmReadLongBuffer {
+    type Response = NvmBuffer;
+}
+
+#[derive(Debug)]
+pub struct NvmBuffer {
+    data: Vec<u8>,
+}
+
+impl NvmBuffer {
+    pub fn new(data: Vec<u8>) -> Self {
+        NvmBuffer {
+            data: data,
+        }
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl Frame for NvmBuffer {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::ExtNvmReadLongBuffer;
+}
+
+/// Writes to the NVM of a 500 series controller.
+#[derive(Debug)]
+pub struct ExtNvmWriteLongBuffer {
+    offset: u32,
+    data: Vec<u8>,
+}
+
+impl ExtNvmWriteLongBuffer {
+    pub fn new(offset: u32, data: Vec<u8>) -> Self {
+        ExtNvmWriteLongBuffer {
+            offset: offset,
+            data: data,
+        }
+    }
+
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl Frame for ExtNvmWriteLongBuffer {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::ExtNvmWriteLongBuffer;
+}
+
+impl Request for ExtNvmWriteLongBuffer {
+    type Response = ExtNvmWriteLongBufferResult;
+}
+
+#[derive(Debug)]
+pub struct ExtNvmWriteLongBufferResult {
+    result: u8,
+}
+
+impl ExtNvmWriteLongBufferResult {
+    pub fn new(result: u8) -> Self {
+        ExtNvmWriteLongBufferResult {
+            result: result,
+        }
+    }
+
+    pub fn result(&self) -> u8 {
+        self.result
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.result != 0
+    }
+}
+
+impl Frame for ExtNvmWriteLongBufferResult {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::ExtNvmWriteLongBuffer;
+}
+
+/// Opens, reads, writes or closes the NVM of a 700 series or later controller. The NVM has to be
+/// opened before it's read or written, and closed afterwards.
+#[derive(Debug)]
+pub struct NvmBackupRestore {
+    operation: NvmOperation,
+    offset: u16,
+    length: u8,
+    data: Vec<u8>,
+}
+
+impl NvmBackupRestore {
+    pub fn open() -> Self {
+        NvmBackupRestore::new(NvmOperation::Open, 0, 0, Vec::new())
+    }
+
+    pub fn read(offset: u16, length: u8) -> Self {
+        NvmBackupRestore::new(NvmOperation::Read, offset, length, Vec::new())
+    }
+
+    pub fn write(offset: u16, data: Vec<u8>) -> Self {
+        let length = data.len() as u8;
+
+        NvmBackupRestore::new(NvmOperation::Write, offset, length, data)
+    }
+
+    pub fn close() -> Self {
+        NvmBackupRestore::new(NvmOperation::Close, 0, 0, Vec::new())
+    }
+
+    fn new(operation: NvmOperation, offset: u16, length: u8, data: Vec<u8>) -> Self {
+        NvmBackupRestore {
+            operation: operation,
+            offset: offset,
+            length: length,
+            data: data,
+        }
+    }
+
+    pub fn operation(&self) -> NvmOperation {
+        self.operation
+    }
+
+    pub fn offset(&self) -> u16 {
+        self.offset
+    }
+
+    pub fn length(&self) -> u8 {
+        self.length
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl Frame for NvmBackupRestore {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::NvmBackupRestore;
+}
+
+impl Request for NvmBackupRestore {
+    type Response = NvmBackupRestoreResult;
+}
+
+#[derive(Debug)]
+pub struct NvmBackupRestoreResult {
+    status: u8,
+    offset: u16,
+    data: Vec<u8>,
+}
+
+impl NvmBackupRestoreResult {
+    pub fn new(status: NvmStatus, offset: u16, data: Vec<u8>) -> Self {
+        NvmBackupRestoreResult::with_status(status as u8, offset, data)
+    }
+
+    pub fn with_status(status: u8, offset: u16, data: Vec<u8>) -> Self {
+        NvmBackupRestoreResult {
+            status: status,
+            offset: offset,
+            data: data,
+        }
+    }
+
+    pub fn status(&self) -> Option<NvmStatus> {
+        NvmStatus::from_u8(self.status)
+    }
+
+    pub fn raw_status(&self) -> u8 {
+        self.status
+    }
+
+    /// Returns the offset of the data that was read or written, or the NVM's size in bytes in
+    /// response to `open()`.
+    pub fn offset(&self) -> u16 {
+        self.offset
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl Frame for NvmBackupRestoreResult {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::NvmBackupRestore;
+}
+
+/// The transmit power of a 700 series or later controller, in tenths of a dBm.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct TxPower {
+    normal: i8,
+    measured_0dbm: i8,
+}
+
+impl TxPower {
+    pub fn new(normal: i8, measured_0dbm: i8) -> Self {
+        TxPower {
+            normal: normal,
+            measured_0dbm: measured_0dbm,
+        }
+    }
+
+    /// Returns the power that the controller transmits at normally.
+    pub fn normal(&self) -> i8 {
+        self.normal
+    }
+
+    /// Returns the power that was measured at the antenna when the controller was set to 0 dBm,
+    /// which calibrates the other setting.
+    pub fn measured_0dbm(&self) -> i8 {
+        self.measured_0dbm
+    }
+}
+
+/// Reads or changes the configuration of a 700 series or later controller. Each variant is one
+/// subcommand.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub enum SerialApiSetup {
+    GetSupportedCommands,
+    GetRfRegion,
+    /// Takes effect once the controller restarts.
+    SetRfRegion(RfRegion),
+    GetTxPower,
+    SetTxPower(TxPower),
+    GetLongRangeMaxTxPower,
+    /// Limits the transmit power for Long Range, in tenths of a dBm.
+    SetLongRangeMaxTxPower(i16),
+    SetNodeIdType(NodeIdType),
+}
+
+impl SerialApiSetup {
+    pub fn command(&self) -> SerialApiSetupCommand {
+        use self::SerialApiSetup::*;
+
+        match *self {
+            GetSupportedCommands => SerialApiSetupCommand::GetSupportedCommands,
+            GetRfRegion => SerialApiSetupCommand::GetRfRegion,
+            SetRfRegion(_) => SerialApiSetupCommand::SetRfRegion,
+            GetTxPower => SerialApiSetupCommand::GetTxPower,
+            SetTxPower(_) => SerialApiSetupCommand::SetTxPower,
+            GetLongRangeMaxTxPower => SerialApiSetupCommand::GetLongRangeMaxTxPower,
+            SetLongRangeMaxTxPower(_) => SerialApiSetupCommand::SetLongRangeMaxTxPower,
+            SetNodeIdType(_) => SerialApiSetupCommand::SetNodeIdType,
+        }
+    }
+}
+
+impl Frame for SerialApiSetup {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Request;
+    const FUNCTION_ID: FunctionId = FunctionId::SerialApiSetup;
+}
+
+impl Request for SerialApiSetup {
+    type Response = SerialApiSetupResult;
+}
+
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub enum SerialApiSetupResult {
+    /// The controller doesn't know the subcommand with the given ID.
+    Unsupported(u8),
+    SupportedCommands(Vec<SerialApiSetupCommand>),
+    RfRegion(RfRegion),
+    TxPower(TxPower),
+    LongRangeMaxTxPower(i16),
+    /// Whether the controller accepted the setting.
+    Set(SerialApiSetupCommand, bool),
+}
+
+impl Frame for SerialApiSetupResult {
+    const MESSAGE_TYPE_ID: MessageTypeId = MessageTypeId::Response;
+    const FUNCTION_ID: FunctionId = FunctionId::SerialApiSetup;
+}
@@ -1,9 +1,12 @@
 use std::any::TypeId;
 use std::borrow::Borrow;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use core::{self, NodeId};
-use protocol::bits::{PreambleId, MessageTypeId, FunctionId};
+use core::{self, NodeId, HomeId};
+use protocol::bits::{PreambleId, MessageTypeId, FunctionId, AddNodeMode, RemoveNodeMode, LearnMode, NvmOperation};
+use protocol::bits::{SerialApiSetupCommand, RfRegion, NodeIdType};
 use super::{Message, Frame, AnyMessage};
 use super::{Ack, Nack, Cancel};
 use protocol::command::CommandSerializer;
@@ -23,120 +26,2492 @@ trait SerializeFrame: Send + 'static {
 }
 
 
+macro_rules! empty_frame_serializer {
+    ($serializer:ident : $frame:ident) => {
+        struct $serializer;
+
+        impl SerializeFrame for $serializer {
+            fn type_id(&self) -> TypeId {
+                TypeId::of::<super::$frame>()
+            }
+
+            fn key(&self) -> (MessageTypeId, FunctionId) {
+                (super::$frame::MESSAGE_TYPE_ID, super::$frame::FUNCTION_ID)
+            }
+
+            fn serialize(&self, message: &Message, _buffer: &mut Vec<u8>) -> core::Result<()> {
+                message.downcast_ref::<super::$frame>().unwrap();
+                Ok(())
+            }
+
+            fn deserialize(&self, _buffer: &[u8]) -> core::Result<AnyMessage> {
+                Ok(AnyMessage::new(super::$frame::new()))
+            }
+        }
+    }
+}
+
+macro_rules! callback_frame_serializer {
+    ($serializer:ident : $frame:ident) => {
+        struct $serializer;
+
+        impl SerializeFrame for $serializer {
+            fn type_id(&self) -> TypeId {
+                TypeId::of::<super::$frame>()
+            }
+
+            fn key(&self) -> (MessageTypeId, FunctionId) {
+                (super::$frame::MESSAGE_TYPE_ID, super::$frame::FUNCTION_ID)
+            }
+
+            fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+                let message = message.downcast_ref::<super::$frame>().unwrap();
+
+                buffer.push(message.callback_id());
+
+                Ok(())
+            }
+
+            fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+                if buffer.len() < 1 {
+                    return Err(core::Error::new(core::ErrorKind::ShortRead));
+                }
+
+                Ok(AnyMessage::new(super::$frame::new(buffer[0])))
+            }
+        }
+    }
+}
+
+fn push_u16(buffer: &mut Vec<u8>, value: u16) {
+    buffer.push((value >> 8) as u8);
+    buffer.push(value as u8);
+}
+
+fn push_u32(buffer: &mut Vec<u8>, value: u32) {
+    push_u16(buffer, (value >> 16) as u16);
+    push_u16(buffer, value as u16);
+}
+
+fn u16_at(buffer: &[u8], offset: usize) -> u16 {
+    (buffer[offset] as u16) << 8 | buffer[offset + 1] as u16
+}
+
+fn u32_at(buffer: &[u8], offset: usize) -> u32 {
+    (u16_at(buffer, offset) as u32) << 16 | u16_at(buffer, offset + 2) as u32
+}
+
+
 struct AckSerializer;
 
-impl SerializeMessage for AckSerializer {
-    fn key(&self) -> PreambleId {
-        PreambleId::Ack
+impl SerializeMessage for AckSerializer {
+    fn key(&self) -> PreambleId {
+        PreambleId::Ack
+    }
+
+    fn serialize(&self, _message: &Message, _buffer: &mut Vec<u8>) -> core::Result<()> {
+        Ok(())
+    }
+
+    fn deserialize(&self, _reader: &mut Read) -> core::Result<AnyMessage> {
+        Ok(AnyMessage::new(Ack::new()))
+    }
+}
+
+struct NackSerializer;
+
+impl SerializeMessage for NackSerializer {
+    fn key(&self) -> PreambleId {
+        PreambleId::Nack
+    }
+
+    fn serialize(&self, _message: &Message, _buffer: &mut Vec<u8>) -> core::Result<()> {
+        Ok(())
+    }
+
+    fn deserialize(&self, _reader: &mut Read) -> core::Result<AnyMessage> {
+        Ok(AnyMessage::new(Nack::new()))
+    }
+}
+
+struct CancelSerializer;
+
+impl SerializeMessage for CancelSerializer {
+    fn key(&self) -> PreambleId {
+        PreambleId::Cancel
+    }
+
+    fn serialize(&self, _message: &Message, _buffer: &mut Vec<u8>) -> core::Result<()> {
+        Ok(())
+    }
+
+    fn deserialize(&self, _reader: &mut Read) -> core::Result<AnyMessage> {
+        Ok(AnyMessage::new(Cancel::new()))
+    }
+}
+
+
+/// How wide node IDs are in frames. The serializers of frames that carry node IDs share one, so
+/// that switching to Long Range node IDs switches all of them.
+#[derive(Clone)]
+struct NodeIdFormat {
+    long: Arc<AtomicBool>,
+}
+
+impl NodeIdFormat {
+    fn new() -> Self {
+        NodeIdFormat {
+            long: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn set(&self, node_id_type: NodeIdType) {
+        self.long.store(node_id_type == NodeIdType::Long, Ordering::Relaxed);
+    }
+
+    /// Returns the number of bytes that a node ID takes.
+    fn width(&self) -> usize {
+        if self.long.load(Ordering::Relaxed) { 2 } else { 1 }
+    }
+
+    fn push(&self, buffer: &mut Vec<u8>, node_id: NodeId) -> core::Result<()> {
+        if self.width() == 2 {
+            push_u16(buffer, node_id.value());
+        }
+        else if node_id.is_long_range() {
+            return Err(core::Error::new(core::ErrorKind::Unsupported));
+        }
+        else {
+            buffer.push(node_id.value() as u8);
+        }
+
+        Ok(())
+    }
+
+    fn at(&self, buffer: &[u8], offset: usize) -> NodeId {
+        if self.width() == 2 {
+            NodeId(u16_at(buffer, offset))
+        }
+        else {
+            NodeId(buffer[offset] as u16)
+        }
+    }
+
+    /// Returns the node ID at `offset`, or zero for callbacks that leave it out in some states.
+    fn get(&self, buffer: &[u8], offset: usize) -> NodeId {
+        if buffer.len() < offset + self.width() {
+            NodeId(0)
+        }
+        else {
+            self.at(buffer, offset)
+        }
+    }
+}
+
+struct SendDataSerializer(CommandSerializer, NodeIdFormat);
+
+impl SerializeFrame for SendDataSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::SendData>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::SendData::MESSAGE_TYPE_ID, super::SendData::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let send_data = message.downcast_ref::<super::SendData>().unwrap();
+
+        try!(self.1.push(buffer, send_data.destination()));
+
+        let length_offset = buffer.len();
+        buffer.push(0x00); // payload length; come back when it's known
+
+        let payload_offset = buffer.len();
+        try!(self.0.serialize(send_data.command().borrow(), buffer));
+
+        buffer[length_offset] = (buffer.len() - payload_offset) as u8;
+
+        buffer.push(send_data.packet_options());
+        buffer.push(send_data.callback_id());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        let offset = 1 + self.1.width();
+
+        if buffer.len() < offset + 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let destination = self.1.at(buffer, 0);
+        let payload_length = buffer[offset - 1] as usize;
+
+        if buffer.len() < offset + payload_length + 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let command = try!(self.0.deserialize(&buffer[offset..offset+payload_length]));
+        let packet_options = buffer[offset + payload_length];
+        let callback_id = buffer[offset + payload_length + 1];
+
+        Ok(AnyMessage::new(super::SendData {
+            destination: destination,
+            command: command,
+            packet_options: packet_options,
+            callback_id: callback_id,
+        }))
+    }
+}
+
+struct MessageTransmittedSerializer;
+
+impl SerializeFrame for MessageTransmittedSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::MessageTransmitted>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::MessageTransmitted::MESSAGE_TYPE_ID, super::MessageTransmitted::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::MessageTransmitted>().unwrap();
+
+        buffer.push(message.flags());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::MessageTransmitted::new(buffer[0])))
+    }
+}
+
+struct MessageReceivedSerializer;
+
+impl SerializeFrame for MessageReceivedSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::MessageReceived>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::MessageReceived::MESSAGE_TYPE_ID, super::MessageReceived::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::MessageReceived>().unwrap();
+
+        buffer.push(message.callback_id());
+        buffer.push(message.flags());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::MessageReceived::new(buffer[0], buffer[1])))
+    }
+}
+
+
+/// The most destinations that the Serial API accepts in one multicast frame.
+const MAX_MULTICAST_DESTINATIONS: usize = 232;
+
+struct SendDataMultiSerializer(CommandSerializer, NodeIdFormat);
+
+impl SerializeFrame for SendDataMultiSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::SendDataMulti>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::SendDataMulti::MESSAGE_TYPE_ID, super::SendDataMulti::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let send_data = message.downcast_ref::<super::SendDataMulti>().unwrap();
+
+        if send_data.destinations().len() > MAX_MULTICAST_DESTINATIONS {
+            return Err(core::Error::new(core::ErrorKind::Unsupported));
+        }
+
+        buffer.push(send_data.destinations().len() as u8);
+
+        for destination in send_data.destinations() {
+            // Long Range nodes can't be reached by multicast
+            if destination.is_long_range() {
+                return Err(core::Error::new(core::ErrorKind::Unsupported));
+            }
+
+            try!(self.1.push(buffer, *destination));
+        }
+
+        let length_offset = buffer.len();
+        buffer.push(0x00); // payload length; come back when it's known
+
+        let payload_offset = buffer.len();
+        try!(self.0.serialize(send_data.command().borrow(), buffer));
+
+        buffer[length_offset] = (buffer.len() - payload_offset) as u8;
+
+        buffer.push(send_data.packet_options());
+        buffer.push(send_data.callback_id());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let count = buffer[0] as usize;
+        let width = self.1.width();
+
+        if buffer.len() < 4 + count * width {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let destinations = (0..count).map(|index| self.1.at(buffer, 1 + index * width)).collect();
+        let payload_offset = 2 + count * width;
+        let payload_length = buffer[1 + count * width] as usize;
+
+        if buffer.len() < payload_offset + payload_length + 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let command = try!(self.0.deserialize(&buffer[payload_offset..payload_offset+payload_length]));
+        let packet_options = buffer[payload_offset + payload_length];
+        let callback_id = buffer[payload_offset + payload_length + 1];
+
+        Ok(AnyMessage::new(super::SendDataMulti {
+            destinations: destinations,
+            command: command,
+            packet_options: packet_options,
+            callback_id: callback_id,
+        }))
+    }
+}
+
+struct MulticastTransmittedSerializer;
+
+impl SerializeFrame for MulticastTransmittedSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::MulticastTransmitted>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::MulticastTransmitted::MESSAGE_TYPE_ID, super::MulticastTransmitted::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::MulticastTransmitted>().unwrap();
+
+        buffer.push(message.flags());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::MulticastTransmitted::new(buffer[0])))
+    }
+}
+
+struct MulticastCompletedSerializer;
+
+impl SerializeFrame for MulticastCompletedSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::MulticastCompleted>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::MulticastCompleted::MESSAGE_TYPE_ID, super::MulticastCompleted::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::MulticastCompleted>().unwrap();
+
+        buffer.push(message.callback_id());
+        buffer.push(message.flags());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::MulticastCompleted::new(buffer[0], buffer[1])))
+    }
+}
+
+empty_frame_serializer!(SendDataAbortSerializer: SendDataAbort);
+
+struct ApplicationCommandHandlerSerializer(CommandSerializer, NodeIdFormat);
+
+impl SerializeFrame for ApplicationCommandHandlerSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::ApplicationCommandHandler>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::ApplicationCommandHandler::MESSAGE_TYPE_ID, super::ApplicationCommandHandler::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::ApplicationCommandHandler>().unwrap();
+
+        buffer.push(message.status());
+        try!(self.1.push(buffer, message.source()));
+
+        let length_offset = buffer.len();
+        buffer.push(0x00); // payload length; come back when it's known
+
+        let payload_offset = buffer.len();
+        try!(self.0.serialize(message.command().borrow(), buffer));
+
+        buffer[length_offset] = (buffer.len() - payload_offset) as u8;
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        let offset = 2 + self.1.width();
+
+        if buffer.len() < offset {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let status = buffer[0];
+        let source = self.1.at(buffer, 1);
+        let payload_length = buffer[offset - 1] as usize;
+
+        if buffer.len() < offset + payload_length {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let command = try!(self.0.deserialize(&buffer[offset..offset+payload_length]));
+
+        Ok(AnyMessage::new(super::ApplicationCommandHandler {
+            status: status,
+            source: source,
+            command: command,
+        }))
+    }
+}
+
+
+const COMMAND_CLASS_MARK: u8 = 0xEF;
+
+fn serialize_node_info(node_info: &super::NodeInfo, buffer: &mut Vec<u8>) {
+    buffer.push(node_info.basic_class());
+    buffer.push(node_info.generic_class());
+    buffer.push(node_info.specific_class());
+    buffer.extend_from_slice(node_info.command_classes());
+
+    if !node_info.controlled_command_classes().is_empty() {
+        buffer.push(COMMAND_CLASS_MARK);
+        buffer.extend_from_slice(node_info.controlled_command_classes());
+    }
+}
+
+fn deserialize_node_info(buffer: &[u8]) -> core::Result<super::NodeInfo> {
+    if buffer.len() < 3 {
+        return Err(core::Error::new(core::ErrorKind::ShortRead));
+    }
+
+    let command_classes = &buffer[3..];
+
+    let (supported, controlled) = match command_classes.iter().position(|&id| id == COMMAND_CLASS_MARK) {
+        Some(mark) => (&command_classes[..mark], &command_classes[mark+1..]),
+        None => (command_classes, &command_classes[command_classes.len()..]),
+    };
+
+    Ok(super::NodeInfo::new(buffer[0], buffer[1], buffer[2], supported.to_vec(), controlled.to_vec()))
+}
+
+const NODE_MASK_LENGTH: usize = 29;
+
+/// Fails for node IDs that the mask has no bit for, which includes Long Range nodes.
+fn serialize_node_mask(node_ids: &[NodeId], buffer: &mut Vec<u8>) -> core::Result<()> {
+    let offset = buffer.len();
+    buffer.extend_from_slice(&[0u8; NODE_MASK_LENGTH]);
+
+    for node_id in node_ids {
+        if node_id.value() == 0 || node_id.value() as usize > NODE_MASK_LENGTH * 8 {
+            return Err(core::Error::new(core::ErrorKind::Unsupported));
+        }
+
+        let bit = node_id.value() as usize - 1;
+        buffer[offset + bit / 8] |= 1 << (bit % 8);
+    }
+
+    Ok(())
+}
+
+fn deserialize_node_mask(buffer: &[u8]) -> Vec<NodeId> {
+    deserialize_node_mask_from(buffer, 1)
+}
+
+/// Returns the node IDs in a mask whose first bit stands for `first`.
+fn deserialize_node_mask_from(buffer: &[u8], first: u16) -> Vec<NodeId> {
+    let mut node_ids = Vec::<NodeId>::new();
+
+    for (index, byte) in buffer.iter().enumerate() {
+        for bit in 0..8 {
+            if byte & (1 << bit) != 0 {
+                node_ids.push(NodeId(first + (index * 8 + bit) as u16));
+            }
+        }
+    }
+
+    node_ids
+}
+
+const LONG_RANGE_SEGMENT_SIZE: u16 = 1024;
+
+/// Returns the first node ID that a segment of the Long Range node list covers.
+fn long_range_segment_start(segment: u8) -> u16 {
+    NodeId::LONG_RANGE_START.value() + segment as u16 * LONG_RANGE_SEGMENT_SIZE
+}
+
+const UPDATE_STATE_NODE_INFO_RECEIVED: u8 = 0x84;
+const UPDATE_STATE_NODE_INFO_REQ_DONE: u8 = 0x82;
+const UPDATE_STATE_NODE_INFO_REQ_FAILED: u8 = 0x81;
+const UPDATE_STATE_ROUTING_PENDING: u8 = 0x80;
+const UPDATE_STATE_NEW_ID_ASSIGNED: u8 = 0x40;
+const UPDATE_STATE_DELETE_DONE: u8 = 0x20;
+const UPDATE_STATE_SUC_ID: u8 = 0x10;
+
+struct ApplicationUpdateSerializer(NodeIdFormat);
+
+impl SerializeFrame for ApplicationUpdateSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::ApplicationUpdate>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::ApplicationUpdate::MESSAGE_TYPE_ID, super::ApplicationUpdate::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        use super::ApplicationUpdate::*;
+
+        let update = message.downcast_ref::<super::ApplicationUpdate>().unwrap();
+
+        let (status, node_id) = match *update {
+            NodeInfoReceived(node_id, _) => (UPDATE_STATE_NODE_INFO_RECEIVED, node_id),
+            NodeInfoRequestDone(node_id) => (UPDATE_STATE_NODE_INFO_REQ_DONE, node_id),
+            NodeInfoRequestFailed => (UPDATE_STATE_NODE_INFO_REQ_FAILED, NodeId(0)),
+            RoutingPending(node_id) => (UPDATE_STATE_ROUTING_PENDING, node_id),
+            NewIdAssigned(node_id) => (UPDATE_STATE_NEW_ID_ASSIGNED, node_id),
+            DeleteDone(node_id) => (UPDATE_STATE_DELETE_DONE, node_id),
+            SucIdChanged(node_id) => (UPDATE_STATE_SUC_ID, node_id),
+        };
+
+        buffer.push(status);
+        try!(self.0.push(buffer, node_id));
+
+        let length_offset = buffer.len();
+        buffer.push(0x00); // node info length; come back when it's known
+
+        if let NodeInfoReceived(_, ref node_info) = *update {
+            serialize_node_info(node_info, buffer);
+        }
+
+        buffer[length_offset] = (buffer.len() - length_offset - 1) as u8;
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        use super::ApplicationUpdate::*;
+
+        let offset = 2 + self.0.width();
+
+        if buffer.len() < offset {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let node_id = self.0.at(buffer, 1);
+        let length = buffer[offset - 1] as usize;
+
+        if buffer.len() < offset + length {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let update = match buffer[0] {
+            UPDATE_STATE_NODE_INFO_RECEIVED => NodeInfoReceived(node_id, try!(deserialize_node_info(&buffer[offset..offset+length]))),
+            UPDATE_STATE_NODE_INFO_REQ_DONE => NodeInfoRequestDone(node_id),
+            UPDATE_STATE_NODE_INFO_REQ_FAILED => NodeInfoRequestFailed,
+            UPDATE_STATE_ROUTING_PENDING => RoutingPending(node_id),
+            UPDATE_STATE_NEW_ID_ASSIGNED => NewIdAssigned(node_id),
+            UPDATE_STATE_DELETE_DONE => DeleteDone(node_id),
+            UPDATE_STATE_SUC_ID => SucIdChanged(node_id),
+            _ => return Err(core::Error::new(core::ErrorKind::Protocol)),
+        };
+
+        Ok(AnyMessage::new(update))
+    }
+}
+
+
+empty_frame_serializer!(GetVersionSerializer: GetVersion);
+
+struct VersionSerializer;
+
+impl SerializeFrame for VersionSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::Version>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::Version::MESSAGE_TYPE_ID, super::Version::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::Version>().unwrap();
+
+        buffer.extend_from_slice(message.library_version().as_bytes());
+        buffer.push(0x00);
+        buffer.push(message.library_type);
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        let terminator = match buffer.iter().position(|&byte| byte == 0x00) {
+            Some(index) => index,
+            None => return Err(core::Error::new(core::ErrorKind::ShortRead)),
+        };
+
+        if buffer.len() < terminator + 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let library_version = try!(String::from_utf8(buffer[..terminator].to_vec()).map_err(|_| {
+            core::Error::new(core::ErrorKind::Protocol)
+        }));
+
+        Ok(AnyMessage::new(super::Version {
+            library_version: library_version,
+            library_type: buffer[terminator + 1],
+        }))
+    }
+}
+
+empty_frame_serializer!(MemoryGetIdSerializer: MemoryGetId);
+
+struct MemoryIdSerializer(NodeIdFormat);
+
+impl SerializeFrame for MemoryIdSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::MemoryId>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::MemoryId::MESSAGE_TYPE_ID, super::MemoryId::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::MemoryId>().unwrap();
+
+        push_u32(buffer, message.home_id().value());
+        try!(self.0.push(buffer, message.node_id()));
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 4 + self.0.width() {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::MemoryId::new(HomeId(u32_at(buffer, 0)), self.0.at(buffer, 4))))
+    }
+}
+
+empty_frame_serializer!(SerialApiGetCapabilitiesSerializer: SerialApiGetCapabilities);
+
+struct SerialApiCapabilitiesSerializer;
+
+impl SerializeFrame for SerialApiCapabilitiesSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::SerialApiCapabilities>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::SerialApiCapabilities::MESSAGE_TYPE_ID, super::SerialApiCapabilities::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::SerialApiCapabilities>().unwrap();
+
+        buffer.push(message.application_version());
+        buffer.push(message.application_revision());
+        push_u16(buffer, message.manufacturer_id());
+        push_u16(buffer, message.product_type());
+        push_u16(buffer, message.product_id());
+        buffer.extend_from_slice(message.function_mask());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 40 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let mut function_mask = [0u8; 32];
+        function_mask.copy_from_slice(&buffer[8..40]);
+
+        Ok(AnyMessage::new(super::SerialApiCapabilities::new(
+            buffer[0],
+            buffer[1],
+            u16_at(buffer, 2),
+            u16_at(buffer, 4),
+            u16_at(buffer, 6),
+            function_mask)))
+    }
+}
+
+
+empty_frame_serializer!(SerialApiGetInitDataSerializer: SerialApiGetInitData);
+
+struct SerialApiInitDataSerializer;
+
+impl SerializeFrame for SerialApiInitDataSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::SerialApiInitData>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::SerialApiInitData::MESSAGE_TYPE_ID, super::SerialApiInitData::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::SerialApiInitData>().unwrap();
+
+        buffer.push(message.api_version());
+        buffer.push(message.capabilities());
+        buffer.push(NODE_MASK_LENGTH as u8);
+        try!(serialize_node_mask(message.node_ids(), buffer));
+        buffer.push(message.chip_type());
+        buffer.push(message.chip_version());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 3 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let mask_length = buffer[2] as usize;
+
+        if buffer.len() < 5 + mask_length {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let node_ids = deserialize_node_mask(&buffer[3..3+mask_length]);
+
+        Ok(AnyMessage::new(super::SerialApiInitData::new(
+            buffer[0],
+            buffer[1],
+            node_ids,
+            buffer[3 + mask_length],
+            buffer[4 + mask_length])))
+    }
+}
+
+struct GetLongRangeNodesSerializer;
+
+impl SerializeFrame for GetLongRangeNodesSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::GetLongRangeNodes>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::GetLongRangeNodes::MESSAGE_TYPE_ID, super::GetLongRangeNodes::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::GetLongRangeNodes>().unwrap();
+
+        buffer.push(message.segment());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::GetLongRangeNodes::new(buffer[0])))
+    }
+}
+
+struct LongRangeNodesSerializer;
+
+impl SerializeFrame for LongRangeNodesSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::LongRangeNodes>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::LongRangeNodes::MESSAGE_TYPE_ID, super::LongRangeNodes::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::LongRangeNodes>().unwrap();
+        let start = long_range_segment_start(message.segment());
+
+        let mut mask = Vec::<u8>::new();
+
+        for node_id in message.node_ids() {
+            if node_id.value() < start || node_id.value() - start >= LONG_RANGE_SEGMENT_SIZE {
+                return Err(core::Error::new(core::ErrorKind::Protocol));
+            }
+
+            let bit = (node_id.value() - start) as usize;
+
+            if mask.len() <= bit / 8 {
+                mask.resize(bit / 8 + 1, 0x00);
+            }
+
+            mask[bit / 8] |= 1 << (bit % 8);
+        }
+
+        buffer.push(message.more() as u8);
+        buffer.push(message.segment());
+        buffer.push(mask.len() as u8);
+        buffer.extend_from_slice(&mask);
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 3 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let mask_length = buffer[2] as usize;
+
+        if buffer.len() < 3 + mask_length {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let node_ids = deserialize_node_mask_from(&buffer[3..3+mask_length], long_range_segment_start(buffer[1]));
+
+        Ok(AnyMessage::new(super::LongRangeNodes::new(buffer[0] != 0, buffer[1], node_ids)))
+    }
+}
+
+struct GetNodeProtocolInfoSerializer(NodeIdFormat);
+
+impl SerializeFrame for GetNodeProtocolInfoSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::GetNodeProtocolInfo>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::GetNodeProtocolInfo::MESSAGE_TYPE_ID, super::GetNodeProtocolInfo::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::GetNodeProtocolInfo>().unwrap();
+
+        try!(self.0.push(buffer, message.node_id()));
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < self.0.width() {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::GetNodeProtocolInfo::new(self.0.at(buffer, 0))))
+    }
+}
+
+struct NodeProtocolInfoSerializer;
+
+impl SerializeFrame for NodeProtocolInfoSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::NodeProtocolInfo>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::NodeProtocolInfo::MESSAGE_TYPE_ID, super::NodeProtocolInfo::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::NodeProtocolInfo>().unwrap();
+
+        buffer.push(message.capability());
+        buffer.push(message.security());
+        buffer.push(message.reserved());
+        buffer.push(message.basic_class());
+        buffer.push(message.generic_class());
+        buffer.push(message.specific_class());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 6 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::NodeProtocolInfo::new(buffer[0], buffer[1], buffer[2], buffer[3], buffer[4], buffer[5])))
+    }
+}
+
+
+const NODE_OPTION_HIGH_POWER: u8 = 0x80;
+const NODE_OPTION_NETWORK_WIDE: u8 = 0x40;
+const NODE_OPTION_LONG_RANGE: u8 = 0x20;
+const NODE_MODE_MASK: u8 = 0x0F;
+
+struct AddNodeToNetworkSerializer;
+
+impl SerializeFrame for AddNodeToNetworkSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::AddNodeToNetwork>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::AddNodeToNetwork::MESSAGE_TYPE_ID, super::AddNodeToNetwork::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::AddNodeToNetwork>().unwrap();
+        let mut mode = message.mode() as u8;
+
+        if message.high_power() {
+            mode |= NODE_OPTION_HIGH_POWER;
+        }
+
+        if message.network_wide() {
+            mode |= NODE_OPTION_NETWORK_WIDE;
+        }
+
+        if message.long_range() {
+            mode |= NODE_OPTION_LONG_RANGE;
+        }
+
+        buffer.push(mode);
+        buffer.push(message.callback_id());
+
+        if let (Some(nwi_home_id), Some(auth_home_id)) = (message.nwi_home_id(), message.auth_home_id()) {
+            push_u32(buffer, nwi_home_id.value());
+            push_u32(buffer, auth_home_id.value());
+        }
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let mode = match AddNodeMode::from_u8(buffer[0] & NODE_MODE_MASK) {
+            Some(mode) => mode,
+            None => return Err(core::Error::new(core::ErrorKind::Protocol)),
+        };
+
+        if mode == AddNodeMode::SmartStartDsk {
+            if buffer.len() < 10 {
+                return Err(core::Error::new(core::ErrorKind::ShortRead));
+            }
+
+            return Ok(AnyMessage::new(super::AddNodeToNetwork::with_home_ids(
+                buffer[1],
+                buffer[0] & NODE_OPTION_LONG_RANGE != 0,
+                HomeId(u32_at(buffer, 2)),
+                HomeId(u32_at(buffer, 6)))));
+        }
+
+        Ok(AnyMessage::new(super::AddNodeToNetwork::with_options(
+            mode,
+            buffer[1],
+            buffer[0] & NODE_OPTION_HIGH_POWER != 0,
+            buffer[0] & NODE_OPTION_NETWORK_WIDE != 0)))
+    }
+}
+
+struct AddNodeUpdateSerializer(NodeIdFormat);
+
+impl SerializeFrame for AddNodeUpdateSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::AddNodeUpdate>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::AddNodeUpdate::MESSAGE_TYPE_ID, super::AddNodeUpdate::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let update = message.downcast_ref::<super::AddNodeUpdate>().unwrap();
+
+        buffer.push(update.callback_id());
+        buffer.push(update.raw_status());
+        try!(self.0.push(buffer, update.node_id()));
+
+        let length_offset = buffer.len();
+        buffer.push(0x00); // node info length; come back when it's known
+
+        if let Some(node_info) = update.node_info() {
+            serialize_node_info(node_info, buffer);
+        }
+
+        buffer[length_offset] = (buffer.len() - length_offset - 1) as u8;
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        // some states are reported without node ID and length
+        let node_id = self.0.get(buffer, 2);
+        let offset = 3 + self.0.width();
+        let length = buffer.get(offset - 1).cloned().unwrap_or(0) as usize;
+
+        if length > 0 && buffer.len() < offset + length {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let node_info = if length > 0 {
+            Some(try!(deserialize_node_info(&buffer[offset..offset+length])))
+        }
+        else {
+            None
+        };
+
+        Ok(AnyMessage::new(super::AddNodeUpdate::with_status(buffer[0], buffer[1], node_id, node_info)))
+    }
+}
+
+
+struct RemoveNodeFromNetworkSerializer;
+
+impl SerializeFrame for RemoveNodeFromNetworkSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::RemoveNodeFromNetwork>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::RemoveNodeFromNetwork::MESSAGE_TYPE_ID, super::RemoveNodeFromNetwork::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::RemoveNodeFromNetwork>().unwrap();
+        let mut mode = message.mode() as u8;
+
+        if message.high_power() {
+            mode |= NODE_OPTION_HIGH_POWER;
+        }
+
+        if message.network_wide() {
+            mode |= NODE_OPTION_NETWORK_WIDE;
+        }
+
+        buffer.push(mode);
+        buffer.push(message.callback_id());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let mode = match RemoveNodeMode::from_u8(buffer[0] & NODE_MODE_MASK) {
+            Some(mode) => mode,
+            None => return Err(core::Error::new(core::ErrorKind::Protocol)),
+        };
+
+        Ok(AnyMessage::new(super::RemoveNodeFromNetwork::with_options(
+            mode,
+            buffer[1],
+            buffer[0] & NODE_OPTION_HIGH_POWER != 0,
+            buffer[0] & NODE_OPTION_NETWORK_WIDE != 0)))
+    }
+}
+
+struct RemoveNodeUpdateSerializer(NodeIdFormat);
+
+impl SerializeFrame for RemoveNodeUpdateSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::RemoveNodeUpdate>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::RemoveNodeUpdate::MESSAGE_TYPE_ID, super::RemoveNodeUpdate::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let update = message.downcast_ref::<super::RemoveNodeUpdate>().unwrap();
+
+        buffer.push(update.callback_id());
+        buffer.push(update.raw_status());
+        try!(self.0.push(buffer, update.node_id()));
+        buffer.push(0x00); // node info length
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        // some states are reported without node ID; the node information frame isn't of interest
+        let node_id = self.0.get(buffer, 2);
+
+        Ok(AnyMessage::new(super::RemoveNodeUpdate::with_status(buffer[0], buffer[1], node_id)))
+    }
+}
+
+
+struct SetLearnModeSerializer;
+
+impl SerializeFrame for SetLearnModeSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::SetLearnMode>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::SetLearnMode::MESSAGE_TYPE_ID, super::SetLearnMode::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::SetLearnMode>().unwrap();
+
+        buffer.push(message.mode() as u8);
+        buffer.push(message.callback_id());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let mode = match LearnMode::from_u8(buffer[0]) {
+            Some(mode) => mode,
+            None => return Err(core::Error::new(core::ErrorKind::Protocol)),
+        };
+
+        Ok(AnyMessage::new(super::SetLearnMode::new(mode, buffer[1])))
+    }
+}
+
+struct LearnModeResultSerializer;
+
+impl SerializeFrame for LearnModeResultSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::LearnModeResult>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::LearnModeResult::MESSAGE_TYPE_ID, super::LearnModeResult::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::LearnModeResult>().unwrap();
+
+        buffer.push(message.result());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::LearnModeResult::new(buffer[0])))
+    }
+}
+
+struct LearnModeUpdateSerializer(NodeIdFormat);
+
+impl SerializeFrame for LearnModeUpdateSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::LearnModeUpdate>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::LearnModeUpdate::MESSAGE_TYPE_ID, super::LearnModeUpdate::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let update = message.downcast_ref::<super::LearnModeUpdate>().unwrap();
+
+        buffer.push(update.callback_id());
+        buffer.push(update.raw_status());
+        try!(self.0.push(buffer, update.node_id()));
+        buffer.push(0x00); // length of the data that follows
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let node_id = self.0.get(buffer, 2);
+
+        Ok(AnyMessage::new(super::LearnModeUpdate::with_status(buffer[0], buffer[1], node_id)))
+    }
+}
+
+
+callback_frame_serializer!(SetDefaultSerializer: SetDefault);
+callback_frame_serializer!(DefaultSetSerializer: DefaultSet);
+empty_frame_serializer!(SoftResetSerializer: SoftReset);
+
+const SUPPORTED_PROTOCOL_LONG_RANGE: u8 = 0x01;
+
+struct SerialApiStartedSerializer;
+
+impl SerializeFrame for SerialApiStartedSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::SerialApiStarted>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::SerialApiStarted::MESSAGE_TYPE_ID, super::SerialApiStarted::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::SerialApiStarted>().unwrap();
+
+        buffer.push(message.wake_up_reason());
+        buffer.push(message.watchdog_started() as u8);
+        buffer.push(message.device_options());
+        buffer.push(message.generic_class());
+        buffer.push(message.specific_class());
+        buffer.push(message.command_classes().len() as u8);
+        buffer.extend_from_slice(message.command_classes());
+        buffer.push(if message.supports_long_range() { SUPPORTED_PROTOCOL_LONG_RANGE } else { 0x00 });
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 6 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let length = buffer[5] as usize;
+
+        if buffer.len() < 6 + length {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        // older firmware doesn't report the supported protocols
+        let supported_protocols = buffer.get(6 + length).cloned().unwrap_or(0);
+
+        Ok(AnyMessage::new(super::SerialApiStarted::new(
+            buffer[0],
+            buffer[1] != 0,
+            buffer[2],
+            buffer[3],
+            buffer[4],
+            buffer[6..6+length].to_vec(),
+            supported_protocols & SUPPORTED_PROTOCOL_LONG_RANGE != 0)))
+    }
+}
+
+
+struct IsFailedNodeSerializer(NodeIdFormat);
+
+impl SerializeFrame for IsFailedNodeSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::IsFailedNode>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::IsFailedNode::MESSAGE_TYPE_ID, super::IsFailedNode::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::IsFailedNode>().unwrap();
+
+        try!(self.0.push(buffer, message.node_id()));
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < self.0.width() {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::IsFailedNode::new(self.0.at(buffer, 0))))
+    }
+}
+
+struct IsFailedNodeResultSerializer;
+
+impl SerializeFrame for IsFailedNodeResultSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::IsFailedNodeResult>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::IsFailedNodeResult::MESSAGE_TYPE_ID, super::IsFailedNodeResult::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::IsFailedNodeResult>().unwrap();
+
+        buffer.push(message.failed() as u8);
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::IsFailedNodeResult::new(buffer[0] != 0)))
+    }
+}
+
+struct RemoveFailedNodeSerializer(NodeIdFormat);
+
+impl SerializeFrame for RemoveFailedNodeSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::RemoveFailedNode>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::RemoveFailedNode::MESSAGE_TYPE_ID, super::RemoveFailedNode::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::RemoveFailedNode>().unwrap();
+
+        try!(self.0.push(buffer, message.node_id()));
+        buffer.push(message.callback_id());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        let length = self.0.width();
+
+        if buffer.len() < length + 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::RemoveFailedNode::new(self.0.at(buffer, 0), buffer[length])))
+    }
+}
+
+struct RemoveFailedNodeResultSerializer;
+
+impl SerializeFrame for RemoveFailedNodeResultSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::RemoveFailedNodeResult>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::RemoveFailedNodeResult::MESSAGE_TYPE_ID, super::RemoveFailedNodeResult::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::RemoveFailedNodeResult>().unwrap();
+
+        buffer.push(message.flags());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::RemoveFailedNodeResult::new(buffer[0])))
+    }
+}
+
+struct RemoveFailedNodeUpdateSerializer;
+
+impl SerializeFrame for RemoveFailedNodeUpdateSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::RemoveFailedNodeUpdate>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::RemoveFailedNodeUpdate::MESSAGE_TYPE_ID, super::RemoveFailedNodeUpdate::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let update = message.downcast_ref::<super::RemoveFailedNodeUpdate>().unwrap();
+
+        buffer.push(update.callback_id());
+        buffer.push(update.raw_status());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::RemoveFailedNodeUpdate::with_status(buffer[0], buffer[1])))
+    }
+}
+
+struct ReplaceFailedNodeSerializer(NodeIdFormat);
+
+impl SerializeFrame for ReplaceFailedNodeSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::ReplaceFailedNode>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::ReplaceFailedNode::MESSAGE_TYPE_ID, super::ReplaceFailedNode::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::ReplaceFailedNode>().unwrap();
+
+        try!(self.0.push(buffer, message.node_id()));
+        buffer.push(message.callback_id());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        let length = self.0.width();
+
+        if buffer.len() < length + 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::ReplaceFailedNode::new(self.0.at(buffer, 0), buffer[length])))
+    }
+}
+
+struct ReplaceFailedNodeResultSerializer;
+
+impl SerializeFrame for ReplaceFailedNodeResultSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::ReplaceFailedNodeResult>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::ReplaceFailedNodeResult::MESSAGE_TYPE_ID, super::ReplaceFailedNodeResult::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::ReplaceFailedNodeResult>().unwrap();
+
+        buffer.push(message.flags());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::ReplaceFailedNodeResult::new(buffer[0])))
+    }
+}
+
+struct ReplaceFailedNodeUpdateSerializer;
+
+impl SerializeFrame for ReplaceFailedNodeUpdateSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::ReplaceFailedNodeUpdate>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::ReplaceFailedNodeUpdate::MESSAGE_TYPE_ID, super::ReplaceFailedNodeUpdate::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let update = message.downcast_ref::<super::ReplaceFailedNodeUpdate>().unwrap();
+
+        buffer.push(update.callback_id());
+        buffer.push(update.raw_status());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::ReplaceFailedNodeUpdate::with_status(buffer[0], buffer[1])))
+    }
+}
+
+
+struct GetRoutingInfoSerializer(NodeIdFormat);
+
+impl SerializeFrame for GetRoutingInfoSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::GetRoutingInfo>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::GetRoutingInfo::MESSAGE_TYPE_ID, super::GetRoutingInfo::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::GetRoutingInfo>().unwrap();
+
+        try!(self.0.push(buffer, message.node_id()));
+        buffer.push(message.remove_bad() as u8);
+        buffer.push(message.remove_non_repeaters() as u8);
+        buffer.push(0x00); // function ID, unused
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        let length = self.0.width();
+
+        if buffer.len() < length + 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::GetRoutingInfo::with_options(self.0.at(buffer, 0), buffer[length] != 0, buffer[length + 1] != 0)))
+    }
+}
+
+struct RoutingInfoSerializer;
+
+impl SerializeFrame for RoutingInfoSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::RoutingInfo>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::RoutingInfo::MESSAGE_TYPE_ID, super::RoutingInfo::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::RoutingInfo>().unwrap();
+
+        try!(serialize_node_mask(message.neighbors(), buffer));
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < NODE_MASK_LENGTH {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::RoutingInfo::new(deserialize_node_mask(&buffer[..NODE_MASK_LENGTH]))))
+    }
+}
+
+
+struct RequestNodeNeighborUpdateSerializer(NodeIdFormat);
+
+impl SerializeFrame for RequestNodeNeighborUpdateSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::RequestNodeNeighborUpdate>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::RequestNodeNeighborUpdate::MESSAGE_TYPE_ID, super::RequestNodeNeighborUpdate::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::RequestNodeNeighborUpdate>().unwrap();
+
+        try!(self.0.push(buffer, message.node_id()));
+        buffer.push(message.callback_id());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        let length = self.0.width();
+
+        if buffer.len() < length + 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::RequestNodeNeighborUpdate::new(self.0.at(buffer, 0), buffer[length])))
+    }
+}
+
+struct NeighborUpdateSerializer;
+
+impl SerializeFrame for NeighborUpdateSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::NeighborUpdate>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::NeighborUpdate::MESSAGE_TYPE_ID, super::NeighborUpdate::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let update = message.downcast_ref::<super::NeighborUpdate>().unwrap();
+
+        buffer.push(update.callback_id());
+        buffer.push(update.raw_status());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::NeighborUpdate::with_status(buffer[0], buffer[1])))
+    }
+}
+
+struct AssignReturnRouteSerializer(NodeIdFormat);
+
+impl SerializeFrame for AssignReturnRouteSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::AssignReturnRoute>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::AssignReturnRoute::MESSAGE_TYPE_ID, super::AssignReturnRoute::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::AssignReturnRoute>().unwrap();
+
+        try!(self.0.push(buffer, message.node_id()));
+        try!(self.0.push(buffer, message.destination()));
+        buffer.push(message.callback_id());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        let length = self.0.width();
+
+        if buffer.len() < 2 * length + 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::AssignReturnRoute::new(self.0.at(buffer, 0), self.0.at(buffer, length), buffer[2 * length])))
+    }
+}
+
+struct AssignReturnRouteResultSerializer;
+
+impl SerializeFrame for AssignReturnRouteResultSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::AssignReturnRouteResult>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::AssignReturnRouteResult::MESSAGE_TYPE_ID, super::AssignReturnRouteResult::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::AssignReturnRouteResult>().unwrap();
+
+        buffer.push(message.result());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::AssignReturnRouteResult::new(buffer[0])))
+    }
+}
+
+struct ReturnRouteAssignedSerializer;
+
+impl SerializeFrame for ReturnRouteAssignedSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::ReturnRouteAssigned>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::ReturnRouteAssigned::MESSAGE_TYPE_ID, super::ReturnRouteAssigned::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::ReturnRouteAssigned>().unwrap();
+
+        buffer.push(message.callback_id());
+        buffer.push(message.status());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::ReturnRouteAssigned::new(buffer[0], buffer[1])))
+    }
+}
+
+
+struct DeleteReturnRouteSerializer(NodeIdFormat);
+
+impl SerializeFrame for DeleteReturnRouteSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::DeleteReturnRoute>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::DeleteReturnRoute::MESSAGE_TYPE_ID, super::DeleteReturnRoute::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::DeleteReturnRoute>().unwrap();
+
+        try!(self.0.push(buffer, message.node_id()));
+        buffer.push(message.callback_id());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        let length = self.0.width();
+
+        if buffer.len() < length + 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::DeleteReturnRoute::new(self.0.at(buffer, 0), buffer[length])))
+    }
+}
+
+struct DeleteReturnRouteResultSerializer;
+
+impl SerializeFrame for DeleteReturnRouteResultSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::DeleteReturnRouteResult>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::DeleteReturnRouteResult::MESSAGE_TYPE_ID, super::DeleteReturnRouteResult::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::DeleteReturnRouteResult>().unwrap();
+
+        buffer.push(message.result());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::DeleteReturnRouteResult::new(buffer[0])))
+    }
+}
+
+struct ReturnRouteDeletedSerializer;
+
+impl SerializeFrame for ReturnRouteDeletedSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::ReturnRouteDeleted>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::ReturnRouteDeleted::MESSAGE_TYPE_ID, super::ReturnRouteDeleted::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::ReturnRouteDeleted>().unwrap();
+
+        buffer.push(message.callback_id());
+        buffer.push(message.status());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::ReturnRouteDeleted::new(buffer[0], buffer[1])))
+    }
+}
+
+struct AssignSucReturnRouteSerializer(NodeIdFormat);
+
+impl SerializeFrame for AssignSucReturnRouteSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::AssignSucReturnRoute>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::AssignSucReturnRoute::MESSAGE_TYPE_ID, super::AssignSucReturnRoute::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::AssignSucReturnRoute>().unwrap();
+
+        try!(self.0.push(buffer, message.node_id()));
+        buffer.push(message.callback_id());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        let length = self.0.width();
+
+        if buffer.len() < length + 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::AssignSucReturnRoute::new(self.0.at(buffer, 0), buffer[length])))
+    }
+}
+
+struct AssignSucReturnRouteResultSerializer;
+
+impl SerializeFrame for AssignSucReturnRouteResultSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::AssignSucReturnRouteResult>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::AssignSucReturnRouteResult::MESSAGE_TYPE_ID, super::AssignSucReturnRouteResult::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::AssignSucReturnRouteResult>().unwrap();
+
+        buffer.push(message.result());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::AssignSucReturnRouteResult::new(buffer[0])))
+    }
+}
+
+struct SucReturnRouteAssignedSerializer;
+
+impl SerializeFrame for SucReturnRouteAssignedSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::SucReturnRouteAssigned>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::SucReturnRouteAssigned::MESSAGE_TYPE_ID, super::SucReturnRouteAssigned::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::SucReturnRouteAssigned>().unwrap();
+
+        buffer.push(message.callback_id());
+        buffer.push(message.status());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::SucReturnRouteAssigned::new(buffer[0], buffer[1])))
+    }
+}
+
+struct DeleteSucReturnRouteSerializer(NodeIdFormat);
+
+impl SerializeFrame for DeleteSucReturnRouteSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::DeleteSucReturnRoute>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::DeleteSucReturnRoute::MESSAGE_TYPE_ID, super::DeleteSucReturnRoute::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::DeleteSucReturnRoute>().unwrap();
+
+        try!(self.0.push(buffer, message.node_id()));
+        buffer.push(message.callback_id());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        let length = self.0.width();
+
+        if buffer.len() < length + 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::DeleteSucReturnRoute::new(self.0.at(buffer, 0), buffer[length])))
+    }
+}
+
+struct DeleteSucReturnRouteResultSerializer;
+
+impl SerializeFrame for DeleteSucReturnRouteResultSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::DeleteSucReturnRouteResult>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::DeleteSucReturnRouteResult::MESSAGE_TYPE_ID, super::DeleteSucReturnRouteResult::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::DeleteSucReturnRouteResult>().unwrap();
+
+        buffer.push(message.result());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::DeleteSucReturnRouteResult::new(buffer[0])))
+    }
+}
+
+struct SucReturnRouteDeletedSerializer;
+
+impl SerializeFrame for SucReturnRouteDeletedSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::SucReturnRouteDeleted>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::SucReturnRouteDeleted::MESSAGE_TYPE_ID, super::SucReturnRouteDeleted::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let message = message.downcast_ref::<super::SucReturnRouteDeleted>().unwrap();
+
+        buffer.push(message.callback_id());
+        buffer.push(message.status());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::SucReturnRouteDeleted::new(buffer[0], buffer[1])))
+    }
+}
+
+
+empty_frame_serializer!(GetControllerCapabilitiesSerializer: GetControllerCapabilities);
+
+struct ControllerCapabilitiesSerializer;
+
+impl SerializeFrame for ControllerCapabilitiesSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::ControllerCapabilities>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::ControllerCapabilities::MESSAGE_TYPE_ID, super::ControllerCapabilities::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let response = message.downcast_ref::<super::ControllerCapabilities>().unwrap();
+
+        buffer.push(response.capabilities());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::ControllerCapabilities::new(buffer[0])))
+    }
+}
+
+empty_frame_serializer!(GetSucNodeIdSerializer: GetSucNodeId);
+
+struct SucNodeIdSerializer(NodeIdFormat);
+
+impl SerializeFrame for SucNodeIdSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::SucNodeId>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::SucNodeId::MESSAGE_TYPE_ID, super::SucNodeId::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let response = message.downcast_ref::<super::SucNodeId>().unwrap();
+
+        try!(self.0.push(buffer, response.node_id().unwrap_or(NodeId(0))));
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < self.0.width() {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let node_id = match self.0.at(buffer, 0) {
+            NodeId(0) => None,
+            node_id => Some(node_id),
+        };
+
+        Ok(AnyMessage::new(super::SucNodeId::new(node_id)))
+    }
+}
+
+const SUC_FUNC_NODE_ID_SERVER: u8 = 0x01;
+
+struct EnableSucSerializer;
+
+impl SerializeFrame for EnableSucSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::EnableSuc>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::EnableSuc::MESSAGE_TYPE_ID, super::EnableSuc::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let request = message.downcast_ref::<super::EnableSuc>().unwrap();
+
+        buffer.push(if request.enable() { 0x01 } else { 0x00 });
+        buffer.push(if request.sis() { SUC_FUNC_NODE_ID_SERVER } else { 0x00 });
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::EnableSuc::new(buffer[0] != 0, buffer[1] & SUC_FUNC_NODE_ID_SERVER != 0)))
+    }
+}
+
+struct EnableSucResultSerializer;
+
+impl SerializeFrame for EnableSucResultSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::EnableSucResult>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::EnableSucResult::MESSAGE_TYPE_ID, super::EnableSucResult::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let response = message.downcast_ref::<super::EnableSucResult>().unwrap();
+
+        buffer.push(response.result());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::EnableSucResult::new(buffer[0])))
+    }
+}
+
+struct SetSucNodeIdSerializer(NodeIdFormat);
+
+impl SerializeFrame for SetSucNodeIdSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::SetSucNodeId>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::SetSucNodeId::MESSAGE_TYPE_ID, super::SetSucNodeId::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let request = message.downcast_ref::<super::SetSucNodeId>().unwrap();
+
+        try!(self.0.push(buffer, request.node_id()));
+        buffer.push(if request.enable() { 0x01 } else { 0x00 });
+        buffer.push(0x00); // normal transmit power
+        buffer.push(if request.sis() { SUC_FUNC_NODE_ID_SERVER } else { 0x00 });
+        buffer.push(request.callback_id());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        let length = self.0.width();
+
+        if buffer.len() < length + 4 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::SetSucNodeId::new(self.0.at(buffer, 0), buffer[length] != 0, buffer[length + 2] & SUC_FUNC_NODE_ID_SERVER != 0, buffer[length + 3])))
+    }
+}
+
+struct SetSucNodeIdResultSerializer;
+
+impl SerializeFrame for SetSucNodeIdResultSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::SetSucNodeIdResult>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::SetSucNodeIdResult::MESSAGE_TYPE_ID, super::SetSucNodeIdResult::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let response = message.downcast_ref::<super::SetSucNodeIdResult>().unwrap();
+
+        buffer.push(response.result());
+
+        Ok(())
     }
 
-    fn serialize(&self, _message: &Message, _buffer: &mut Vec<u8>) -> core::Result<()> {
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::SetSucNodeIdResult::new(buffer[0])))
+    }
+}
+
+struct SucNodeIdSetSerializer;
+
+impl SerializeFrame for SucNodeIdSetSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::SucNodeIdSet>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::SucNodeIdSet::MESSAGE_TYPE_ID, super::SucNodeIdSet::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let callback = message.downcast_ref::<super::SucNodeIdSet>().unwrap();
+
+        buffer.push(callback.callback_id());
+        buffer.push(callback.raw_status());
+
         Ok(())
     }
 
-    fn deserialize(&self, _reader: &mut Read) -> core::Result<AnyMessage> {
-        Ok(AnyMessage::new(Ack::new()))
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::SucNodeIdSet::with_status(buffer[0], buffer[1])))
     }
 }
 
-struct NackSerializer;
 
-impl SerializeMessage for NackSerializer {
-    fn key(&self) -> PreambleId {
-        PreambleId::Nack
+empty_frame_serializer!(GetNvmIdSerializer: GetNvmId);
+
+struct NvmIdSerializer;
+
+impl SerializeFrame for NvmIdSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::NvmId>()
     }
 
-    fn serialize(&self, _message: &Message, _buffer: &mut Vec<u8>) -> core::Result<()> {
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::NvmId::MESSAGE_TYPE_ID, super::NvmId::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let response = message.downcast_ref::<super::NvmId>().unwrap();
+
+        buffer.push(response.manufacturer_id());
+        buffer.push(response.memory_type());
+        buffer.push(response.memory_size());
+
         Ok(())
     }
 
-    fn deserialize(&self, _reader: &mut Read) -> core::Result<AnyMessage> {
-        Ok(AnyMessage::new(Nack::new()))
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 3 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::NvmId::new(buffer[0], buffer[1], buffer[2])))
     }
 }
 
-struct CancelSerializer;
+fn push_u24(buffer: &mut Vec<u8>, value: u32) {
+    buffer.push((value >> 16) as u8);
+    push_u16(buffer, value as u16);
+}
 
-impl SerializeMessage for CancelSerializer {
-    fn key(&self) -> PreambleId {
-        PreambleId::Cancel
+fn u24_at(buffer: &[u8], offset: usize) -> u32 {
+    (buffer[offset] as u32) << 16 | u16_at(buffer, offset + 1) as u32
+}
+
+struct ExtNvmReadLongBufferSerializer;
+
+impl SerializeFrame for ExtNvmReadLongBufferSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::ExtNvmReadLongBuffer>()
     }
 
-    fn serialize(&self, _message: &Message, _buffer: &mut Vec<u8>) -> core::Result<()> {
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::ExtNvmReadLongBuffer::MESSAGE_TYPE_ID, super::ExtNvmReadLongBuffer::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let request = message.downcast_ref::<super::ExtNvmReadLongBuffer>().unwrap();
+
+        push_u24(buffer, request.offset());
+        push_u16(buffer, request.length());
+
         Ok(())
     }
 
-    fn deserialize(&self, _reader: &mut Read) -> core::Result<AnyMessage> {
-        Ok(AnyMessage::new(Cancel::new()))
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 5 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::ExtNvmReadLongBuffer::new(u24_at(buffer, 0), u16_at(buffer, 3))))
     }
 }
 
+struct NvmBufferSerializer;
 
-struct SendDataSerializer(CommandSerializer);
+impl SerializeFrame for NvmBufferSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::NvmBuffer>()
+    }
 
-impl SerializeFrame for SendDataSerializer {
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::NvmBuffer::MESSAGE_TYPE_ID, super::NvmBuffer::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let response = message.downcast_ref::<super::NvmBuffer>().unwrap();
+
+        buffer.extend_from_slice(response.data());
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        Ok(AnyMessage::new(super::NvmBuffer::new(buffer.to_vec())))
+    }
+}
+
+struct ExtNvmWriteLongBufferSerializer;
+
+impl SerializeFrame for ExtNvmWriteLongBufferSerializer {
     fn type_id(&self) -> TypeId {
-        TypeId::of::<super::SendData>()
+        TypeId::of::<super::ExtNvmWriteLongBuffer>()
     }
 
     fn key(&self) -> (MessageTypeId, FunctionId) {
-        (super::SendData::MESSAGE_TYPE_ID, super::SendData::FUNCTION_ID)
+        (super::ExtNvmWriteLongBuffer::MESSAGE_TYPE_ID, super::ExtNvmWriteLongBuffer::FUNCTION_ID)
     }
 
     fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
-        let send_data = message.downcast_ref::<super::SendData>().unwrap();
+        let request = message.downcast_ref::<super::ExtNvmWriteLongBuffer>().unwrap();
 
-        buffer.push(send_data.destination().value());
+        push_u24(buffer, request.offset());
+        push_u16(buffer, request.data().len() as u16);
+        buffer.extend_from_slice(request.data());
 
-        let length_offset = buffer.len();
-        buffer.push(0x00); // payload length; come back when it's known
+        Ok(())
+    }
 
-        let payload_offset = buffer.len();
-        try!(self.0.serialize(send_data.command().borrow(), buffer));
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 5 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
 
-        buffer[length_offset] = (buffer.len() - payload_offset) as u8;
+        let length = u16_at(buffer, 3) as usize;
 
-        buffer.push(send_data.packet_options());
-        buffer.push(send_data.callback_id());
+        if buffer.len() < 5 + length {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::ExtNvmWriteLongBuffer::new(u24_at(buffer, 0), buffer[5..5+length].to_vec())))
+    }
+}
+
+struct ExtNvmWriteLongBufferResultSerializer;
+
+impl SerializeFrame for ExtNvmWriteLongBufferResultSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::ExtNvmWriteLongBufferResult>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::ExtNvmWriteLongBufferResult::MESSAGE_TYPE_ID, super::ExtNvmWriteLongBufferResult::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let response = message.downcast_ref::<super::ExtNvmWriteLongBufferResult>().unwrap();
+
+        buffer.push(response.result());
 
         Ok(())
     }
 
     fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
-        if buffer.len() < 4 {
+        if buffer.len() < 1 {
             return Err(core::Error::new(core::ErrorKind::ShortRead));
         }
 
-        let destination = NodeId(buffer[0]);
-        let payload_length = buffer[1] as usize;
-        let command = try!(self.0.deserialize(&buffer[2..2+payload_length]));
-        let packet_options = buffer[2 + payload_length];
-        let callback_id = buffer[3 + payload_length];
+        Ok(AnyMessage::new(super::ExtNvmWriteLongBufferResult::new(buffer[0])))
+    }
+}
 
-        Ok(AnyMessage::new(super::SendData {
-            destination: destination,
-            command: command,
-            packet_options: packet_options,
-            callback_id: callback_id,
-        }))
+struct NvmBackupRestoreSerializer;
+
+impl SerializeFrame for NvmBackupRestoreSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::NvmBackupRestore>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::NvmBackupRestore::MESSAGE_TYPE_ID, super::NvmBackupRestore::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let request = message.downcast_ref::<super::NvmBackupRestore>().unwrap();
+
+        buffer.push(request.operation() as u8);
+
+        match request.operation() {
+            NvmOperation::Read | NvmOperation::Write => {
+                buffer.push(request.length());
+                push_u16(buffer, request.offset());
+                buffer.extend_from_slice(request.data());
+            },
+            NvmOperation::Open | NvmOperation::Close => (),
+        }
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 1 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let request = match NvmOperation::from_u8(buffer[0]) {
+            Some(NvmOperation::Open) => super::NvmBackupRestore::open(),
+            Some(NvmOperation::Close) => super::NvmBackupRestore::close(),
+            Some(operation) => {
+                if buffer.len() < 4 {
+                    return Err(core::Error::new(core::ErrorKind::ShortRead));
+                }
+
+                let length = buffer[1];
+                let offset = u16_at(buffer, 2);
+
+                if operation == NvmOperation::Read {
+                    super::NvmBackupRestore::read(offset, length)
+                }
+                else if buffer.len() < 4 + length as usize {
+                    return Err(core::Error::new(core::ErrorKind::ShortRead));
+                }
+                else {
+                    super::NvmBackupRestore::write(offset, buffer[4..4+length as usize].to_vec())
+                }
+            },
+            None => return Err(core::Error::new(core::ErrorKind::Protocol)),
+        };
+
+        Ok(AnyMessage::new(request))
     }
 }
 
-struct MessageTransmittedSerializer;
+struct NvmBackupRestoreResultSerializer;
 
-impl SerializeFrame for MessageTransmittedSerializer {
+impl SerializeFrame for NvmBackupRestoreResultSerializer {
     fn type_id(&self) -> TypeId {
-        TypeId::of::<super::MessageTransmitted>()
+        TypeId::of::<super::NvmBackupRestoreResult>()
     }
 
     fn key(&self) -> (MessageTypeId, FunctionId) {
-        (super::MessageTransmitted::MESSAGE_TYPE_ID, super::MessageTransmitted::FUNCTION_ID)
+        (super::NvmBackupRestoreResult::MESSAGE_TYPE_ID, super::NvmBackupRestoreResult::FUNCTION_ID)
     }
 
     fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
-        let message = message.downcast_ref::<super::MessageTransmitted>().unwrap();
+        let response = message.downcast_ref::<super::NvmBackupRestoreResult>().unwrap();
 
-        buffer.push(message.flags());
+        buffer.push(response.raw_status());
+        buffer.push(response.data().len() as u8);
+        push_u16(buffer, response.offset());
+        buffer.extend_from_slice(response.data());
 
         Ok(())
     }
@@ -146,36 +2521,204 @@ impl SerializeFrame for MessageTransmittedSerializer {
             return Err(core::Error::new(core::ErrorKind::ShortRead));
         }
 
-        Ok(AnyMessage::new(super::MessageTransmitted::new(buffer[0])))
+        // closing only reports the status
+        if buffer.len() < 4 {
+            return Ok(AnyMessage::new(super::NvmBackupRestoreResult::with_status(buffer[0], 0, Vec::new())));
+        }
+
+        let length = buffer[1] as usize;
+
+        if buffer.len() < 4 + length {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        Ok(AnyMessage::new(super::NvmBackupRestoreResult::with_status(buffer[0], u16_at(buffer, 2), buffer[4..4+length].to_vec())))
     }
 }
 
-struct MessageReceivedSerializer;
+struct SerialApiSetupSerializer;
 
-impl SerializeFrame for MessageReceivedSerializer {
+impl SerializeFrame for SerialApiSetupSerializer {
     fn type_id(&self) -> TypeId {
-        TypeId::of::<super::MessageReceived>()
+        TypeId::of::<super::SerialApiSetup>()
     }
 
     fn key(&self) -> (MessageTypeId, FunctionId) {
-        (super::MessageReceived::MESSAGE_TYPE_ID, super::MessageReceived::FUNCTION_ID)
+        (super::SerialApiSetup::MESSAGE_TYPE_ID, super::SerialApiSetup::FUNCTION_ID)
     }
 
     fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
-        let message = message.downcast_ref::<super::MessageReceived>().unwrap();
+        use super::SerialApiSetup::*;
 
-        buffer.push(message.callback_id());
-        buffer.push(message.flags());
+        let request = message.downcast_ref::<super::SerialApiSetup>().unwrap();
+
+        buffer.push(request.command() as u8);
+
+        match *request {
+            SetRfRegion(region) => buffer.push(region as u8),
+            SetTxPower(tx_power) => {
+                buffer.push(tx_power.normal() as u8);
+                buffer.push(tx_power.measured_0dbm() as u8);
+            },
+            SetLongRangeMaxTxPower(power) => push_u16(buffer, power as u16),
+            SetNodeIdType(node_id_type) => buffer.push(node_id_type as u8),
+            GetSupportedCommands | GetRfRegion | GetTxPower | GetLongRangeMaxTxPower => (),
+        }
 
         Ok(())
     }
 
     fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        use super::SerialApiSetup::*;
+
         if buffer.len() < 1 {
             return Err(core::Error::new(core::ErrorKind::ShortRead));
         }
 
-        Ok(AnyMessage::new(super::MessageReceived::new(buffer[0], buffer[1])))
+        let command = match SerialApiSetupCommand::from_u8(buffer[0]) {
+            Some(command) => command,
+            None => return Err(core::Error::new(core::ErrorKind::Protocol)),
+        };
+
+        let length = match command {
+            SerialApiSetupCommand::SetTxPower | SerialApiSetupCommand::SetLongRangeMaxTxPower => 3,
+            SerialApiSetupCommand::SetRfRegion | SerialApiSetupCommand::SetNodeIdType => 2,
+            _ => 1,
+        };
+
+        if buffer.len() < length {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let request = match command {
+            SerialApiSetupCommand::GetSupportedCommands => GetSupportedCommands,
+            SerialApiSetupCommand::GetRfRegion => GetRfRegion,
+            SerialApiSetupCommand::SetRfRegion => match RfRegion::from_u8(buffer[1]) {
+                Some(region) => SetRfRegion(region),
+                None => return Err(core::Error::new(core::ErrorKind::Protocol)),
+            },
+            SerialApiSetupCommand::GetTxPower => GetTxPower,
+            SerialApiSetupCommand::SetTxPower => SetTxPower(super::TxPower::new(buffer[1] as i8, buffer[2] as i8)),
+            SerialApiSetupCommand::GetLongRangeMaxTxPower => GetLongRangeMaxTxPower,
+            SerialApiSetupCommand::SetLongRangeMaxTxPower => SetLongRangeMaxTxPower(u16_at(buffer, 1) as i16),
+            SerialApiSetupCommand::SetNodeIdType => match NodeIdType::from_u8(buffer[1]) {
+                Some(node_id_type) => SetNodeIdType(node_id_type),
+                None => return Err(core::Error::new(core::ErrorKind::Protocol)),
+            },
+            SerialApiSetupCommand::Unsupported => return Err(core::Error::new(core::ErrorKind::Protocol)),
+        };
+
+        Ok(AnyMessage::new(request))
+    }
+}
+
+struct SerialApiSetupResultSerializer;
+
+impl SerializeFrame for SerialApiSetupResultSerializer {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<super::SerialApiSetupResult>()
+    }
+
+    fn key(&self) -> (MessageTypeId, FunctionId) {
+        (super::SerialApiSetupResult::MESSAGE_TYPE_ID, super::SerialApiSetupResult::FUNCTION_ID)
+    }
+
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
+        let response = message.downcast_ref::<super::SerialApiSetupResult>().unwrap();
+
+        match *response {
+            super::SerialApiSetupResult::Unsupported(command) => {
+                buffer.push(SerialApiSetupCommand::Unsupported as u8);
+                buffer.push(command);
+            },
+            super::SerialApiSetupResult::SupportedCommands(ref commands) => {
+                buffer.push(SerialApiSetupCommand::GetSupportedCommands as u8);
+
+                // The first byte only covers the subcommands whose IDs are single bits, while
+                // the mask after it has a bit for every subcommand ID.
+                let highest = commands.iter().map(|&command| command as usize).max().unwrap_or(0);
+                let offset = buffer.len();
+                buffer.push(0x00);
+                buffer.extend_from_slice(&vec![0u8; highest / 8 + 1]);
+
+                for &command in commands {
+                    let value = command as u8;
+
+                    if value.count_ones() == 1 {
+                        buffer[offset] |= value;
+                    }
+
+                    buffer[offset + 1 + value as usize / 8] |= 1 << (value % 8);
+                }
+            },
+            super::SerialApiSetupResult::RfRegion(region) => {
+                buffer.push(SerialApiSetupCommand::GetRfRegion as u8);
+                buffer.push(region as u8);
+            },
+            super::SerialApiSetupResult::TxPower(tx_power) => {
+                buffer.push(SerialApiSetupCommand::GetTxPower as u8);
+                buffer.push(tx_power.normal() as u8);
+                buffer.push(tx_power.measured_0dbm() as u8);
+            },
+            super::SerialApiSetupResult::LongRangeMaxTxPower(power) => {
+                buffer.push(SerialApiSetupCommand::GetLongRangeMaxTxPower as u8);
+                push_u16(buffer, power as u16);
+            },
+            super::SerialApiSetupResult::Set(command, accepted) => {
+                buffer.push(command as u8);
+                buffer.push(if accepted { 0x01 } else { 0x00 });
+            },
+        }
+
+        Ok(())
+    }
+
+    fn deserialize(&self, buffer: &[u8]) -> core::Result<AnyMessage> {
+        if buffer.len() < 2 {
+            return Err(core::Error::new(core::ErrorKind::ShortRead));
+        }
+
+        let response = match SerialApiSetupCommand::from_u8(buffer[0]) {
+            Some(SerialApiSetupCommand::Unsupported) => super::SerialApiSetupResult::Unsupported(buffer[1]),
+            Some(SerialApiSetupCommand::GetSupportedCommands) => {
+                let mut values = BTreeSet::<u8>::new();
+
+                for bit in 0..8 {
+                    if buffer[1] & (1 << bit) != 0 {
+                        values.insert(1 << bit);
+                    }
+                }
+
+                for (index, byte) in buffer[2..].iter().enumerate() {
+                    for bit in 0..8 {
+                        if byte & (1 << bit) != 0 {
+                            values.insert((index * 8 + bit) as u8);
+                        }
+                    }
+                }
+
+                super::SerialApiSetupResult::SupportedCommands(values.into_iter().filter_map(SerialApiSetupCommand::from_u8).filter(|&command| command != SerialApiSetupCommand::Unsupported).collect())
+            },
+            Some(SerialApiSetupCommand::GetRfRegion) => super::SerialApiSetupResult::RfRegion(RfRegion::from_u8(buffer[1]).unwrap_or(RfRegion::Unknown)),
+            Some(SerialApiSetupCommand::GetTxPower) => {
+                if buffer.len() < 3 {
+                    return Err(core::Error::new(core::ErrorKind::ShortRead));
+                }
+
+                super::SerialApiSetupResult::TxPower(super::TxPower::new(buffer[1] as i8, buffer[2] as i8))
+            },
+            Some(SerialApiSetupCommand::GetLongRangeMaxTxPower) => {
+                if buffer.len() < 3 {
+                    return Err(core::Error::new(core::ErrorKind::ShortRead));
+                }
+
+                super::SerialApiSetupResult::LongRangeMaxTxPower(u16_at(buffer, 1) as i16)
+            },
+            Some(command) => super::SerialApiSetupResult::Set(command, buffer[1] != 0x00),
+            None => return Err(core::Error::new(core::ErrorKind::Protocol)),
+        };
+
+        Ok(AnyMessage::new(response))
     }
 }
 
@@ -193,19 +2736,91 @@ impl FrameSerializer {
         }
     }
 
-    fn for_request() -> Self {
+    fn for_request(node_ids: &NodeIdFormat) -> Self {
         let mut serializer = Self::new();
 
-        serializer.register(SendDataSerializer(CommandSerializer::new()));
+        serializer.register(SendDataSerializer(CommandSerializer::new(), node_ids.clone()));
+        serializer.register(SendDataMultiSerializer(CommandSerializer::new(), node_ids.clone()));
+        serializer.register(SendDataAbortSerializer);
+        serializer.register(GetVersionSerializer);
+        serializer.register(MemoryGetIdSerializer);
+        serializer.register(SerialApiGetCapabilitiesSerializer);
+        serializer.register(SerialApiGetInitDataSerializer);
+        serializer.register(GetNodeProtocolInfoSerializer(node_ids.clone()));
+        serializer.register(AddNodeToNetworkSerializer);
+        serializer.register(RemoveNodeFromNetworkSerializer);
+        serializer.register(SetLearnModeSerializer);
+        serializer.register(SetDefaultSerializer);
+        serializer.register(SoftResetSerializer);
+        serializer.register(IsFailedNodeSerializer(node_ids.clone()));
+        serializer.register(RemoveFailedNodeSerializer(node_ids.clone()));
+        serializer.register(ReplaceFailedNodeSerializer(node_ids.clone()));
+        serializer.register(GetRoutingInfoSerializer(node_ids.clone()));
+        serializer.register(RequestNodeNeighborUpdateSerializer(node_ids.clone()));
+        serializer.register(AssignReturnRouteSerializer(node_ids.clone()));
+        serializer.register(DeleteReturnRouteSerializer(node_ids.clone()));
+        serializer.register(AssignSucReturnRouteSerializer(node_ids.clone()));
+        serializer.register(DeleteSucReturnRouteSerializer(node_ids.clone()));
+        serializer.register(GetSucNodeIdSerializer);
+        serializer.register(GetControllerCapabilitiesSerializer);
+        serializer.register(EnableSucSerializer);
+        serializer.register(SetSucNodeIdSerializer(node_ids.clone()));
+        serializer.register(GetNvmIdSerializer);
+        serializer.register(ExtNvmReadLongBufferSerializer);
+        serializer.register(ExtNvmWriteLongBufferSerializer);
+        serializer.register(NvmBackupRestoreSerializer);
+        serializer.register(SerialApiSetupSerializer);
+        serializer.register(GetLongRangeNodesSerializer);
 
         serializer
     }
 
-    fn for_response() -> Self {
+    fn for_response(node_ids: &NodeIdFormat) -> Self {
         let mut serializer = Self::new();
 
         serializer.register(MessageTransmittedSerializer);
         serializer.register(MessageReceivedSerializer);
+        serializer.register(MulticastTransmittedSerializer);
+        serializer.register(MulticastCompletedSerializer);
+        serializer.register(ApplicationCommandHandlerSerializer(CommandSerializer::new(), node_ids.clone()));
+        serializer.register(ApplicationUpdateSerializer(node_ids.clone()));
+        serializer.register(VersionSerializer);
+        serializer.register(MemoryIdSerializer(node_ids.clone()));
+        serializer.register(SerialApiCapabilitiesSerializer);
+        serializer.register(SerialApiInitDataSerializer);
+        serializer.register(NodeProtocolInfoSerializer);
+        serializer.register(AddNodeUpdateSerializer(node_ids.clone()));
+        serializer.register(RemoveNodeUpdateSerializer(node_ids.clone()));
+        serializer.register(LearnModeResultSerializer);
+        serializer.register(LearnModeUpdateSerializer(node_ids.clone()));
+        serializer.register(DefaultSetSerializer);
+        serializer.register(SerialApiStartedSerializer);
+        serializer.register(IsFailedNodeResultSerializer);
+        serializer.register(RemoveFailedNodeResultSerializer);
+        serializer.register(RemoveFailedNodeUpdateSerializer);
+        serializer.register(ReplaceFailedNodeResultSerializer);
+        serializer.register(ReplaceFailedNodeUpdateSerializer);
+        serializer.register(RoutingInfoSerializer);
+        serializer.register(NeighborUpdateSerializer);
+        serializer.register(AssignReturnRouteResultSerializer);
+        serializer.register(ReturnRouteAssignedSerializer);
+        serializer.register(DeleteReturnRouteResultSerializer);
+        serializer.register(ReturnRouteDeletedSerializer);
+        serializer.register(AssignSucReturnRouteResultSerializer);
+        serializer.register(SucReturnRouteAssignedSerializer);
+        serializer.register(DeleteSucReturnRouteResultSerializer);
+        serializer.register(SucReturnRouteDeletedSerializer);
+        serializer.register(SucNodeIdSerializer(node_ids.clone()));
+        serializer.register(ControllerCapabilitiesSerializer);
+        serializer.register(EnableSucResultSerializer);
+        serializer.register(SetSucNodeIdResultSerializer);
+        serializer.register(SucNodeIdSetSerializer);
+        serializer.register(NvmIdSerializer);
+        serializer.register(NvmBufferSerializer);
+        serializer.register(ExtNvmWriteLongBufferResultSerializer);
+        serializer.register(NvmBackupRestoreResultSerializer);
+        serializer.register(SerialApiSetupResultSerializer);
+        serializer.register(LongRangeNodesSerializer);
 
         serializer
     }
@@ -234,6 +2849,11 @@ impl SerializeMessage for FrameSerializer {
 
         try!(serializer.serialize(message, buffer));
 
+        // the length has to fit into one byte, so longer frames can't be sent at all
+        if buffer.len() - length_index > 0xFF {
+            return Err(core::Error::new(core::ErrorKind::Unsupported));
+        }
+
         buffer[length_index] = (buffer.len() - length_index) as u8; // set frame length
 
         let parity = buffer.iter().skip(length_index).fold(0xFF, |acc, &x| acc ^ x);
@@ -248,7 +2868,7 @@ impl SerializeMessage for FrameSerializer {
 
         let parity = buffer.iter().fold(0xFF ^ length as u8, |acc, &x| acc ^ x);
 
-        if parity != 0 {
+        if parity != 0 || length < 3 {
             return Err(core::Error::new(core::ErrorKind::Corrupt));
         }
 
@@ -257,7 +2877,13 @@ impl SerializeMessage for FrameSerializer {
                 match self.types.get(&(message_type_id, function_id)) {
                     Some(type_id) => {
                         match self.serializers.get(type_id) {
-                            Some(serializer) => serializer.deserialize(&buffer[2..length-1]),
+                            Some(serializer) => {
+                                // the frame arrived intact, so a payload that can't be decoded is a
+                                // protocol error rather than a transmission error
+                                serializer.deserialize(&buffer[2..length-1]).map_err(|_| {
+                                    core::Error::new(core::ErrorKind::Protocol)
+                                })
+                            },
                             None => Err(core::Error::new(core::ErrorKind::Protocol)),
                         }
                     },
@@ -272,12 +2898,14 @@ impl SerializeMessage for FrameSerializer {
 
 pub struct MessageSerializer {
     serializers: HashMap<PreambleId, Box<SerializeMessage>>,
+    node_ids: NodeIdFormat,
 }
 
 impl MessageSerializer {
-    fn new() -> Self {
+    fn new(node_ids: NodeIdFormat) -> Self {
         let mut serializer = MessageSerializer {
             serializers: HashMap::<PreambleId, Box<SerializeMessage>>::new(),
+            node_ids: node_ids,
         };
 
         serializer.register(AckSerializer);
@@ -288,21 +2916,29 @@ impl MessageSerializer {
     }
 
     pub fn for_request() -> Self {
-        let mut serializer = Self::new();
+        let node_ids = NodeIdFormat::new();
+        let mut serializer = Self::new(node_ids.clone());
 
-        serializer.register(FrameSerializer::for_request());
+        serializer.register(FrameSerializer::for_request(&node_ids));
 
         serializer
     }
 
     pub fn for_response() -> Self {
-        let mut serializer = Self::new();
+        let node_ids = NodeIdFormat::new();
+        let mut serializer = Self::new(node_ids.clone());
 
-        serializer.register(FrameSerializer::for_response());
+        serializer.register(FrameSerializer::for_response(&node_ids));
 
         serializer
     }
 
+    /// Switches between one-byte and two-byte node IDs, which has to match what the controller
+    /// was set up for.
+    pub fn set_node_id_type(&mut self, node_id_type: NodeIdType) {
+        self.node_ids.set(node_id_type);
+    }
+
     pub fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> core::Result<()> {
         buffer.push(message.preamble_id() as u8);
 
@@ -311,13 +2947,13 @@ impl MessageSerializer {
         serializer.serialize(message, buffer)
     }
 
+    /// Deserializes the next message, discarding any bytes that precede a valid preamble.
     pub fn deserialize(&self, reader: &mut Read) -> core::Result<AnyMessage> {
-        match PreambleId::from_u8(try!(reader.read_u8())) {
-            Some(preamble_id) => {
+        loop {
+            if let Some(preamble_id) = PreambleId::from_u8(try!(reader.read_u8())) {
                 let serializer = self.serializers.get(&preamble_id).unwrap();
-                serializer.deserialize(reader)
-            },
-            None => Err(core::Error::new(core::ErrorKind::Protocol)),
+                return serializer.deserialize(reader);
+            }
         }
     }
 
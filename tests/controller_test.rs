@@ -6,18 +6,19 @@ use std::thread;
 use std::time::Duration;
 
 use zwave::core::{self, Error, ErrorKind};
-use zwave::protocol::message::{Message, AnyMessage};
+use zwave::protocol::message::{Message, AnyMessage, Ack, Nack};
 use zwave::io::driver::Driver;
+use zwave::io::controller::{Controller, RetryPolicy};
 
 struct DriverMock {
-    send: VecDeque<(Box<Fn(&Message) -> core::Result<()> + Send>, Option<core::Result<AnyMessage>>)>,
+    send: VecDeque<(Box<Fn(&Message) -> core::Result<()> + Send>, Vec<core::Result<AnyMessage>>)>,
     receive: VecDeque<core::Result<AnyMessage>>,
 }
 
 impl DriverMock {
     fn new() -> Self {
         DriverMock {
-            send: VecDeque::<(Box<Fn(&Message) -> core::Result<()> + Send>, Option<core::Result<AnyMessage>>)>::new(),
+            send: VecDeque::<(Box<Fn(&Message) -> core::Result<()> + Send>, Vec<core::Result<AnyMessage>>)>::new(),
             receive: VecDeque::<core::Result<AnyMessage>>::new(),
         }
     }
@@ -39,12 +40,30 @@ impl FakeDriver {
 
     fn expect_send<F: Fn(&Message) -> core::Result<()> + Send + 'static>(&mut self, f: F) {
         let mut mock = self.mock.lock().unwrap();
-        mock.send.push_back((Box::new(f), None));
+        mock.send.push_back((Box::new(f), vec![]));
     }
 
     fn expect_send_with_response<F: Fn(&Message) -> core::Result<()> + Send + 'static>(&mut self, f: F, response: core::Result<AnyMessage>) {
+        self.expect_send_with_responses(f, vec![response]);
+    }
+
+    fn expect_send_with_responses<F: Fn(&Message) -> core::Result<()> + Send + 'static>(&mut self, f: F, responses: Vec<core::Result<AnyMessage>>) {
         let mut mock = self.mock.lock().unwrap();
-        mock.send.push_back((Box::new(f), Some(response)));
+        mock.send.push_back((Box::new(f), responses));
+    }
+
+    fn expect_ack(&mut self) {
+        self.expect_send(|message| {
+            assert!(message.is::<Ack>());
+            Ok(())
+        });
+    }
+
+    fn expect_nack(&mut self) {
+        self.expect_send(|message| {
+            assert!(message.is::<Nack>());
+            Ok(())
+        });
     }
 
     fn push_response(&mut self, response: core::Result<AnyMessage>) {
@@ -74,13 +93,11 @@ impl FakeDriver {
 impl Driver for FakeDriver {
     fn send(&mut self, message: &Message) -> core::Result<()> {
         let mut mock = self.mock.lock().unwrap();
-        let (f, response) = mock.send.pop_front().expect("unexpected call to send()");
+        let (f, responses) = mock.send.pop_front().expect("unexpected call to send()");
 
         let retval = f(message);
 
-        if let Some(res) = response {
-            mock.receive.push_back(res);
-        }
+        mock.receive.extend(responses);
 
         retval
     }
@@ -102,26 +119,27 @@ impl Driver for FakeDriver {
     }
 }
 
-mod send_data {
-    use std::thread;
-    use std::time::Duration;
+fn with_fake_driver<F: FnOnce(&mut FakeDriver, &mut Controller<FakeDriver>) -> ()>(f: F) {
+    let mut driver = FakeDriver::new();
+    let mut controller = Controller::new(driver.clone());
 
-    use zwave::core::{NodeId, Error, ErrorKind};
-    use zwave::protocol::message::{AnyMessage, Ack, Nack, Cancel, SendData};
-    use zwave::protocol::command::basic::SetValue;
-    use zwave::io::controller::Controller;
+    controller.set_retry_policy(RetryPolicy::new(0, Duration::from_millis(100)));
+    controller.set_response_timeout(Duration::from_millis(100));
+    controller.set_callback_timeout(Duration::from_millis(100));
 
-    use super::FakeDriver;
+    f(&mut driver, &mut controller);
 
-    fn with_fake_driver<F: FnOnce(&mut FakeDriver, &mut Controller<FakeDriver>) -> ()>(f: F) {
-        let mut driver = FakeDriver::new();
-        let mut controller = Controller::new(driver.clone());
+    controller.stop();
+    driver.verify();
+}
 
-        f(&mut driver, &mut controller);
+mod send_data {
+    use zwave::core::{NodeId, Error, ErrorKind};
+    use zwave::protocol::bits::TransmitStatus;
+    use zwave::protocol::message::{AnyMessage, Ack, Nack, Cancel, SendData, MessageTransmitted, MessageReceived};
+    use zwave::protocol::command::basic::SetValue;
 
-        controller.stop();
-        driver.verify();
-    }
+    use super::{FakeDriver, with_fake_driver};
 
     #[test]
     fn it_sends_send_data_message() {
@@ -185,14 +203,130 @@ mod send_data {
         });
     }
 
+    fn expect_transmission(driver: &mut FakeDriver, callback_id: u8, flags: u8) {
+        driver.expect_send_with_responses(|_| {
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(MessageTransmitted::new(0x01))),
+            Ok(AnyMessage::new(MessageReceived::new(callback_id, flags))),
+        ]);
+
+        driver.expect_ack();
+        driver.expect_ack();
+    }
+
     #[test]
-    fn it_returns_ok_if_reply_is_ack() {
+    fn it_returns_ok_if_transmission_is_complete() {
         with_fake_driver(|driver, controller| {
-            driver.expect_send_with_response(|_| {
+            expect_transmission(driver, 0x01, 0x00);
+
+            assert_eq!(Ok(TransmitStatus::Ok), controller.send_data(NodeId(42), SetValue::new(42)));
+        });
+    }
+
+    #[test]
+    fn it_returns_transmit_status_from_callback() {
+        with_fake_driver(|driver, controller| {
+            expect_transmission(driver, 0x01, 0x01);
+            assert_eq!(Ok(TransmitStatus::NoAck), controller.send_data(NodeId(42), SetValue::new(42)));
+
+            expect_transmission(driver, 0x02, 0x02);
+            assert_eq!(Ok(TransmitStatus::Fail), controller.send_data(NodeId(42), SetValue::new(42)));
+
+            expect_transmission(driver, 0x03, 0x03);
+            assert_eq!(Ok(TransmitStatus::RoutingNotIdle), controller.send_data(NodeId(42), SetValue::new(42)));
+
+            expect_transmission(driver, 0x04, 0x04);
+            assert_eq!(Ok(TransmitStatus::NoRoute), controller.send_data(NodeId(42), SetValue::new(42)));
+        });
+    }
+
+    #[test]
+    fn it_returns_protocol_error_if_transmit_status_is_unknown() {
+        with_fake_driver(|driver, controller| {
+            expect_transmission(driver, 0x01, 0x2A);
+
+            assert_eq!(Err(Error::new(ErrorKind::Protocol)), controller.send_data(NodeId(42), SetValue::new(42)));
+        });
+    }
+
+    #[test]
+    fn it_uses_rolling_callback_ids() {
+        with_fake_driver(|driver, controller| {
+            for callback_id in 1..256 {
+                let expected = callback_id as u8;
+
+                driver.expect_send_with_responses(move |message| {
+                    assert_eq!(expected, message.downcast_ref::<SendData>().unwrap().callback_id());
+                    Ok(())
+                }, vec![
+                    Ok(AnyMessage::new(Ack::new())),
+                    Ok(AnyMessage::new(MessageTransmitted::new(0x01))),
+                    Ok(AnyMessage::new(MessageReceived::new(expected, 0x00))),
+                ]);
+
+                driver.expect_ack();
+                driver.expect_ack();
+
+                assert_eq!(Ok(TransmitStatus::Ok), controller.send_data(NodeId(42), SetValue::new(42)));
+            }
+
+            // callback ID 0 means no callback, so it wraps around to 1
+            expect_transmission(driver, 0x01, 0x00);
+            assert_eq!(Ok(TransmitStatus::Ok), controller.send_data(NodeId(42), SetValue::new(42)));
+        });
+    }
+
+    #[test]
+    fn it_ignores_callbacks_with_other_callback_ids() {
+        with_fake_driver(|driver, controller| {
+            driver.expect_send_with_responses(|_| {
                 Ok(())
-            }, Ok(AnyMessage::new(Ack::new())));
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(MessageTransmitted::new(0x01))),
+                Ok(AnyMessage::new(MessageReceived::new(0x2A, 0x01))),
+                Ok(AnyMessage::new(MessageReceived::new(0x01, 0x00))),
+            ]);
+
+            driver.expect_ack();
+            driver.expect_ack();
+            driver.expect_ack();
+
+            assert_eq!(Ok(TransmitStatus::Ok), controller.send_data(NodeId(42), SetValue::new(42)));
+        });
+    }
+
+    #[test]
+    fn it_returns_rejected_error_if_message_is_not_transmitted() {
+        with_fake_driver(|driver, controller| {
+            driver.expect_send_with_responses(|_| {
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(MessageTransmitted::new(0x00))),
+            ]);
+
+            driver.expect_ack();
+
+            assert_eq!(Err(Error::new(ErrorKind::Rejected)), controller.send_data(NodeId(42), SetValue::new(42)));
+        });
+    }
+
+    #[test]
+    fn it_returns_timeout_error_if_no_callback_is_received() {
+        with_fake_driver(|driver, controller| {
+            driver.expect_send_with_responses(|_| {
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(MessageTransmitted::new(0x01))),
+            ]);
 
-            assert_eq!(Ok(()), controller.send_data(NodeId(42), SetValue::new(42)));
+            driver.expect_ack();
+
+            assert_eq!(Err(Error::new(ErrorKind::Timeout)), controller.send_data(NodeId(42), SetValue::new(42)));
         });
     }
 
@@ -235,11 +369,2615 @@ mod send_data {
             driver.push_response(Ok(AnyMessage::new(Cancel::new())));
             driver.wait_for_receive();
 
+            expect_transmission(driver, 0x02, 0x00);
+
+            assert_eq!(Ok(TransmitStatus::Ok), controller.send_data(NodeId(42), SetValue::new(42)));
+        });
+    }
+}
+
+mod send_data_abort {
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use zwave::core::{NodeId, Error, ErrorKind};
+    use zwave::protocol::bits::TransmitStatus;
+    use zwave::protocol::message::{AnyMessage, Ack, Nack, SendData, SendDataAbort, MessageTransmitted, MessageReceived};
+    use zwave::protocol::command::basic::SetValue;
+    use zwave::io::controller::RetryPolicy;
+
+    use super::{FakeDriver, with_fake_driver};
+
+    fn expect_send_data(driver: &mut FakeDriver, responses: Vec<AnyMessage>) {
+        let acks = responses.len() - 1;
+
+        driver.expect_send_with_responses(|message| {
+            assert!(message.is::<SendData>());
+            Ok(())
+        }, responses.into_iter().map(Ok).collect());
+
+        for _ in 0..acks {
+            driver.expect_ack();
+        }
+    }
+
+    #[test]
+    fn it_aborts_pending_transmission() {
+        with_fake_driver(|driver, controller| {
+            controller.set_callback_timeout(Duration::from_secs(10));
+
+            expect_send_data(driver, vec![AnyMessage::new(Ack::new()), AnyMessage::new(MessageTransmitted::new(0x01))]);
+
+            driver.expect_send_with_response(|message| {
+                assert!(message.is::<SendDataAbort>());
+                Ok(())
+            }, Ok(AnyMessage::new(Ack::new())));
+
+            let handle = controller.abort_handle();
+
+            let aborter = thread::spawn(move || {
+                thread::sleep(Duration::from_millis(50));
+                handle.abort();
+            });
+
+            let started = Instant::now();
+
+            assert_eq!(Err(Error::new(ErrorKind::Aborted)), controller.send_data(NodeId(2), SetValue::new(42)));
+            assert!(started.elapsed() < Duration::from_secs(5));
+
+            aborter.join().unwrap();
+        });
+    }
+
+    #[test]
+    fn it_does_not_retransmit_after_abort_during_backoff() {
+        with_fake_driver(|driver, controller| {
+            controller.set_retry_policy(RetryPolicy::with_backoff(3, Duration::from_millis(100), Duration::from_secs(2), Duration::from_secs(1)));
+
+            driver.expect_send_with_response(|message| {
+                assert!(message.is::<SendData>());
+                Ok(())
+            }, Ok(AnyMessage::new(Nack::new())));
+
+            driver.expect_send_with_response(|message| {
+                assert!(message.is::<SendDataAbort>());
+                Ok(())
+            }, Ok(AnyMessage::new(Ack::new())));
+
+            let handle = controller.abort_handle();
+
+            let aborter = thread::spawn(move || {
+                thread::sleep(Duration::from_millis(50));
+                handle.abort();
+            });
+
+            let started = Instant::now();
+
+            assert_eq!(Err(Error::new(ErrorKind::Aborted)), controller.send_data(NodeId(2), SetValue::new(42)));
+            assert!(started.elapsed() < Duration::from_secs(1));
+            assert_eq!(1, controller.attempts());
+
+            aborter.join().unwrap();
+        });
+    }
+
+    #[test]
+    fn it_ignores_abort_without_pending_transmission() {
+        with_fake_driver(|driver, controller| {
+            controller.abort_handle().abort();
+
+            expect_send_data(driver, vec![
+                AnyMessage::new(Ack::new()),
+                AnyMessage::new(MessageTransmitted::new(0x01)),
+                AnyMessage::new(MessageReceived::new(0x01, 0x00)),
+            ]);
+
+            assert_eq!(Ok(TransmitStatus::Ok), controller.send_data(NodeId(2), SetValue::new(42)));
+        });
+    }
+}
+
+mod broadcast {
+    use zwave::core::NodeId;
+    use zwave::io::controller::BroadcastStatus;
+    use zwave::protocol::bits::TransmitStatus;
+    use zwave::protocol::message::{AnyMessage, Ack, SendData, MessageTransmitted, MessageReceived};
+    use zwave::protocol::command::basic::SetValue;
+
+    use super::{FakeDriver, with_fake_driver};
+
+    fn expect_broadcast(driver: &mut FakeDriver, status: u8) {
+        driver.expect_send_with_responses(|message| {
+            let request = message.downcast_ref::<SendData>().unwrap();
+            assert_eq!(NodeId(0xFF), request.destination());
+            assert_eq!(0x00, request.packet_options());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(MessageTransmitted::new(0x01))),
+            Ok(AnyMessage::new(MessageReceived::new(0x01, status))),
+        ]);
+
+        driver.expect_ack();
+        driver.expect_ack();
+    }
+
+    #[test]
+    fn it_broadcasts_without_asking_for_acks() {
+        with_fake_driver(|driver, controller| {
+            expect_broadcast(driver, 0x00);
+
+            assert_eq!(Ok(BroadcastStatus::Transmitted), controller.broadcast(SetValue::new(0)));
+        });
+    }
+
+    #[test]
+    fn it_reports_broadcast_that_did_not_go_out() {
+        with_fake_driver(|driver, controller| {
+            expect_broadcast(driver, 0x02);
+
+            assert_eq!(Ok(BroadcastStatus::NotTransmitted(TransmitStatus::Fail)), controller.broadcast(SetValue::new(0)));
+        });
+    }
+
+    #[test]
+    fn it_does_not_ask_for_acks_when_sending_to_broadcast_node_id() {
+        with_fake_driver(|driver, controller| {
+            expect_broadcast(driver, 0x00);
+
+            assert_eq!(Ok(TransmitStatus::Ok), controller.send_data(NodeId::BROADCAST, SetValue::new(0)));
+        });
+    }
+}
+
+mod send_data_multi {
+    use zwave::core::{NodeId, Error, ErrorKind};
+    use zwave::protocol::bits::TransmitStatus;
+    use zwave::protocol::message::{AnyMessage, Ack, SendData, MessageTransmitted, MessageReceived};
+    use zwave::protocol::message::{SendDataMulti, MulticastTransmitted, MulticastCompleted};
+    use zwave::protocol::command::basic::SetValue;
+
+    use super::{FakeDriver, with_fake_driver};
+
+    fn expect_multicast(driver: &mut FakeDriver, callback_id: u8) {
+        driver.expect_send_with_responses(move |message| {
+            let request = message.downcast_ref::<SendDataMulti>().unwrap();
+            assert_eq!(&[NodeId(2), NodeId(3), NodeId(4)], request.destinations());
+            assert_eq!(0, request.command().downcast_ref::<SetValue>().unwrap().value());
+            assert_eq!(callback_id, request.callback_id());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(MulticastTransmitted::new(0x01))),
+            Ok(AnyMessage::new(MulticastCompleted::new(callback_id, 0x00))),
+        ]);
+
+        driver.expect_ack();
+        driver.expect_ack();
+    }
+
+    #[test]
+    fn it_sends_command_to_all_nodes_at_once() {
+        with_fake_driver(|driver, controller| {
+            expect_multicast(driver, 0x01);
+
+            assert_eq!(Ok(TransmitStatus::Ok), controller.send_data_multi(&[NodeId(2), NodeId(3), NodeId(4)], SetValue::new(0)));
+        });
+    }
+
+    #[test]
+    fn it_returns_rejected_error_if_multicast_is_refused() {
+        with_fake_driver(|driver, controller| {
+            driver.expect_send_with_responses(|_| {
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(MulticastTransmitted::new(0x00))),
+            ]);
+
+            driver.expect_ack();
+
+            assert_eq!(Err(Error::new(ErrorKind::Rejected)), controller.send_data_multi(&[NodeId(2), NodeId(3), NodeId(4)], SetValue::new(0)));
+        });
+    }
+
+    #[test]
+    fn it_follows_up_with_singlecast_to_confirmed_nodes() {
+        with_fake_driver(|driver, controller| {
+            expect_multicast(driver, 0x01);
+
+            driver.expect_send_with_responses(|message| {
+                let request = message.downcast_ref::<SendData>().unwrap();
+                assert_eq!(NodeId(3), request.destination());
+                assert_eq!(0, request.command().downcast_ref::<SetValue>().unwrap().value());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(MessageTransmitted::new(0x01))),
+                Ok(AnyMessage::new(MessageReceived::new(0x02, 0x01))),
+            ]);
+
+            driver.expect_ack();
+            driver.expect_ack();
+
+            let (status, results) = controller.send_data_multi_confirmed(&[NodeId(2), NodeId(3), NodeId(4)], SetValue::new(0), &[NodeId(3)]).unwrap();
+
+            assert_eq!(TransmitStatus::Ok, status);
+            assert_eq!(vec![(NodeId(3), Ok(TransmitStatus::NoAck))], results);
+        });
+    }
+}
+
+mod request {
+    use zwave::core::{NodeId, Error, ErrorKind};
+    use zwave::protocol::message::{AnyMessage, Ack, SendData, MessageTransmitted, MessageReceived};
+    use zwave::protocol::command::basic::SetValue;
+
+    use super::with_fake_driver;
+
+    #[test]
+    fn it_returns_response() {
+        with_fake_driver(|driver, controller| {
+            driver.expect_send_with_responses(|message| {
+                assert!(message.is::<SendData>());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(MessageTransmitted::new(0x2A))),
+            ]);
+
+            driver.expect_ack();
+
+            let response = controller.request(SendData::new(NodeId(42), SetValue::new(42), 0x00)).unwrap();
+
+            assert_eq!(0x2A, response.flags());
+        });
+    }
+
+    #[test]
+    fn it_ignores_frames_other_than_response() {
+        with_fake_driver(|driver, controller| {
+            driver.expect_send_with_responses(|_| {
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(MessageReceived::new(0x11, 0x00))),
+                Ok(AnyMessage::new(MessageTransmitted::new(0x2A))),
+            ]);
+
+            driver.expect_ack();
+            driver.expect_ack();
+
+            let response = controller.request(SendData::new(NodeId(42), SetValue::new(42), 0x00)).unwrap();
+
+            assert_eq!(0x2A, response.flags());
+        });
+    }
+
+    #[test]
+    fn it_returns_timeout_error_if_no_response_is_received() {
+        with_fake_driver(|driver, controller| {
             driver.expect_send_with_response(|_| {
                 Ok(())
             }, Ok(AnyMessage::new(Ack::new())));
 
-            assert_eq!(Ok(()), controller.send_data(NodeId(42), SetValue::new(42)));
+            let result = controller.request(SendData::new(NodeId(42), SetValue::new(42), 0x00));
+
+            assert_eq!(Some(Error::new(ErrorKind::Timeout)), result.err());
+        });
+    }
+
+    #[test]
+    fn it_returns_response_and_callback() {
+        with_fake_driver(|driver, controller| {
+            driver.expect_send_with_responses(|message| {
+                assert_eq!(0x01, message.downcast_ref::<SendData>().unwrap().callback_id());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(MessageTransmitted::new(0x01))),
+                Ok(AnyMessage::new(MessageReceived::new(0x01, 0x2A))),
+            ]);
+
+            driver.expect_ack();
+            driver.expect_ack();
+
+            let (response, callback) = controller.request_with_callback(SendData::new(NodeId(42), SetValue::new(42), 0x00)).unwrap();
+
+            assert_eq!(0x01, response.flags());
+            assert_eq!(0x01, callback.callback_id());
+            assert_eq!(0x2A, callback.flags());
+        });
+    }
+}
+
+mod retry_policy {
+    use std::time::Duration;
+
+    use zwave::io::controller::RetryPolicy;
+
+    #[test]
+    fn it_defaults_to_three_retransmissions() {
+        assert_eq!(3, RetryPolicy::default().retransmissions());
+    }
+
+    #[test]
+    fn it_defaults_to_spec_ack_timeout() {
+        assert_eq!(Duration::from_millis(1600), RetryPolicy::default().ack_timeout());
+    }
+
+    #[test]
+    fn it_increases_backoff_with_each_retransmission() {
+        let policy = RetryPolicy::default();
+
+        assert_eq!(Duration::from_millis(100),  policy.backoff(0));
+        assert_eq!(Duration::from_millis(1100), policy.backoff(1));
+        assert_eq!(Duration::from_millis(2100), policy.backoff(2));
+    }
+}
+
+mod retransmission {
+    use std::time::Duration;
+
+    use zwave::core::{NodeId, Error, ErrorKind};
+    use zwave::protocol::message::{AnyMessage, Ack, Nack, Cancel, SendData, MessageTransmitted};
+    use zwave::protocol::command::basic::SetValue;
+    use zwave::io::controller::RetryPolicy;
+
+    use super::with_fake_driver;
+
+    fn retry_policy() -> RetryPolicy {
+        RetryPolicy::with_backoff(2, Duration::from_millis(50), Duration::from_millis(1), Duration::from_millis(1))
+    }
+
+    fn request() -> SendData {
+        SendData::new(NodeId(42), SetValue::new(42), 0x00)
+    }
+
+    #[test]
+    fn it_retransmits_after_nack() {
+        with_fake_driver(|driver, controller| {
+            controller.set_retry_policy(retry_policy());
+
+            driver.expect_send_with_response(|message| {
+                assert!(message.is::<SendData>());
+                Ok(())
+            }, Ok(AnyMessage::new(Nack::new())));
+
+            driver.expect_send_with_responses(|message| {
+                assert!(message.is::<SendData>());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(MessageTransmitted::new(0x01))),
+            ]);
+
+            driver.expect_ack();
+
+            assert!(controller.request(request()).is_ok());
+            assert_eq!(2, controller.attempts());
+        });
+    }
+
+    #[test]
+    fn it_retransmits_after_cancel() {
+        with_fake_driver(|driver, controller| {
+            controller.set_retry_policy(retry_policy());
+
+            driver.expect_send_with_response(|_| { Ok(()) }, Ok(AnyMessage::new(Cancel::new())));
+
+            driver.expect_send_with_responses(|_| {
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(MessageTransmitted::new(0x01))),
+            ]);
+
+            driver.expect_ack();
+
+            assert!(controller.request(request()).is_ok());
+            assert_eq!(2, controller.attempts());
+        });
+    }
+
+    #[test]
+    fn it_retransmits_after_ack_timeout() {
+        with_fake_driver(|driver, controller| {
+            controller.set_retry_policy(retry_policy());
+
+            driver.expect_send(|_| { Ok(()) });
+            driver.expect_send(|_| { Ok(()) });
+
+            driver.expect_send_with_responses(|_| {
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(MessageTransmitted::new(0x01))),
+            ]);
+
+            driver.expect_ack();
+
+            assert!(controller.request(request()).is_ok());
+            assert_eq!(3, controller.attempts());
+        });
+    }
+
+    #[test]
+    fn it_gives_up_after_last_retransmission() {
+        with_fake_driver(|driver, controller| {
+            controller.set_retry_policy(retry_policy());
+
+            driver.expect_send_with_response(|_| { Ok(()) }, Ok(AnyMessage::new(Nack::new())));
+            driver.expect_send_with_response(|_| { Ok(()) }, Ok(AnyMessage::new(Nack::new())));
+            driver.expect_send_with_response(|_| { Ok(()) }, Ok(AnyMessage::new(Nack::new())));
+
+            assert_eq!(Some(Error::new(ErrorKind::Nack)), controller.request(request()).err());
+            assert_eq!(3, controller.attempts());
+        });
+    }
+
+    #[test]
+    fn it_reports_single_attempt_if_acknowledged() {
+        with_fake_driver(|driver, controller| {
+            controller.set_retry_policy(retry_policy());
+
+            driver.expect_send_with_responses(|_| {
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(MessageTransmitted::new(0x01))),
+            ]);
+
+            driver.expect_ack();
+
+            assert!(controller.request(request()).is_ok());
+            assert_eq!(1, controller.attempts());
+        });
+    }
+}
+
+mod reader {
+    use std::sync::mpsc::channel;
+    use std::time::{Duration, Instant};
+
+    use zwave::core::{NodeId, Error, ErrorKind};
+    use zwave::protocol::message::{AnyMessage, MessageReceived, ApplicationCommandHandler, ApplicationUpdate};
+    use zwave::protocol::command::basic::Report;
+    use zwave::io::controller::Event;
+
+    use super::with_fake_driver;
+
+    #[test]
+    fn it_acks_valid_frames() {
+        with_fake_driver(|driver, _| {
+            driver.expect_ack();
+
+            driver.push_response(Ok(AnyMessage::new(MessageReceived::new(0x11, 0x00))));
+            driver.wait_for_receive();
+        });
+    }
+
+    #[test]
+    fn it_nacks_corrupt_frames() {
+        with_fake_driver(|driver, _| {
+            driver.expect_nack();
+
+            driver.push_response(Err(Error::new(ErrorKind::Corrupt)));
+            driver.wait_for_receive();
+        });
+    }
+
+    #[test]
+    fn it_nacks_incomplete_frames() {
+        with_fake_driver(|driver, _| {
+            driver.expect_nack();
+
+            driver.push_response(Err(Error::new(ErrorKind::ShortRead)));
+            driver.wait_for_receive();
+        });
+    }
+
+    #[test]
+    fn it_acks_intact_frames_that_cannot_be_decoded() {
+        with_fake_driver(|driver, _| {
+            driver.expect_ack();
+
+            driver.push_response(Err(Error::new(ErrorKind::Protocol)));
+            driver.wait_for_receive();
+        });
+    }
+
+    #[test]
+    fn it_reports_receive_errors_as_events() {
+        with_fake_driver(|driver, controller| {
+            let (tx, rx) = channel();
+
+            controller.add_listener(move |event| {
+                match *event {
+                    Event::Error(err) => tx.send(err.kind()).unwrap(),
+                    _ => (),
+                }
+            });
+
+            driver.expect_nack();
+            driver.expect_ack();
+
+            driver.push_response(Err(Error::new(ErrorKind::Corrupt)));
+            driver.push_response(Err(Error::new(ErrorKind::Protocol)));
+            driver.push_response(Err(Error::new(ErrorKind::Io)));
+
+            assert_eq!(ErrorKind::Corrupt,  rx.recv_timeout(Duration::from_millis(100)).unwrap());
+            assert_eq!(ErrorKind::Protocol, rx.recv_timeout(Duration::from_millis(100)).unwrap());
+            assert_eq!(ErrorKind::Io,       rx.recv_timeout(Duration::from_millis(100)).unwrap());
+        });
+    }
+
+    #[test]
+    fn it_reports_persistent_receive_errors_once() {
+        with_fake_driver(|driver, controller| {
+            let (tx, rx) = channel();
+
+            controller.add_listener(move |event| {
+                match *event {
+                    Event::Error(err) => tx.send(err.kind()).unwrap(),
+                    _ => (),
+                }
+            });
+
+            for _ in 0..5 {
+                driver.push_response(Err(Error::new(ErrorKind::Io)));
+            }
+
+            driver.wait_for_receive();
+
+            assert_eq!(ErrorKind::Io, rx.recv_timeout(Duration::from_millis(100)).unwrap());
+            assert!(rx.recv_timeout(Duration::from_millis(10)).is_err());
+        });
+    }
+
+    #[test]
+    fn it_backs_off_after_receive_errors() {
+        with_fake_driver(|driver, _| {
+            for _ in 0..5 {
+                driver.push_response(Err(Error::new(ErrorKind::Io)));
+            }
+
+            let started = Instant::now();
+            driver.wait_for_receive();
+
+            assert!(started.elapsed() >= Duration::from_millis(300));
+        });
+    }
+
+    #[test]
+    fn it_ignores_receive_timeouts() {
+        with_fake_driver(|driver, controller| {
+            let (tx, rx) = channel();
+
+            controller.add_listener(move |event| {
+                tx.send(format!("{:?}", event)).unwrap();
+            });
+
+            driver.push_response(Err(Error::new(ErrorKind::Timeout)));
+            driver.wait_for_receive();
+
+            assert!(rx.recv_timeout(Duration::from_millis(10)).is_err());
+        });
+    }
+
+    #[test]
+    fn it_dispatches_application_commands_to_listeners() {
+        with_fake_driver(|driver, controller| {
+            let (tx, rx) = channel();
+
+            controller.add_listener(move |event| {
+                match *event {
+                    Event::ApplicationCommand(ref frame) => {
+                        let report = frame.command().downcast_ref::<Report>().unwrap();
+                        tx.send((frame.source(), frame.status(), report.value())).unwrap();
+                    },
+                    _ => (),
+                }
+            });
+
+            driver.expect_ack();
+
+            driver.push_response(Ok(AnyMessage::new(ApplicationCommandHandler::new(0x00, NodeId(7), Report::new(42)))));
+
+            assert_eq!((NodeId(7), 0x00, 42), rx.recv_timeout(Duration::from_millis(100)).unwrap());
+        });
+    }
+
+    #[test]
+    fn it_dispatches_events_to_every_listener() {
+        with_fake_driver(|driver, controller| {
+            let (tx, rx) = channel();
+            let other_tx = tx.clone();
+
+            controller.add_listener(move |_| tx.send(1).unwrap());
+            controller.add_listener(move |_| other_tx.send(2).unwrap());
+
+            driver.expect_ack();
+
+            driver.push_response(Ok(AnyMessage::new(ApplicationCommandHandler::new(0x00, NodeId(7), Report::new(42)))));
+
+            assert_eq!(1, rx.recv_timeout(Duration::from_millis(100)).unwrap());
+            assert_eq!(2, rx.recv_timeout(Duration::from_millis(100)).unwrap());
+        });
+    }
+
+    #[test]
+    fn it_dispatches_application_updates_to_listeners() {
+        with_fake_driver(|driver, controller| {
+            let (tx, rx) = channel();
+
+            controller.add_listener(move |event| {
+                match *event {
+                    Event::ApplicationUpdate(ref update) => tx.send(update.clone()).unwrap(),
+                    _ => (),
+                }
+            });
+
+            driver.expect_ack();
+
+            driver.push_response(Ok(AnyMessage::new(ApplicationUpdate::NewIdAssigned(NodeId(7)))));
+
+            assert_eq!(ApplicationUpdate::NewIdAssigned(NodeId(7)), rx.recv_timeout(Duration::from_millis(100)).unwrap());
+        });
+    }
+}
+
+mod identify {
+    use zwave::core::{NodeId, HomeId, Error, ErrorKind};
+    use zwave::protocol::bits::{FunctionId, LibraryType, TransmitStatus};
+    use zwave::protocol::message::{AnyMessage, Ack, SendData, MessageTransmitted, MessageReceived};
+    use zwave::protocol::command::basic::SetValue;
+    use zwave::protocol::message::{GetVersion, Version, MemoryGetId, MemoryId, SerialApiGetCapabilities, SerialApiCapabilities};
+
+    use super::{FakeDriver, with_fake_driver};
+
+    fn function_mask() -> [u8; 32] {
+        let mut function_mask = [0u8; 32];
+
+        function_mask[0] = 0xFE;
+        function_mask[1] = 0x80;
+        function_mask[2] = 0x0F;
+        function_mask[9] = 0x80;
+
+        function_mask
+    }
+
+    pub fn expect_identification(driver: &mut FakeDriver) {
+        expect_identification_in(driver, HomeId(0xC0FFEE42), NodeId(1));
+    }
+
+    pub fn expect_identification_in(driver: &mut FakeDriver, home_id: HomeId, node_id: NodeId) {
+        driver.expect_send_with_responses(|message| {
+            assert!(message.is::<GetVersion>());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(Version::new("Z-Wave 4.05", 0x01))),
+        ]);
+
+        driver.expect_ack();
+
+        driver.expect_send_with_responses(|message| {
+            assert!(message.is::<MemoryGetId>());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(MemoryId::new(home_id, node_id))),
+        ]);
+
+        driver.expect_ack();
+
+        driver.expect_send_with_responses(|message| {
+            assert!(message.is::<SerialApiGetCapabilities>());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(SerialApiCapabilities::new(5, 10, 0x0086, 0x0101, 0x005A, function_mask()))),
+        ]);
+
+        driver.expect_ack();
+    }
+
+    #[test]
+    fn it_reports_library() {
+        with_fake_driver(|driver, controller| {
+            expect_identification(driver);
+
+            let identity = controller.identify().unwrap();
+
+            assert_eq!("Z-Wave 4.05", identity.library_version());
+            assert_eq!(Some(LibraryType::StaticController), identity.library_type());
+        });
+    }
+
+    #[test]
+    fn it_reports_network() {
+        with_fake_driver(|driver, controller| {
+            expect_identification(driver);
+
+            let identity = controller.identify().unwrap();
+
+            assert_eq!(HomeId(0xC0FFEE42), identity.home_id());
+            assert_eq!(NodeId(1), identity.node_id());
+        });
+    }
+
+    #[test]
+    fn it_reports_hardware() {
+        with_fake_driver(|driver, controller| {
+            expect_identification(driver);
+
+            let identity = controller.identify().unwrap();
+
+            assert_eq!(5, identity.application_version());
+            assert_eq!(10, identity.application_revision());
+            assert_eq!(0x0086, identity.manufacturer_id());
+            assert_eq!(0x0101, identity.product_type());
+            assert_eq!(0x005A, identity.product_id());
+        });
+    }
+
+    #[test]
+    fn it_reports_supported_functions() {
+        with_fake_driver(|driver, controller| {
+            expect_identification(driver);
+
+            let identity = controller.identify().unwrap();
+
+            assert!(identity.supports(FunctionId::SerialApiGetCapabilities));
+            assert!(identity.supports(FunctionId::SendData));
+            assert!(!identity.supports(FunctionId::GetVersion));
+            assert!(!identity.supports(FunctionId::ApplicationUpdate));
+        });
+    }
+
+    #[test]
+    fn it_keeps_identity() {
+        with_fake_driver(|driver, controller| {
+            assert!(controller.identity().is_none());
+
+            expect_identification(driver);
+
+            let identity = controller.identify().unwrap();
+
+            assert_eq!(Some(&identity), controller.identity());
+        });
+    }
+
+    #[test]
+    fn it_refuses_unsupported_functions() {
+        with_fake_driver(|driver, controller| {
+            expect_identification(driver);
+            controller.identify().unwrap();
+
+            assert_eq!(Some(Error::new(ErrorKind::Unsupported)), controller.request(GetVersion::new()).err());
+        });
+    }
+
+    #[test]
+    fn it_sends_supported_functions() {
+        with_fake_driver(|driver, controller| {
+            expect_identification(driver);
+            controller.identify().unwrap();
+
+            driver.expect_send_with_responses(|message| {
+                assert!(message.is::<SendData>());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(MessageTransmitted::new(0x01))),
+                Ok(AnyMessage::new(MessageReceived::new(0x01, 0x00))),
+            ]);
+
+            driver.expect_ack();
+            driver.expect_ack();
+
+            assert_eq!(Ok(TransmitStatus::Ok), controller.send_data(NodeId(2), SetValue::new(42)));
+        });
+    }
+
+    #[test]
+    fn it_identifies_again_regardless_of_previous_capabilities() {
+        with_fake_driver(|driver, controller| {
+            expect_identification(driver);
+            controller.identify().unwrap();
+
+            expect_identification(driver);
+            assert!(controller.identify().is_ok());
+        });
+    }
+}
+
+mod nodes {
+    use zwave::core::{NodeId, Error, ErrorKind};
+    use zwave::protocol::bits::{SerialApiSetupCommand, NodeIdType};
+    use zwave::protocol::message::{AnyMessage, Ack};
+    use zwave::protocol::message::{SerialApiGetInitData, SerialApiInitData, GetNodeProtocolInfo, NodeProtocolInfo};
+    use zwave::protocol::message::{GetLongRangeNodes, LongRangeNodes, SerialApiSetup, SerialApiSetupResult};
+
+    use super::{FakeDriver, with_fake_driver};
+
+    fn expect_long_range_nodes(driver: &mut FakeDriver, response: LongRangeNodes) {
+        let segment = response.segment();
+
+        driver.expect_send_with_responses(move |message| {
+            assert_eq!(segment, message.downcast_ref::<GetLongRangeNodes>().unwrap().segment());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(response)),
+        ]);
+
+        driver.expect_ack();
+    }
+
+    fn expect_protocol_info(driver: &mut FakeDriver, node_id: NodeId, protocol_info: NodeProtocolInfo) {
+        driver.expect_send_with_responses(move |message| {
+            assert_eq!(node_id, message.downcast_ref::<GetNodeProtocolInfo>().unwrap().node_id());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(protocol_info)),
+        ]);
+
+        driver.expect_ack();
+    }
+
+    #[test]
+    fn it_lists_nodes_with_protocol_info() {
+        with_fake_driver(|driver, controller| {
+            let controller_info = NodeProtocolInfo::new(0xD3, 0x9C, 0x01, 0x02, 0x02, 0x07);
+            let sensor_info = NodeProtocolInfo::new(0x52, 0x5C, 0x00, 0x04, 0x21, 0x01);
+
+            driver.expect_send_with_responses(|message| {
+                assert!(message.is::<SerialApiGetInitData>());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(SerialApiInitData::new(0x05, 0x08, vec![NodeId(1), NodeId(5)], 0x05, 0x00))),
+            ]);
+
+            driver.expect_ack();
+            expect_protocol_info(driver, NodeId(1), controller_info.clone());
+            expect_protocol_info(driver, NodeId(5), sensor_info.clone());
+
+            let nodes = controller.nodes().unwrap();
+
+            assert_eq!(2, nodes.len());
+            assert_eq!(NodeId(1), nodes[0].node_id());
+            assert_eq!(&controller_info, nodes[0].protocol_info());
+            assert_eq!(NodeId(5), nodes[1].node_id());
+            assert_eq!(&sensor_info, nodes[1].protocol_info());
+        });
+    }
+
+    #[test]
+    fn it_lists_long_range_nodes_from_all_segments() {
+        with_fake_driver(|driver, controller| {
+            let sensor_info = NodeProtocolInfo::new(0x52, 0x5C, 0x00, 0x04, 0x21, 0x01);
+
+            driver.expect_send_with_responses(|message| {
+                assert_eq!(&SerialApiSetup::SetNodeIdType(NodeIdType::Long), message.downcast_ref::<SerialApiSetup>().unwrap());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(SerialApiSetupResult::Set(SerialApiSetupCommand::SetNodeIdType, true))),
+            ]);
+
+            driver.expect_ack();
+            expect_long_range_nodes(driver, LongRangeNodes::new(true, 0, vec![NodeId(256)]));
+            expect_long_range_nodes(driver, LongRangeNodes::new(false, 1, vec![NodeId(1280)]));
+            expect_protocol_info(driver, NodeId(256), sensor_info.clone());
+            expect_protocol_info(driver, NodeId(1280), sensor_info.clone());
+
+            let nodes = controller.long_range_nodes().unwrap();
+
+            assert_eq!(NodeIdType::Long, controller.node_id_type());
+            assert_eq!(vec![NodeId(256), NodeId(1280)], nodes.iter().map(|node| node.node_id()).collect::<Vec<_>>());
+        });
+    }
+
+    #[test]
+    fn it_returns_protocol_error_if_controller_reports_too_many_segments() {
+        with_fake_driver(|driver, controller| {
+            driver.expect_send_with_responses(|message| {
+                assert!(message.is::<SerialApiSetup>());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(SerialApiSetupResult::Set(SerialApiSetupCommand::SetNodeIdType, true))),
+            ]);
+
+            driver.expect_ack();
+
+            for segment in 0..GetLongRangeNodes::SEGMENTS {
+                expect_long_range_nodes(driver, LongRangeNodes::new(true, segment, vec![]));
+            }
+
+            assert_eq!(Some(Error::new(ErrorKind::Protocol)), controller.long_range_nodes().err());
+        });
+    }
+
+    #[test]
+    fn it_keeps_two_byte_node_ids_once_set() {
+        with_fake_driver(|driver, controller| {
+            driver.expect_send_with_responses(|message| {
+                assert!(message.is::<SerialApiSetup>());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(SerialApiSetupResult::Set(SerialApiSetupCommand::SetNodeIdType, true))),
+            ]);
+
+            driver.expect_ack();
+            expect_long_range_nodes(driver, LongRangeNodes::new(false, 0, vec![]));
+
+            controller.set_node_id_type(NodeIdType::Long).unwrap();
+
+            assert_eq!(0, controller.long_range_nodes().unwrap().len());
+        });
+    }
+}
+
+mod add_node {
+    use std::time::Duration;
+
+    use zwave::core::{NodeId, HomeId, Error, ErrorKind};
+    use zwave::protocol::bits::{AddNodeMode, AddNodeStatus, SerialApiSetupCommand};
+    use zwave::protocol::message::{AnyMessage, Ack, Nack, NodeInfo, AddNodeToNetwork, AddNodeUpdate};
+    use zwave::protocol::message::{SerialApiSetup, SerialApiSetupResult};
+
+    use super::{FakeDriver, with_fake_driver};
+
+    fn node_info() -> NodeInfo {
+        NodeInfo::new(0x04, 0x10, 0x01, vec![0x25, 0x27], vec![])
+    }
+
+    fn update(status: AddNodeStatus, node_id: u16, node_info: Option<NodeInfo>) -> AnyMessage {
+        AnyMessage::new(AddNodeUpdate::new(0x01, status, NodeId(node_id), node_info))
+    }
+
+    fn expect_add_node(driver: &mut FakeDriver, mode: AddNodeMode, callback_id: u8, updates: Vec<AnyMessage>) {
+        let acks = updates.len();
+        let mut responses = vec![Ok(AnyMessage::new(Ack::new()))];
+        responses.extend(updates.into_iter().map(|update| Ok(update)));
+
+        driver.expect_send_with_responses(move |message| {
+            let request = message.downcast_ref::<AddNodeToNetwork>().unwrap();
+            assert_eq!(mode, request.mode());
+            assert_eq!(callback_id, request.callback_id());
+            Ok(())
+        }, responses);
+
+        for _ in 0..acks {
+            driver.expect_ack();
+        }
+    }
+
+    #[test]
+    fn it_reports_added_node() {
+        with_fake_driver(|driver, controller| {
+            expect_add_node(driver, AddNodeMode::Any, 0x01, vec![
+                update(AddNodeStatus::LearnReady, 0, None),
+                update(AddNodeStatus::NodeFound, 0, None),
+                update(AddNodeStatus::AddingSlave, 5, Some(node_info())),
+                update(AddNodeStatus::ProtocolDone, 5, None),
+            ]);
+
+            expect_add_node(driver, AddNodeMode::Stop, 0x01, vec![
+                update(AddNodeStatus::Done, 5, None),
+            ]);
+
+            expect_add_node(driver, AddNodeMode::Stop, 0x00, vec![]);
+
+            let inclusion = controller.add_node(AddNodeMode::Any, true, true).unwrap();
+
+            assert_eq!(Ok((NodeId(5), node_info())), inclusion.wait(Duration::from_millis(100)));
+        });
+    }
+
+    #[test]
+    fn it_reports_added_node_if_final_stop_fails() {
+        with_fake_driver(|driver, controller| {
+            expect_add_node(driver, AddNodeMode::Any, 0x01, vec![
+                update(AddNodeStatus::LearnReady, 0, None),
+                update(AddNodeStatus::AddingSlave, 5, Some(node_info())),
+                update(AddNodeStatus::ProtocolDone, 5, None),
+            ]);
+
+            expect_add_node(driver, AddNodeMode::Stop, 0x01, vec![
+                update(AddNodeStatus::Done, 5, None),
+            ]);
+
+            driver.expect_send_with_response(|message| {
+                let request = message.downcast_ref::<AddNodeToNetwork>().unwrap();
+                assert_eq!((AddNodeMode::Stop, 0x00), (request.mode(), request.callback_id()));
+                Ok(())
+            }, Ok(AnyMessage::new(Nack::new())));
+
+            let inclusion = controller.add_node(AddNodeMode::Any, true, true).unwrap();
+
+            assert_eq!(Ok((NodeId(5), node_info())), inclusion.wait(Duration::from_millis(100)));
+        });
+    }
+
+    #[test]
+    fn it_reports_each_status() {
+        with_fake_driver(|driver, controller| {
+            expect_add_node(driver, AddNodeMode::Slave, 0x01, vec![
+                update(AddNodeStatus::LearnReady, 0, None),
+                update(AddNodeStatus::NodeFound, 0, None),
+                update(AddNodeStatus::AddingSlave, 5, Some(node_info())),
+            ]);
+
+            expect_add_node(driver, AddNodeMode::Stop, 0x00, vec![]);
+
+            let mut inclusion = controller.add_node(AddNodeMode::Slave, true, true).unwrap();
+
+            assert_eq!(Ok(AddNodeStatus::NodeFound), inclusion.next_status(Duration::from_millis(100)));
+            assert_eq!(None, inclusion.node_id());
+
+            assert_eq!(Ok(AddNodeStatus::AddingSlave), inclusion.next_status(Duration::from_millis(100)));
+            assert_eq!(Some(NodeId(5)), inclusion.node_id());
+            assert_eq!(Some(&node_info()), inclusion.node_info());
+        });
+    }
+
+    #[test]
+    fn it_sends_inclusion_options() {
+        with_fake_driver(|driver, controller| {
+            driver.expect_send_with_responses(|message| {
+                let request = message.downcast_ref::<AddNodeToNetwork>().unwrap();
+                assert!(!request.high_power());
+                assert!(request.network_wide());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(update(AddNodeStatus::LearnReady, 0, None)),
+            ]);
+
+            driver.expect_ack();
+            expect_add_node(driver, AddNodeMode::Stop, 0x00, vec![]);
+
+            controller.add_node(AddNodeMode::Any, false, true).unwrap().cancel().unwrap();
+        });
+    }
+
+    #[test]
+    fn it_stops_inclusion_when_canceled() {
+        with_fake_driver(|driver, controller| {
+            expect_add_node(driver, AddNodeMode::Any, 0x01, vec![
+                update(AddNodeStatus::LearnReady, 0, None),
+            ]);
+
+            expect_add_node(driver, AddNodeMode::Stop, 0x00, vec![]);
+
+            let inclusion = controller.add_node(AddNodeMode::Any, true, true).unwrap();
+
+            assert_eq!(Ok(()), inclusion.cancel());
+        });
+    }
+
+    #[test]
+    fn it_stops_inclusion_on_timeout() {
+        with_fake_driver(|driver, controller| {
+            expect_add_node(driver, AddNodeMode::Any, 0x01, vec![
+                update(AddNodeStatus::LearnReady, 0, None),
+            ]);
+
+            expect_add_node(driver, AddNodeMode::Stop, 0x00, vec![]);
+
+            let inclusion = controller.add_node(AddNodeMode::Any, true, true).unwrap();
+
+            assert_eq!(Err(Error::new(ErrorKind::Timeout)), inclusion.wait(Duration::from_millis(10)));
+        });
+    }
+
+    #[test]
+    fn it_returns_failed_error_if_inclusion_fails() {
+        with_fake_driver(|driver, controller| {
+            expect_add_node(driver, AddNodeMode::Any, 0x01, vec![
+                update(AddNodeStatus::LearnReady, 0, None),
+                update(AddNodeStatus::NodeFound, 0, None),
+                update(AddNodeStatus::Failed, 0, None),
+            ]);
+
+            expect_add_node(driver, AddNodeMode::Stop, 0x00, vec![]);
+
+            let inclusion = controller.add_node(AddNodeMode::Any, true, true).unwrap();
+
+            assert_eq!(Err(Error::new(ErrorKind::Failed)), inclusion.wait(Duration::from_millis(100)));
+        });
+    }
+
+    #[test]
+    fn it_adds_long_range_node_by_dsk() {
+        with_fake_driver(|driver, controller| {
+            let dsk = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x05, 0xC4, 0x05, 0x06, 0x06];
+
+            driver.expect_send_with_responses(|message| {
+                assert!(message.is::<SerialApiSetup>());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(SerialApiSetupResult::Set(SerialApiSetupCommand::SetNodeIdType, true))),
+            ]);
+
+            driver.expect_ack();
+
+            driver.expect_send_with_responses(|message| {
+                let request = message.downcast_ref::<AddNodeToNetwork>().unwrap();
+                assert_eq!(AddNodeMode::SmartStartDsk, request.mode());
+                assert!(request.long_range());
+                assert_eq!(Some(HomeId(0xC1020304)), request.nwi_home_id());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(update(AddNodeStatus::NodeFound, 0, None)),
+                Ok(update(AddNodeStatus::AddingSlave, 0x0100, Some(node_info()))),
+                Ok(update(AddNodeStatus::ProtocolDone, 0x0100, None)),
+            ]);
+
+            driver.expect_ack();
+            driver.expect_ack();
+            driver.expect_ack();
+
+            expect_add_node(driver, AddNodeMode::Stop, 0x01, vec![
+                update(AddNodeStatus::Done, 0x0100, None),
+            ]);
+
+            expect_add_node(driver, AddNodeMode::Stop, 0x00, vec![]);
+
+            let inclusion = controller.add_long_range_node(&dsk).unwrap();
+
+            assert_eq!(Ok((NodeId(0x0100), node_info())), inclusion.wait(Duration::from_millis(100)));
+        });
+    }
+}
+
+mod remove_node {
+    use std::time::Duration;
+
+    use zwave::core::{NodeId, Error, ErrorKind};
+    use zwave::protocol::bits::{RemoveNodeMode, RemoveNodeStatus};
+    use zwave::protocol::message::{AnyMessage, Ack, Nack, RemoveNodeFromNetwork, RemoveNodeUpdate};
+
+    use super::{FakeDriver, with_fake_driver};
+
+    fn update(status: RemoveNodeStatus, node_id: u16) -> AnyMessage {
+        AnyMessage::new(RemoveNodeUpdate::new(0x01, status, NodeId(node_id)))
+    }
+
+    fn expect_remove_node(driver: &mut FakeDriver, mode: RemoveNodeMode, callback_id: u8, updates: Vec<AnyMessage>) {
+        let acks = updates.len();
+        let mut responses = vec![Ok(AnyMessage::new(Ack::new()))];
+        responses.extend(updates.into_iter().map(|update| Ok(update)));
+
+        driver.expect_send_with_responses(move |message| {
+            let request = message.downcast_ref::<RemoveNodeFromNetwork>().unwrap();
+            assert_eq!(mode, request.mode());
+            assert_eq!(callback_id, request.callback_id());
+            Ok(())
+        }, responses);
+
+        for _ in 0..acks {
+            driver.expect_ack();
+        }
+    }
+
+    #[test]
+    fn it_reports_removed_node() {
+        with_fake_driver(|driver, controller| {
+            expect_remove_node(driver, RemoveNodeMode::Any, 0x01, vec![
+                update(RemoveNodeStatus::LearnReady, 0),
+                update(RemoveNodeStatus::NodeFound, 0),
+                update(RemoveNodeStatus::RemovingSlave, 5),
+                update(RemoveNodeStatus::Done, 0),
+            ]);
+
+            expect_remove_node(driver, RemoveNodeMode::Stop, 0x00, vec![]);
+
+            let exclusion = controller.remove_node(RemoveNodeMode::Any, true, true).unwrap();
+
+            assert_eq!(Ok(NodeId(5)), exclusion.wait(Duration::from_millis(100)));
+        });
+    }
+
+    #[test]
+    fn it_reports_removed_node_if_final_stop_fails() {
+        with_fake_driver(|driver, controller| {
+            expect_remove_node(driver, RemoveNodeMode::Any, 0x01, vec![
+                update(RemoveNodeStatus::LearnReady, 0),
+                update(RemoveNodeStatus::RemovingSlave, 5),
+                update(RemoveNodeStatus::Done, 0),
+            ]);
+
+            driver.expect_send_with_response(|message| {
+                assert_eq!(RemoveNodeMode::Stop, message.downcast_ref::<RemoveNodeFromNetwork>().unwrap().mode());
+                Ok(())
+            }, Ok(AnyMessage::new(Nack::new())));
+
+            let exclusion = controller.remove_node(RemoveNodeMode::Any, true, true).unwrap();
+
+            assert_eq!(Ok(NodeId(5)), exclusion.wait(Duration::from_millis(100)));
+        });
+    }
+
+    #[test]
+    fn it_reports_node_from_other_network_as_node_zero() {
+        with_fake_driver(|driver, controller| {
+            expect_remove_node(driver, RemoveNodeMode::Any, 0x01, vec![
+                update(RemoveNodeStatus::LearnReady, 0),
+                update(RemoveNodeStatus::NodeFound, 0),
+                update(RemoveNodeStatus::RemovingSlave, 0),
+                update(RemoveNodeStatus::Done, 0),
+            ]);
+
+            expect_remove_node(driver, RemoveNodeMode::Stop, 0x00, vec![]);
+
+            let exclusion = controller.remove_node(RemoveNodeMode::Any, true, true).unwrap();
+
+            assert_eq!(Ok(NodeId(0)), exclusion.wait(Duration::from_millis(100)));
+        });
+    }
+
+    #[test]
+    fn it_stops_exclusion_when_canceled() {
+        with_fake_driver(|driver, controller| {
+            expect_remove_node(driver, RemoveNodeMode::Any, 0x01, vec![
+                update(RemoveNodeStatus::LearnReady, 0),
+            ]);
+
+            expect_remove_node(driver, RemoveNodeMode::Stop, 0x00, vec![]);
+
+            let exclusion = controller.remove_node(RemoveNodeMode::Any, true, true).unwrap();
+
+            assert_eq!(Ok(()), exclusion.cancel());
+        });
+    }
+
+    #[test]
+    fn it_stops_exclusion_on_timeout() {
+        with_fake_driver(|driver, controller| {
+            expect_remove_node(driver, RemoveNodeMode::Any, 0x01, vec![
+                update(RemoveNodeStatus::LearnReady, 0),
+                update(RemoveNodeStatus::NodeFound, 0),
+            ]);
+
+            expect_remove_node(driver, RemoveNodeMode::Stop, 0x00, vec![]);
+
+            let exclusion = controller.remove_node(RemoveNodeMode::Any, true, true).unwrap();
+
+            assert_eq!(Err(Error::new(ErrorKind::Timeout)), exclusion.wait(Duration::from_millis(10)));
+        });
+    }
+
+    #[test]
+    fn it_returns_failed_error_if_exclusion_fails() {
+        with_fake_driver(|driver, controller| {
+            expect_remove_node(driver, RemoveNodeMode::Any, 0x01, vec![
+                update(RemoveNodeStatus::LearnReady, 0),
+                update(RemoveNodeStatus::Failed, 0),
+            ]);
+
+            expect_remove_node(driver, RemoveNodeMode::Stop, 0x00, vec![]);
+
+            let exclusion = controller.remove_node(RemoveNodeMode::Any, true, true).unwrap();
+
+            assert_eq!(Err(Error::new(ErrorKind::Failed)), exclusion.wait(Duration::from_millis(100)));
+        });
+    }
+}
+
+mod learn_mode {
+    use std::time::Duration;
+
+    use zwave::core::{NodeId, HomeId, Error, ErrorKind};
+    use zwave::protocol::bits::{LearnMode, LearnModeStatus};
+    use zwave::protocol::message::{AnyMessage, Ack, Nack, MemoryGetId, MemoryId};
+    use zwave::protocol::message::{SetLearnMode, LearnModeResult, LearnModeUpdate};
+
+    use super::{FakeDriver, with_fake_driver};
+    use super::identify::{expect_identification, expect_identification_in};
+
+    fn update(status: LearnModeStatus, node_id: u16) -> AnyMessage {
+        AnyMessage::new(LearnModeUpdate::new(0x01, status, NodeId(node_id)))
+    }
+
+    fn expect_memory_id(driver: &mut FakeDriver) {
+        driver.expect_send_with_responses(|message| {
+            assert!(message.is::<MemoryGetId>());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(MemoryId::new(HomeId(0xDEADBEEF), NodeId(7)))),
+        ]);
+
+        driver.expect_ack();
+    }
+
+    fn expect_learn_mode(driver: &mut FakeDriver, mode: LearnMode, result: u8, updates: Vec<AnyMessage>) {
+        let acks = updates.len() + 1;
+        let mut responses = vec![Ok(AnyMessage::new(Ack::new())), Ok(AnyMessage::new(LearnModeResult::new(result)))];
+        responses.extend(updates.into_iter().map(|update| Ok(update)));
+
+        driver.expect_send_with_responses(move |message| {
+            assert_eq!(mode, message.downcast_ref::<SetLearnMode>().unwrap().mode());
+            Ok(())
+        }, responses);
+
+        for _ in 0..acks {
+            driver.expect_ack();
+        }
+    }
+
+    #[test]
+    fn it_reports_new_network() {
+        with_fake_driver(|driver, controller| {
+            expect_learn_mode(driver, LearnMode::NetworkWide, 0x01, vec![
+                update(LearnModeStatus::Started, 0),
+                update(LearnModeStatus::Done, 7),
+            ]);
+
+            expect_learn_mode(driver, LearnMode::Disable, 0x01, vec![]);
+            expect_memory_id(driver);
+
+            let learning = controller.learn_mode(LearnMode::NetworkWide).unwrap();
+
+            assert_eq!(Ok((HomeId(0xDEADBEEF), NodeId(7))), learning.wait(Duration::from_millis(100)));
+        });
+    }
+
+    #[test]
+    fn it_reports_new_network_if_final_disable_fails() {
+        with_fake_driver(|driver, controller| {
+            expect_learn_mode(driver, LearnMode::NetworkWide, 0x01, vec![
+                update(LearnModeStatus::Done, 7),
+            ]);
+
+            driver.expect_send_with_response(|message| {
+                assert_eq!(LearnMode::Disable, message.downcast_ref::<SetLearnMode>().unwrap().mode());
+                Ok(())
+            }, Ok(AnyMessage::new(Nack::new())));
+
+            expect_memory_id(driver);
+
+            let learning = controller.learn_mode(LearnMode::NetworkWide).unwrap();
+
+            assert_eq!(Ok((HomeId(0xDEADBEEF), NodeId(7))), learning.wait(Duration::from_millis(100)));
+        });
+    }
+
+    #[test]
+    fn it_identifies_controller_again_in_new_network() {
+        with_fake_driver(|driver, controller| {
+            expect_identification(driver);
+            controller.identify().unwrap();
+
+            expect_learn_mode(driver, LearnMode::NetworkWide, 0x01, vec![
+                update(LearnModeStatus::Done, 7),
+            ]);
+
+            expect_learn_mode(driver, LearnMode::Disable, 0x01, vec![]);
+            expect_identification_in(driver, HomeId(0xDEADBEEF), NodeId(7));
+
+            let learning = controller.learn_mode(LearnMode::NetworkWide).unwrap();
+
+            assert_eq!(Ok((HomeId(0xDEADBEEF), NodeId(7))), learning.wait(Duration::from_millis(100)));
+
+            let identity = controller.identity().unwrap();
+
+            assert_eq!(HomeId(0xDEADBEEF), identity.home_id());
+            assert_eq!(NodeId(7), identity.node_id());
+        });
+    }
+
+    #[test]
+    fn it_returns_rejected_error_if_learn_mode_is_refused() {
+        with_fake_driver(|driver, controller| {
+            expect_learn_mode(driver, LearnMode::Classic, 0x00, vec![]);
+
+            assert_eq!(Some(Error::new(ErrorKind::Rejected)), controller.learn_mode(LearnMode::Classic).err());
+        });
+    }
+
+    #[test]
+    fn it_disables_learn_mode_when_canceled() {
+        with_fake_driver(|driver, controller| {
+            expect_learn_mode(driver, LearnMode::Classic, 0x01, vec![]);
+            expect_learn_mode(driver, LearnMode::Disable, 0x01, vec![]);
+
+            let learning = controller.learn_mode(LearnMode::Classic).unwrap();
+
+            assert_eq!(Ok(()), learning.cancel());
+        });
+    }
+
+    #[test]
+    fn it_disables_learn_mode_on_timeout() {
+        with_fake_driver(|driver, controller| {
+            expect_learn_mode(driver, LearnMode::Classic, 0x01, vec![
+                update(LearnModeStatus::Started, 0),
+            ]);
+
+            expect_learn_mode(driver, LearnMode::Disable, 0x01, vec![]);
+
+            let learning = controller.learn_mode(LearnMode::Classic).unwrap();
+
+            assert_eq!(Err(Error::new(ErrorKind::Timeout)), learning.wait(Duration::from_millis(10)));
+        });
+    }
+
+    #[test]
+    fn it_returns_failed_error_if_learn_mode_fails() {
+        with_fake_driver(|driver, controller| {
+            expect_learn_mode(driver, LearnMode::Classic, 0x01, vec![
+                update(LearnModeStatus::Failed, 0),
+            ]);
+
+            expect_learn_mode(driver, LearnMode::Disable, 0x01, vec![]);
+
+            let learning = controller.learn_mode(LearnMode::Classic).unwrap();
+
+            assert_eq!(Err(Error::new(ErrorKind::Failed)), learning.wait(Duration::from_millis(100)));
+        });
+    }
+}
+
+mod reset {
+    use std::sync::mpsc::channel;
+    use std::time::Duration;
+
+    use zwave::core::NodeId;
+    use zwave::io::controller::Event;
+    use zwave::protocol::bits::TransmitStatus;
+    use zwave::protocol::command::basic::SetValue;
+    use zwave::protocol::message::{AnyMessage, Ack, SendData, MessageTransmitted, MessageReceived};
+    use zwave::protocol::message::{SetDefault, DefaultSet, SoftReset, SerialApiStarted, GetVersion};
+
+    use super::{FakeDriver, with_fake_driver};
+    use super::identify::expect_identification;
+
+    fn serial_api_started() -> SerialApiStarted {
+        SerialApiStarted::new(0x07, false, 0x01, 0x02, 0x07, vec![0x5E, 0x86], false)
+    }
+
+    fn expect_soft_reset(driver: &mut FakeDriver) {
+        driver.expect_send_with_responses(|message| {
+            assert!(message.is::<SoftReset>());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(serial_api_started())),
+        ]);
+
+        driver.expect_ack();
+    }
+
+    #[test]
+    fn it_soft_resets_controller() {
+        with_fake_driver(|driver, controller| {
+            expect_soft_reset(driver);
+
+            assert_eq!(Ok(()), controller.soft_reset());
+        });
+    }
+
+    #[test]
+    fn it_identifies_controller_again_after_soft_reset() {
+        with_fake_driver(|driver, controller| {
+            expect_identification(driver);
+            controller.identify().unwrap();
+
+            expect_soft_reset(driver);
+            expect_identification(driver);
+
+            assert_eq!(Ok(()), controller.soft_reset());
+            assert!(controller.identity().is_some());
+        });
+    }
+
+    #[test]
+    fn it_resets_controller_to_factory_defaults() {
+        with_fake_driver(|driver, controller| {
+            driver.expect_send_with_responses(|message| {
+                assert_eq!(0x01, message.downcast_ref::<SetDefault>().unwrap().callback_id());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(DefaultSet::new(0x01))),
+            ]);
+
+            driver.expect_ack();
+
+            assert_eq!(Ok(()), controller.set_default());
+        });
+    }
+
+    #[test]
+    fn it_notifies_listeners_of_restart() {
+        with_fake_driver(|driver, controller| {
+            let (tx, rx) = channel();
+
+            controller.add_listener(move |event| {
+                if let Event::SerialApiStarted(ref frame) = *event {
+                    tx.send(frame.clone()).unwrap();
+                }
+            });
+
+            driver.expect_ack();
+            driver.push_response(Ok(AnyMessage::new(serial_api_started())));
+
+            assert_eq!(serial_api_started(), rx.recv_timeout(Duration::from_millis(100)).unwrap());
+        });
+    }
+
+    #[test]
+    fn it_identifies_controller_again_after_unexpected_restart() {
+        with_fake_driver(|driver, controller| {
+            let (tx, rx) = channel();
+
+            controller.add_listener(move |event| {
+                if let Event::SerialApiStarted(_) = *event {
+                    tx.send(()).unwrap();
+                }
+            });
+
+            expect_identification(driver);
+            controller.identify().unwrap();
+
+            driver.expect_ack();
+            driver.push_response(Ok(AnyMessage::new(serial_api_started())));
+            rx.recv_timeout(Duration::from_millis(100)).unwrap();
+
+            expect_identification(driver);
+
+            driver.expect_send_with_responses(|message| {
+                assert!(message.is::<SendData>());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(MessageTransmitted::new(0x01))),
+                Ok(AnyMessage::new(MessageReceived::new(0x01, 0x00))),
+            ]);
+
+            driver.expect_ack();
+            driver.expect_ack();
+
+            assert_eq!(Ok(TransmitStatus::Ok), controller.send_data(NodeId(2), SetValue::new(42)));
+        });
+    }
+
+    #[test]
+    fn it_does_not_identify_controller_after_restart_unless_identified_before() {
+        with_fake_driver(|driver, controller| {
+            let (tx, rx) = channel();
+
+            controller.add_listener(move |event| {
+                if let Event::SerialApiStarted(_) = *event {
+                    tx.send(()).unwrap();
+                }
+            });
+
+            driver.expect_ack();
+            driver.push_response(Ok(AnyMessage::new(serial_api_started())));
+            rx.recv_timeout(Duration::from_millis(100)).unwrap();
+
+            driver.expect_send_with_responses(|message| {
+                assert!(message.is::<GetVersion>());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+            ]);
+
+            assert!(controller.request(GetVersion::new()).is_err());
+        });
+    }
+}
+
+mod failed_nodes {
+    use zwave::core::{NodeId, Error, ErrorKind};
+    use zwave::protocol::bits::FailedNodeStatus;
+    use zwave::protocol::message::{AnyMessage, Ack, IsFailedNode, IsFailedNodeResult};
+    use zwave::protocol::message::{RemoveFailedNode, RemoveFailedNodeResult, RemoveFailedNodeUpdate};
+    use zwave::protocol::message::{ReplaceFailedNode, ReplaceFailedNodeResult, ReplaceFailedNodeUpdate};
+
+    use super::{FakeDriver, with_fake_driver};
+
+    fn expect_remove_failed(driver: &mut FakeDriver, flags: u8, status: Option<FailedNodeStatus>) {
+        let mut responses = vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(RemoveFailedNodeResult::new(flags))),
+        ];
+
+        if let Some(status) = status {
+            responses.push(Ok(AnyMessage::new(RemoveFailedNodeUpdate::new(0x01, status))));
+        }
+
+        let acks = responses.len() - 1;
+
+        driver.expect_send_with_responses(|message| {
+            let request = message.downcast_ref::<RemoveFailedNode>().unwrap();
+            assert_eq!(NodeId(5), request.node_id());
+            assert_eq!(0x01, request.callback_id());
+            Ok(())
+        }, responses);
+
+        for _ in 0..acks {
+            driver.expect_ack();
+        }
+    }
+
+    fn expect_replace_failed(driver: &mut FakeDriver, flags: u8, statuses: Vec<FailedNodeStatus>) {
+        let mut responses = vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(ReplaceFailedNodeResult::new(flags))),
+        ];
+
+        responses.extend(statuses.into_iter().map(|status| Ok(AnyMessage::new(ReplaceFailedNodeUpdate::new(0x01, status)))));
+
+        let acks = responses.len() - 1;
+
+        driver.expect_send_with_responses(|message| {
+            let request = message.downcast_ref::<ReplaceFailedNode>().unwrap();
+            assert_eq!(NodeId(5), request.node_id());
+            assert_eq!(0x01, request.callback_id());
+            Ok(())
+        }, responses);
+
+        for _ in 0..acks {
+            driver.expect_ack();
+        }
+    }
+
+    #[test]
+    fn it_reports_failed_node() {
+        with_fake_driver(|driver, controller| {
+            driver.expect_send_with_responses(|message| {
+                assert_eq!(NodeId(5), message.downcast_ref::<IsFailedNode>().unwrap().node_id());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(IsFailedNodeResult::new(true))),
+            ]);
+
+            driver.expect_ack();
+
+            assert_eq!(Ok(true), controller.is_failed(NodeId(5)));
+        });
+    }
+
+    #[test]
+    fn it_removes_failed_node() {
+        with_fake_driver(|driver, controller| {
+            expect_remove_failed(driver, 0x00, Some(FailedNodeStatus::Removed));
+
+            assert_eq!(Ok(FailedNodeStatus::Removed), controller.remove_failed(NodeId(5)));
+        });
+    }
+
+    #[test]
+    fn it_keeps_responding_node() {
+        with_fake_driver(|driver, controller| {
+            expect_remove_failed(driver, 0x00, Some(FailedNodeStatus::NodeOk));
+
+            assert_eq!(Ok(FailedNodeStatus::NodeOk), controller.remove_failed(NodeId(5)));
+        });
+    }
+
+    #[test]
+    fn it_returns_rejected_error_if_removal_is_refused() {
+        with_fake_driver(|driver, controller| {
+            expect_remove_failed(driver, 0x04, None);
+
+            assert_eq!(Err(Error::new(ErrorKind::Rejected)), controller.remove_failed(NodeId(5)));
+        });
+    }
+
+    #[test]
+    fn it_replaces_failed_node() {
+        with_fake_driver(|driver, controller| {
+            expect_replace_failed(driver, 0x00, vec![FailedNodeStatus::Replace, FailedNodeStatus::ReplaceDone]);
+
+            assert_eq!(Ok(FailedNodeStatus::ReplaceDone), controller.replace_failed(NodeId(5)));
+        });
+    }
+
+    #[test]
+    fn it_reports_failed_replacement() {
+        with_fake_driver(|driver, controller| {
+            expect_replace_failed(driver, 0x00, vec![FailedNodeStatus::Replace, FailedNodeStatus::ReplaceFailed]);
+
+            assert_eq!(Ok(FailedNodeStatus::ReplaceFailed), controller.replace_failed(NodeId(5)));
+        });
+    }
+
+    #[test]
+    fn it_returns_rejected_error_if_replacement_is_refused() {
+        with_fake_driver(|driver, controller| {
+            expect_replace_failed(driver, 0x08, vec![]);
+
+            assert_eq!(Err(Error::new(ErrorKind::Rejected)), controller.replace_failed(NodeId(5)));
+        });
+    }
+}
+
+mod network_map {
+    use zwave::core::NodeId;
+    use zwave::network::NodeRole;
+    use zwave::protocol::message::{AnyMessage, Ack, SerialApiGetInitData, SerialApiInitData, NodeProtocolInfo};
+    use zwave::protocol::message::{GetNodeProtocolInfo, GetRoutingInfo, RoutingInfo};
+
+    use super::{FakeDriver, with_fake_driver};
+
+    fn expect_node(driver: &mut FakeDriver, node_id: NodeId, protocol_info: NodeProtocolInfo) {
+        driver.expect_send_with_responses(move |message| {
+            assert_eq!(node_id, message.downcast_ref::<GetNodeProtocolInfo>().unwrap().node_id());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(protocol_info)),
+        ]);
+
+        driver.expect_ack();
+    }
+
+    fn expect_routing_info(driver: &mut FakeDriver, node_id: NodeId, neighbors: Vec<NodeId>) {
+        driver.expect_send_with_responses(move |message| {
+            assert_eq!(node_id, message.downcast_ref::<GetRoutingInfo>().unwrap().node_id());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(RoutingInfo::new(neighbors))),
+        ]);
+
+        driver.expect_ack();
+    }
+
+    #[test]
+    fn it_maps_neighbors_of_every_node() {
+        with_fake_driver(|driver, controller| {
+            driver.expect_send_with_responses(|message| {
+                assert!(message.is::<SerialApiGetInitData>());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(SerialApiInitData::new(0x05, 0x08, vec![NodeId(1), NodeId(5)], 0x05, 0x00))),
+            ]);
+
+            driver.expect_ack();
+            expect_node(driver, NodeId(1), NodeProtocolInfo::new(0xD3, 0x16, 0x01, 0x02, 0x02, 0x07));
+            expect_node(driver, NodeId(5), NodeProtocolInfo::new(0x53, 0x9C, 0x00, 0x04, 0x07, 0x01));
+            expect_routing_info(driver, NodeId(1), vec![NodeId(5)]);
+            expect_routing_info(driver, NodeId(5), vec![NodeId(1)]);
+
+            let network_map = controller.network_map().unwrap();
+
+            assert_eq!(Some(NodeRole::Controller), network_map.role(NodeId(1)));
+            assert_eq!(Some(NodeRole::Sleeping), network_map.role(NodeId(5)));
+            assert_eq!(vec![(NodeId(1), NodeId(5))], network_map.links());
+        });
+    }
+}
+
+mod heal {
+    use std::sync::mpsc::channel;
+    use std::time::Duration;
+
+    use zwave::core::{NodeId, HomeId, Error, ErrorKind};
+    use zwave::io::controller::Event;
+    use zwave::protocol::bits::NeighborUpdateStatus;
+    use zwave::protocol::message::{AnyMessage, Ack, MemoryGetId, MemoryId};
+    use zwave::protocol::message::{SerialApiGetInitData, SerialApiInitData, NodeProtocolInfo};
+    use zwave::protocol::message::{RequestNodeNeighborUpdate, NeighborUpdate};
+    use zwave::protocol::message::{AssignReturnRoute, AssignReturnRouteResult, ReturnRouteAssigned};
+
+    use super::{FakeDriver, with_fake_driver};
+
+    fn expect_neighbor_update(driver: &mut FakeDriver, node_id: NodeId, callback_id: u8, statuses: Vec<NeighborUpdateStatus>) {
+        let acks = statuses.len();
+        let mut responses = vec![Ok(AnyMessage::new(Ack::new()))];
+        responses.extend(statuses.into_iter().map(|status| Ok(AnyMessage::new(NeighborUpdate::new(callback_id, status)))));
+
+        driver.expect_send_with_responses(move |message| {
+            let request = message.downcast_ref::<RequestNodeNeighborUpdate>().unwrap();
+            assert_eq!(node_id, request.node_id());
+            assert_eq!(callback_id, request.callback_id());
+            Ok(())
+        }, responses);
+
+        for _ in 0..acks {
+            driver.expect_ack();
+        }
+    }
+
+    fn expect_return_route(driver: &mut FakeDriver, node_id: NodeId, callback_id: u8) {
+        driver.expect_send_with_responses(move |message| {
+            let request = message.downcast_ref::<AssignReturnRoute>().unwrap();
+            assert_eq!(node_id, request.node_id());
+            assert_eq!(NodeId(1), request.destination());
+            assert_eq!(callback_id, request.callback_id());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(AssignReturnRouteResult::new(0x01))),
+            Ok(AnyMessage::new(ReturnRouteAssigned::new(callback_id, 0x00))),
+        ]);
+
+        driver.expect_ack();
+        driver.expect_ack();
+    }
+
+    fn expect_request(driver: &mut FakeDriver, response: AnyMessage) {
+        driver.expect_send_with_responses(|_| {
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(response),
+        ]);
+
+        driver.expect_ack();
+    }
+
+    fn expect_network(driver: &mut FakeDriver) {
+        driver.expect_send_with_responses(|message| {
+            assert!(message.is::<MemoryGetId>());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(MemoryId::new(HomeId(0xC0FFEE42), NodeId(1)))),
+        ]);
+
+        driver.expect_ack();
+
+        driver.expect_send_with_responses(|message| {
+            assert!(message.is::<SerialApiGetInitData>());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(SerialApiInitData::new(0x05, 0x08, vec![NodeId(1), NodeId(2), NodeId(3), NodeId(4)], 0x05, 0x00))),
+        ]);
+
+        driver.expect_ack();
+
+        // controller, two listening nodes and a sleeping one
+        expect_request(driver, AnyMessage::new(NodeProtocolInfo::new(0xD3, 0x16, 0x01, 0x02, 0x02, 0x07)));
+        expect_request(driver, AnyMessage::new(NodeProtocolInfo::new(0xD3, 0x9C, 0x01, 0x04, 0x10, 0x01)));
+        expect_request(driver, AnyMessage::new(NodeProtocolInfo::new(0xD3, 0x9C, 0x01, 0x04, 0x11, 0x01)));
+        expect_request(driver, AnyMessage::new(NodeProtocolInfo::new(0x53, 0x9C, 0x00, 0x04, 0x07, 0x01)));
+    }
+
+    #[test]
+    fn it_updates_neighbors() {
+        with_fake_driver(|driver, controller| {
+            expect_neighbor_update(driver, NodeId(5), 0x01, vec![NeighborUpdateStatus::Started, NeighborUpdateStatus::Done]);
+
+            assert_eq!(Ok(()), controller.update_neighbors(NodeId(5)));
+        });
+    }
+
+    #[test]
+    fn it_returns_failed_error_if_neighbor_update_fails() {
+        with_fake_driver(|driver, controller| {
+            expect_neighbor_update(driver, NodeId(5), 0x01, vec![NeighborUpdateStatus::Started, NeighborUpdateStatus::Failed]);
+
+            assert_eq!(Err(Error::new(ErrorKind::Failed)), controller.update_neighbors(NodeId(5)));
+        });
+    }
+
+    #[test]
+    fn it_returns_timeout_error_if_neighbor_update_does_not_finish() {
+        with_fake_driver(|driver, controller| {
+            expect_neighbor_update(driver, NodeId(5), 0x01, vec![NeighborUpdateStatus::Started]);
+
+            assert_eq!(Err(Error::new(ErrorKind::Timeout)), controller.update_neighbors(NodeId(5)));
+        });
+    }
+
+    #[test]
+    fn it_heals_listening_nodes() {
+        with_fake_driver(|driver, controller| {
+            expect_network(driver);
+
+            expect_neighbor_update(driver, NodeId(2), 0x01, vec![NeighborUpdateStatus::Started, NeighborUpdateStatus::Done]);
+            expect_return_route(driver, NodeId(2), 0x02);
+            expect_neighbor_update(driver, NodeId(3), 0x03, vec![NeighborUpdateStatus::Started, NeighborUpdateStatus::Failed]);
+
+            let results = controller.heal().unwrap();
+
+            assert_eq!(vec![(NodeId(2), Ok(())), (NodeId(3), Err(Error::new(ErrorKind::Failed)))], results);
+        });
+    }
+
+    #[test]
+    fn it_reports_progress_to_listeners() {
+        with_fake_driver(|driver, controller| {
+            let (tx, rx) = channel();
+
+            controller.add_listener(move |event| {
+                if let Event::HealProgress { node_id, completed, total, result } = *event {
+                    tx.send((node_id, completed, total, result)).unwrap();
+                }
+            });
+
+            expect_network(driver);
+
+            expect_neighbor_update(driver, NodeId(2), 0x01, vec![NeighborUpdateStatus::Done]);
+            expect_return_route(driver, NodeId(2), 0x02);
+            expect_neighbor_update(driver, NodeId(3), 0x03, vec![NeighborUpdateStatus::Done]);
+            expect_return_route(driver, NodeId(3), 0x04);
+
+            controller.heal().unwrap();
+
+            assert_eq!((NodeId(2), 1, 2, Ok(())), rx.recv_timeout(Duration::from_millis(100)).unwrap());
+            assert_eq!((NodeId(3), 2, 2, Ok(())), rx.recv_timeout(Duration::from_millis(100)).unwrap());
+        });
+    }
+}
+
+mod return_routes {
+    use zwave::core::{NodeId, Error, ErrorKind};
+    use zwave::protocol::bits::TransmitStatus;
+    use zwave::protocol::message::{AnyMessage, Ack};
+    use zwave::protocol::message::{DeleteReturnRoute, DeleteReturnRouteResult, ReturnRouteDeleted};
+    use zwave::protocol::message::{AssignSucReturnRoute, AssignSucReturnRouteResult, SucReturnRouteAssigned};
+    use zwave::protocol::message::{DeleteSucReturnRoute, DeleteSucReturnRouteResult, SucReturnRouteDeleted};
+
+    use super::with_fake_driver;
+
+    #[test]
+    fn it_deletes_return_routes() {
+        with_fake_driver(|driver, controller| {
+            driver.expect_send_with_responses(|message| {
+                let request = message.downcast_ref::<DeleteReturnRoute>().unwrap();
+                assert_eq!(NodeId(5), request.node_id());
+                assert_eq!(0x01, request.callback_id());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(DeleteReturnRouteResult::new(0x01))),
+                Ok(AnyMessage::new(ReturnRouteDeleted::new(0x01, 0x00))),
+            ]);
+
+            driver.expect_ack();
+            driver.expect_ack();
+
+            assert_eq!(TransmitStatus::Ok, controller.delete_return_routes(NodeId(5)).unwrap());
+        });
+    }
+
+    #[test]
+    fn it_assigns_suc_return_route() {
+        with_fake_driver(|driver, controller| {
+            driver.expect_send_with_responses(|message| {
+                let request = message.downcast_ref::<AssignSucReturnRoute>().unwrap();
+                assert_eq!(NodeId(5), request.node_id());
+                assert_eq!(0x01, request.callback_id());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(AssignSucReturnRouteResult::new(0x01))),
+                Ok(AnyMessage::new(SucReturnRouteAssigned::new(0x01, 0x01))),
+            ]);
+
+            driver.expect_ack();
+            driver.expect_ack();
+
+            assert_eq!(TransmitStatus::NoAck, controller.assign_suc_return_route(NodeId(5)).unwrap());
+        });
+    }
+
+    #[test]
+    fn it_deletes_suc_return_route() {
+        with_fake_driver(|driver, controller| {
+            driver.expect_send_with_responses(|message| {
+                let request = message.downcast_ref::<DeleteSucReturnRoute>().unwrap();
+                assert_eq!(NodeId(5), request.node_id());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(DeleteSucReturnRouteResult::new(0x01))),
+                Ok(AnyMessage::new(SucReturnRouteDeleted::new(0x01, 0x00))),
+            ]);
+
+            driver.expect_ack();
+            driver.expect_ack();
+
+            assert_eq!(TransmitStatus::Ok, controller.delete_suc_return_route(NodeId(5)).unwrap());
+        });
+    }
+
+    #[test]
+    fn it_returns_rejected_error_if_request_is_refused() {
+        with_fake_driver(|driver, controller| {
+            driver.expect_send_with_responses(|_| {
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(DeleteReturnRouteResult::new(0x00))),
+            ]);
+
+            driver.expect_ack();
+
+            assert_eq!(Err(Error::new(ErrorKind::Rejected)), controller.delete_return_routes(NodeId(5)));
+        });
+    }
+}
+
+mod suc {
+    use zwave::core::{NodeId, HomeId, Error, ErrorKind};
+    use zwave::protocol::bits::SetSucStatus;
+    use zwave::protocol::message::{AnyMessage, Ack, MemoryGetId, MemoryId};
+    use zwave::protocol::message::{GetSucNodeId, SucNodeId, EnableSuc, EnableSucResult};
+    use zwave::protocol::message::{SetSucNodeId, SetSucNodeIdResult, SucNodeIdSet};
+    use zwave::protocol::message::{GetControllerCapabilities, ControllerCapabilities};
+
+    use super::{FakeDriver, with_fake_driver};
+
+    fn expect_suc_node_id(driver: &mut FakeDriver, node_id: Option<NodeId>) {
+        driver.expect_send_with_responses(|message| {
+            assert!(message.is::<GetSucNodeId>());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(SucNodeId::new(node_id))),
+        ]);
+
+        driver.expect_ack();
+    }
+
+    fn expect_memory_id(driver: &mut FakeDriver) {
+        driver.expect_send_with_responses(|message| {
+            assert!(message.is::<MemoryGetId>());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(MemoryId::new(HomeId(0xC0FFEE42), NodeId(1)))),
+        ]);
+
+        driver.expect_ack();
+    }
+
+    fn expect_controller_capabilities(driver: &mut FakeDriver, capabilities: u8) {
+        driver.expect_send_with_responses(|message| {
+            assert!(message.is::<GetControllerCapabilities>());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(ControllerCapabilities::new(capabilities))),
+        ]);
+
+        driver.expect_ack();
+    }
+
+    fn expect_enable_sis(driver: &mut FakeDriver) {
+        driver.expect_send_with_responses(|message| {
+            let request = message.downcast_ref::<EnableSuc>().unwrap();
+            assert!(request.enable());
+            assert!(request.sis());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(EnableSucResult::new(0x01))),
+        ]);
+
+        driver.expect_ack();
+    }
+
+    fn expect_set_sis(driver: &mut FakeDriver) {
+        driver.expect_send_with_responses(|message| {
+            let request = message.downcast_ref::<SetSucNodeId>().unwrap();
+            assert_eq!(NodeId(1), request.node_id());
+            assert!(request.enable());
+            assert!(request.sis());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(SetSucNodeIdResult::new(0x01))),
+        ]);
+
+        driver.expect_ack();
+    }
+
+    #[test]
+    fn it_keeps_existing_sis() {
+        with_fake_driver(|driver, controller| {
+            expect_suc_node_id(driver, Some(NodeId(7)));
+            expect_controller_capabilities(driver, 0x04);
+
+            assert_eq!(NodeId(7), controller.ensure_sis().unwrap());
+        });
+    }
+
+    #[test]
+    fn it_returns_conflict_error_if_other_node_is_suc_without_sis() {
+        with_fake_driver(|driver, controller| {
+            expect_suc_node_id(driver, Some(NodeId(7)));
+            expect_controller_capabilities(driver, 0x00);
+            expect_memory_id(driver);
+
+            assert_eq!(Err(Error::new(ErrorKind::Conflict)), controller.ensure_sis());
+        });
+    }
+
+    #[test]
+    fn it_becomes_sis_if_it_is_suc_without_sis() {
+        with_fake_driver(|driver, controller| {
+            expect_suc_node_id(driver, Some(NodeId(1)));
+            expect_controller_capabilities(driver, 0x10);
+            expect_memory_id(driver);
+            expect_enable_sis(driver);
+            expect_memory_id(driver);
+            expect_set_sis(driver);
+
+            assert_eq!(NodeId(1), controller.ensure_sis().unwrap());
+        });
+    }
+
+    #[test]
+    fn it_becomes_sis_if_network_has_no_suc() {
+        with_fake_driver(|driver, controller| {
+            expect_suc_node_id(driver, None);
+            expect_memory_id(driver);
+            expect_enable_sis(driver);
+            expect_memory_id(driver);
+            expect_set_sis(driver);
+
+            assert_eq!(NodeId(1), controller.ensure_sis().unwrap());
+        });
+    }
+
+    #[test]
+    fn it_returns_rejected_error_if_suc_cannot_be_enabled() {
+        with_fake_driver(|driver, controller| {
+            expect_suc_node_id(driver, None);
+            expect_memory_id(driver);
+
+            driver.expect_send_with_responses(|_| {
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(EnableSucResult::new(0x00))),
+            ]);
+
+            driver.expect_ack();
+
+            assert_eq!(Err(Error::new(ErrorKind::Rejected)), controller.ensure_sis());
+        });
+    }
+
+    #[test]
+    fn it_waits_for_callback_when_setting_other_controller() {
+        with_fake_driver(|driver, controller| {
+            expect_memory_id(driver);
+
+            driver.expect_send_with_responses(|message| {
+                let request = message.downcast_ref::<SetSucNodeId>().unwrap();
+                assert_eq!(NodeId(7), request.node_id());
+                assert_eq!(0x01, request.callback_id());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(SetSucNodeIdResult::new(0x01))),
+                Ok(AnyMessage::new(SucNodeIdSet::new(0x01, SetSucStatus::Failed))),
+            ]);
+
+            driver.expect_ack();
+            driver.expect_ack();
+
+            assert_eq!(Err(Error::new(ErrorKind::Failed)), controller.set_suc_node_id(NodeId(7), true, true));
+        });
+    }
+}
+
+mod nvm {
+    use std::env;
+    use std::fs::{self, File};
+    use std::io::{Read, Write};
+    use std::path::PathBuf;
+    use std::process;
+    use std::sync::mpsc::channel;
+
+    use zwave::core::{NodeId, HomeId, Error, ErrorKind};
+    use zwave::io::controller::{Event, NvmTransfer};
+    use zwave::protocol::bits::{FunctionId, NvmOperation, NvmStatus};
+    use zwave::protocol::message::{Message, AnyMessage, Ack, GetVersion, Version, MemoryGetId, MemoryId};
+    use zwave::protocol::message::{SerialApiGetCapabilities, SerialApiCapabilities, SoftReset, SerialApiStarted};
+    use zwave::protocol::message::{GetNvmId, NvmId, ExtNvmReadLongBuffer, NvmBuffer};
+    use zwave::protocol::message::{ExtNvmWriteLongBuffer, ExtNvmWriteLongBufferResult};
+    use zwave::protocol::message::{NvmBackupRestore, NvmBackupRestoreResult};
+
+    use super::{FakeDriver, with_fake_driver};
+
+    const SIZE: usize = 80;
+
+    fn image() -> Vec<u8> {
+        (0..SIZE).map(|index| index as u8).collect()
+    }
+
+    /// A file in the temp directory that no other test process uses, removed when dropped.
+    struct TempFile {
+        path: PathBuf,
+    }
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            TempFile {
+                path: env::temp_dir().join(format!("zwave-nvm-{}-{}.bin", process::id(), name)),
+            }
+        }
+
+        fn with_contents(name: &str, contents: &[u8]) -> Self {
+            let file = TempFile::new(name);
+            File::create(&file.path).unwrap().write_all(contents).unwrap();
+            file
+        }
+
+        fn path(&self) -> &PathBuf {
+            &self.path
+        }
+
+        fn contents(&self) -> Vec<u8> {
+            let mut contents = Vec::new();
+            File::open(&self.path).unwrap().read_to_end(&mut contents).unwrap();
+            contents
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+
+    fn expect_request<F: Fn(&Message) + Send + 'static>(driver: &mut FakeDriver, check: F, response: AnyMessage) {
+        driver.expect_send_with_responses(move |message| {
+            check(message);
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(response),
+        ]);
+
+        driver.expect_ack();
+    }
+
+    fn extended_nvm_functions() -> Vec<FunctionId> {
+        vec![FunctionId::GetNvmId, FunctionId::ExtNvmReadLongBuffer, FunctionId::ExtNvmWriteLongBuffer]
+    }
+
+    fn expect_identification(driver: &mut FakeDriver, functions: Vec<FunctionId>) {
+        let mut function_mask = [0u8; 32];
+
+        for function_id in vec![FunctionId::GetVersion, FunctionId::MemoryGetId, FunctionId::SerialApiGetCapabilities, FunctionId::SoftReset].into_iter().chain(functions) {
+            let bit = function_id as usize - 1;
+            function_mask[bit / 8] |= 1 << (bit % 8);
+        }
+
+        expect_request(driver, |message| assert!(message.is::<GetVersion>()), AnyMessage::new(Version::new("Z-Wave 7.16", 0x07)));
+        expect_request(driver, |message| assert!(message.is::<MemoryGetId>()), AnyMessage::new(MemoryId::new(HomeId(0xC0FFEE42), NodeId(1))));
+        expect_request(driver, |message| assert!(message.is::<SerialApiGetCapabilities>()), AnyMessage::new(SerialApiCapabilities::new(7, 16, 0x0000, 0x0004, 0x0004, function_mask)));
+    }
+
+    fn expect_nvm(driver: &mut FakeDriver, operation: NvmOperation, offset: u16, data: Vec<u8>, response: NvmBackupRestoreResult) {
+        expect_request(driver, move |message| {
+            let request = message.downcast_ref::<NvmBackupRestore>().unwrap();
+            assert_eq!(operation, request.operation());
+            assert_eq!(offset, request.offset());
+            assert_eq!(&data[..], request.data());
+        }, AnyMessage::new(response));
+    }
+
+    fn expect_backup(driver: &mut FakeDriver, image: &[u8]) {
+        expect_nvm(driver, NvmOperation::Open, 0, vec![], NvmBackupRestoreResult::new(NvmStatus::Ok, SIZE as u16, vec![]));
+        expect_nvm(driver, NvmOperation::Read, 0, vec![], NvmBackupRestoreResult::new(NvmStatus::Ok, 0, image[..64].to_vec()));
+        expect_nvm(driver, NvmOperation::Read, 64, vec![], NvmBackupRestoreResult::new(NvmStatus::EndOfFile, 64, image[64..].to_vec()));
+        expect_nvm(driver, NvmOperation::Close, 0, vec![], NvmBackupRestoreResult::new(NvmStatus::Ok, 0, vec![]));
+    }
+
+    fn expect_restore(driver: &mut FakeDriver, image: &[u8]) {
+        expect_nvm(driver, NvmOperation::Open, 0, vec![], NvmBackupRestoreResult::new(NvmStatus::Ok, SIZE as u16, vec![]));
+        expect_nvm(driver, NvmOperation::Write, 0, image[..64].to_vec(), NvmBackupRestoreResult::new(NvmStatus::Ok, 0, vec![]));
+        expect_nvm(driver, NvmOperation::Write, 64, image[64..].to_vec(), NvmBackupRestoreResult::new(NvmStatus::EndOfFile, 64, vec![]));
+        expect_nvm(driver, NvmOperation::Close, 0, vec![], NvmBackupRestoreResult::new(NvmStatus::Ok, 0, vec![]));
+    }
+
+    #[test]
+    fn it_backs_up_nvm_in_chunks() {
+        with_fake_driver(|driver, controller| {
+            expect_identification(driver, vec![FunctionId::NvmBackupRestore]);
+            expect_backup(driver, &image());
+            expect_backup(driver, &image());
+
+            let file = TempFile::new("backup");
+
+            assert_eq!(Ok(()), controller.backup_nvm(file.path()));
+            assert_eq!(image(), file.contents());
+        });
+    }
+
+    #[test]
+    fn it_returns_corrupt_error_if_backup_reads_back_differently() {
+        with_fake_driver(|driver, controller| {
+            let mut different = image();
+            different[70] = 0xFF;
+
+            expect_identification(driver, vec![FunctionId::NvmBackupRestore]);
+            expect_backup(driver, &image());
+            expect_backup(driver, &different);
+
+            let file = TempFile::new("backup-mismatch");
+
+            assert_eq!(Err(Error::new(ErrorKind::Corrupt)), controller.backup_nvm(file.path()));
+            assert!(!file.path().exists());
+        });
+    }
+
+    #[test]
+    fn it_reports_backup_progress() {
+        with_fake_driver(|driver, controller| {
+            let (tx, rx) = channel();
+
+            controller.add_listener(move |event| {
+                if let Event::NvmProgress { transfer, completed, total } = *event {
+                    tx.send((transfer, completed, total)).unwrap();
+                }
+            });
+
+            expect_identification(driver, vec![FunctionId::NvmBackupRestore]);
+            expect_backup(driver, &image());
+            expect_backup(driver, &image());
+
+            let file = TempFile::new("progress");
+
+            controller.backup_nvm(file.path()).unwrap();
+
+            assert_eq!((NvmTransfer::Backup, 64, SIZE), rx.try_recv().unwrap());
+            assert_eq!((NvmTransfer::Backup, SIZE, SIZE), rx.try_recv().unwrap());
+            assert_eq!((NvmTransfer::Verify, 64, SIZE), rx.try_recv().unwrap());
+            assert_eq!((NvmTransfer::Verify, SIZE, SIZE), rx.try_recv().unwrap());
+        });
+    }
+
+    #[test]
+    fn it_backs_up_500_series_nvm() {
+        with_fake_driver(|driver, controller| {
+            expect_identification(driver, extended_nvm_functions());
+
+            for _ in 0..2 {
+                // 2^7 = 128 bytes
+                expect_request(driver, |message| assert!(message.is::<GetNvmId>()), AnyMessage::new(NvmId::new(0xEF, 0x01, 0x07)));
+
+                for offset in vec![0, 64] {
+                    expect_request(driver, move |message| {
+                        let request = message.downcast_ref::<ExtNvmReadLongBuffer>().unwrap();
+                        assert_eq!(offset, request.offset());
+                        assert_eq!(64, request.length());
+                    }, AnyMessage::new(NvmBuffer::new(vec![offset as u8; 64])));
+                }
+            }
+
+            let file = TempFile::new("backup-500");
+
+            assert_eq!(Ok(()), controller.backup_nvm(file.path()));
+            assert_eq!([vec![0x00; 64], vec![0x40; 64]].concat(), file.contents());
+        });
+    }
+
+    #[test]
+    fn it_returns_unsupported_error_without_nvm_functions() {
+        with_fake_driver(|driver, controller| {
+            expect_identification(driver, vec![]);
+
+            let file = TempFile::new("unsupported");
+
+            assert_eq!(Err(Error::new(ErrorKind::Unsupported)), controller.backup_nvm(file.path()));
+        });
+    }
+
+    #[test]
+    fn it_restores_and_verifies_nvm() {
+        with_fake_driver(|driver, controller| {
+            expect_identification(driver, vec![FunctionId::NvmBackupRestore]);
+            expect_restore(driver, &image());
+            expect_backup(driver, &image());
+
+            expect_request(driver, |message| assert!(message.is::<SoftReset>()), AnyMessage::new(SerialApiStarted::new(0x00, false, 0x01, 0x02, 0x07, vec![], false)));
+            expect_identification(driver, vec![FunctionId::NvmBackupRestore]);
+
+            let file = TempFile::with_contents("restore", &image());
+
+            assert_eq!(Ok(()), controller.restore_nvm(file.path()));
+        });
+    }
+
+    #[test]
+    fn it_restores_500_series_nvm() {
+        with_fake_driver(|driver, controller| {
+            let image = vec![0x5A; 128];
+
+            expect_identification(driver, extended_nvm_functions());
+            expect_request(driver, |message| assert!(message.is::<GetNvmId>()), AnyMessage::new(NvmId::new(0xEF, 0x01, 0x07)));
+
+            for offset in vec![0, 64] {
+                expect_request(driver, move |message| {
+                    let request = message.downcast_ref::<ExtNvmWriteLongBuffer>().unwrap();
+                    assert_eq!(offset, request.offset());
+                    assert_eq!(&[0x5A; 64][..], request.data());
+                }, AnyMessage::new(ExtNvmWriteLongBufferResult::new(0x01)));
+            }
+
+            expect_request(driver, |message| assert!(message.is::<GetNvmId>()), AnyMessage::new(NvmId::new(0xEF, 0x01, 0x07)));
+
+            for _ in 0..2 {
+                expect_request(driver, |message| assert!(message.is::<ExtNvmReadLongBuffer>()), AnyMessage::new(NvmBuffer::new(vec![0x5A; 64])));
+            }
+
+            expect_request(driver, |message| assert!(message.is::<SoftReset>()), AnyMessage::new(SerialApiStarted::new(0x00, false, 0x01, 0x02, 0x07, vec![], false)));
+            expect_identification(driver, extended_nvm_functions());
+
+            let file = TempFile::with_contents("restore-500", &image);
+
+            assert_eq!(Ok(()), controller.restore_nvm(file.path()));
+        });
+    }
+
+    #[test]
+    fn it_returns_corrupt_error_for_image_of_wrong_size() {
+        with_fake_driver(|driver, controller| {
+            expect_identification(driver, vec![FunctionId::NvmBackupRestore]);
+
+            expect_nvm(driver, NvmOperation::Open, 0, vec![], NvmBackupRestoreResult::new(NvmStatus::Ok, SIZE as u16, vec![]));
+            expect_nvm(driver, NvmOperation::Close, 0, vec![], NvmBackupRestoreResult::new(NvmStatus::Ok, 0, vec![]));
+
+            let file = TempFile::with_contents("wrong-size", &[0x00; 16]);
+
+            assert_eq!(Err(Error::new(ErrorKind::Corrupt)), controller.restore_nvm(file.path()));
+        });
+    }
+
+    #[test]
+    fn it_returns_corrupt_error_if_nvm_reads_back_differently() {
+        with_fake_driver(|driver, controller| {
+            let mut different = image();
+            different[70] = 0xFF;
+
+            expect_identification(driver, vec![FunctionId::NvmBackupRestore]);
+            expect_restore(driver, &image());
+            expect_backup(driver, &different);
+
+            let file = TempFile::with_contents("mismatch", &image());
+
+            assert_eq!(Err(Error::new(ErrorKind::Corrupt)), controller.restore_nvm(file.path()));
+        });
+    }
+
+    #[test]
+    fn it_returns_failed_error_if_write_fails() {
+        with_fake_driver(|driver, controller| {
+            expect_identification(driver, vec![FunctionId::NvmBackupRestore]);
+
+            expect_nvm(driver, NvmOperation::Open, 0, vec![], NvmBackupRestoreResult::new(NvmStatus::Ok, SIZE as u16, vec![]));
+            expect_nvm(driver, NvmOperation::Write, 0, image()[..64].to_vec(), NvmBackupRestoreResult::new(NvmStatus::Error, 0, vec![]));
+            expect_nvm(driver, NvmOperation::Close, 0, vec![], NvmBackupRestoreResult::new(NvmStatus::Ok, 0, vec![]));
+
+            let file = TempFile::with_contents("write-fails", &image());
+
+            assert_eq!(Err(Error::new(ErrorKind::Failed)), controller.restore_nvm(file.path()));
+        });
+    }
+}
+
+mod serial_api_setup {
+    use zwave::core::{Error, ErrorKind};
+    use zwave::protocol::bits::{SerialApiSetupCommand, RfRegion, NodeIdType};
+    use zwave::protocol::message::{Message, AnyMessage, Ack, SoftReset, SerialApiStarted};
+    use zwave::protocol::message::{SerialApiSetup, SerialApiSetupResult, TxPower};
+
+    use super::{FakeDriver, with_fake_driver};
+
+    fn expect_setup(driver: &mut FakeDriver, request: SerialApiSetup, response: SerialApiSetupResult) {
+        driver.expect_send_with_responses(move |message: &Message| {
+            assert_eq!(&request, message.downcast_ref::<SerialApiSetup>().unwrap());
+            Ok(())
+        }, vec![
+            Ok(AnyMessage::new(Ack::new())),
+            Ok(AnyMessage::new(response)),
+        ]);
+
+        driver.expect_ack();
+    }
+
+    #[test]
+    fn it_gets_rf_region() {
+        with_fake_driver(|driver, controller| {
+            expect_setup(driver, SerialApiSetup::GetRfRegion, SerialApiSetupResult::RfRegion(RfRegion::Europe));
+
+            assert_eq!(Ok(RfRegion::Europe), controller.get_rf_region());
+        });
+    }
+
+    #[test]
+    fn it_restarts_controller_after_setting_rf_region() {
+        with_fake_driver(|driver, controller| {
+            expect_setup(driver, SerialApiSetup::SetRfRegion(RfRegion::Usa), SerialApiSetupResult::Set(SerialApiSetupCommand::SetRfRegion, true));
+
+            driver.expect_send_with_responses(|message| {
+                assert!(message.is::<SoftReset>());
+                Ok(())
+            }, vec![
+                Ok(AnyMessage::new(Ack::new())),
+                Ok(AnyMessage::new(SerialApiStarted::new(0x00, false, 0x01, 0x02, 0x07, vec![], false))),
+            ]);
+
+            driver.expect_ack();
+
+            assert_eq!(Ok(()), controller.set_rf_region(RfRegion::Usa));
+        });
+    }
+
+    #[test]
+    fn it_returns_rejected_error_if_rf_region_is_not_set() {
+        with_fake_driver(|driver, controller| {
+            expect_setup(driver, SerialApiSetup::SetRfRegion(RfRegion::Japan), SerialApiSetupResult::Set(SerialApiSetupCommand::SetRfRegion, false));
+
+            assert_eq!(Err(Error::new(ErrorKind::Rejected)), controller.set_rf_region(RfRegion::Japan));
+        });
+    }
+
+    #[test]
+    fn it_returns_unsupported_error_for_unknown_subcommand() {
+        with_fake_driver(|driver, controller| {
+            expect_setup(driver, SerialApiSetup::GetRfRegion, SerialApiSetupResult::Unsupported(SerialApiSetupCommand::GetRfRegion as u8));
+
+            assert_eq!(Err(Error::new(ErrorKind::Unsupported)), controller.get_rf_region());
+        });
+    }
+
+    #[test]
+    fn it_gets_and_sets_tx_power() {
+        with_fake_driver(|driver, controller| {
+            expect_setup(driver, SerialApiSetup::GetTxPower, SerialApiSetupResult::TxPower(TxPower::new(0, 33)));
+            expect_setup(driver, SerialApiSetup::SetTxPower(TxPower::new(-10, 33)), SerialApiSetupResult::Set(SerialApiSetupCommand::SetTxPower, true));
+
+            let tx_power = controller.get_tx_power().unwrap();
+
+            assert_eq!(33, tx_power.measured_0dbm());
+            assert_eq!(Ok(()), controller.set_tx_power(TxPower::new(-10, tx_power.measured_0dbm())));
+        });
+    }
+
+    #[test]
+    fn it_gets_and_sets_long_range_max_tx_power() {
+        with_fake_driver(|driver, controller| {
+            expect_setup(driver, SerialApiSetup::GetLongRangeMaxTxPower, SerialApiSetupResult::LongRangeMaxTxPower(140));
+            expect_setup(driver, SerialApiSetup::SetLongRangeMaxTxPower(200), SerialApiSetupResult::Set(SerialApiSetupCommand::SetLongRangeMaxTxPower, true));
+
+            assert_eq!(Ok(140), controller.get_long_range_max_tx_power());
+            assert_eq!(Ok(()), controller.set_long_range_max_tx_power(200));
+        });
+    }
+
+    #[test]
+    fn it_sets_node_id_type() {
+        with_fake_driver(|driver, controller| {
+            expect_setup(driver, SerialApiSetup::SetNodeIdType(NodeIdType::Long), SerialApiSetupResult::Set(SerialApiSetupCommand::SetNodeIdType, true));
+
+            assert_eq!(Ok(()), controller.set_node_id_type(NodeIdType::Long));
+        });
+    }
+
+    #[test]
+    fn it_returns_protocol_error_for_mismatched_response() {
+        with_fake_driver(|driver, controller| {
+            expect_setup(driver, SerialApiSetup::GetTxPower, SerialApiSetupResult::RfRegion(RfRegion::Europe));
+
+            assert_eq!(Err(Error::new(ErrorKind::Protocol)), controller.get_tx_power());
         });
     }
 }
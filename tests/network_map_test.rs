@@ -0,0 +1,93 @@
+extern crate zwave;
+
+use zwave::core::NodeId;
+use zwave::network::{NetworkMap, NodeRole};
+use zwave::protocol::message::NodeProtocolInfo;
+
+fn network_map() -> NetworkMap {
+    let mut network_map = NetworkMap::new();
+
+    network_map.add_node(NodeId(1), NodeRole::Controller, vec![NodeId(2), NodeId(3)]);
+    network_map.add_node(NodeId(2), NodeRole::Listening, vec![NodeId(1), NodeId(3)]);
+    network_map.add_node(NodeId(3), NodeRole::Sleeping, vec![NodeId(2)]);
+
+    network_map
+}
+
+mod node_role {
+    use zwave::network::NodeRole;
+    use zwave::protocol::message::NodeProtocolInfo;
+
+    #[test]
+    fn it_recognizes_controllers() {
+        assert_eq!(NodeRole::Controller, NodeRole::of(&NodeProtocolInfo::new(0xD3, 0x16, 0x01, 0x02, 0x02, 0x07)));
+    }
+
+    #[test]
+    fn it_recognizes_listening_nodes() {
+        assert_eq!(NodeRole::Listening, NodeRole::of(&NodeProtocolInfo::new(0xD3, 0x9C, 0x01, 0x04, 0x10, 0x01)));
+    }
+
+    #[test]
+    fn it_recognizes_sleeping_nodes() {
+        assert_eq!(NodeRole::Sleeping, NodeRole::of(&NodeProtocolInfo::new(0x53, 0x9C, 0x00, 0x04, 0x07, 0x01)));
+    }
+}
+
+#[test]
+fn it_lists_nodes() {
+    let network_map = network_map();
+
+    assert_eq!(vec![NodeId(1), NodeId(2), NodeId(3)], network_map.node_ids());
+    assert_eq!(Some(NodeRole::Listening), network_map.role(NodeId(2)));
+    assert_eq!(Some(&[NodeId(1), NodeId(3)][..]), network_map.neighbors(NodeId(2)));
+    assert_eq!(None, network_map.role(NodeId(4)));
+}
+
+#[test]
+fn it_lists_each_link_once() {
+    let mut network_map = network_map();
+
+    network_map.add_node(NodeId(4), NodeRole::Sleeping, vec![NodeId(1)]);
+
+    assert_eq!(vec![(NodeId(1), NodeId(2)), (NodeId(1), NodeId(3)), (NodeId(1), NodeId(4)), (NodeId(2), NodeId(3))], network_map.links());
+}
+
+#[test]
+fn it_renders_dot() {
+    let expected = concat!(
+        "graph zwave {\n",
+        "    1 [shape=doublecircle, style=filled, fillcolor=lightblue];\n",
+        "    2 [shape=circle, style=filled, fillcolor=palegreen];\n",
+        "    3 [shape=circle, style=dashed];\n",
+        "    1 -- 2;\n",
+        "    1 -- 3;\n",
+        "    2 -- 3;\n",
+        "}\n");
+
+    assert_eq!(expected, network_map().to_dot());
+}
+
+#[test]
+fn it_renders_json() {
+    let expected = concat!(
+        "{\"nodes\":[{\"id\":1,\"role\":\"controller\"},{\"id\":2,\"role\":\"listening\"},{\"id\":3,\"role\":\"sleeping\"}],",
+        "\"links\":[{\"source\":1,\"target\":2},{\"source\":1,\"target\":3},{\"source\":2,\"target\":3}]}");
+
+    assert_eq!(expected, network_map().to_json());
+}
+
+#[test]
+fn it_renders_empty_map() {
+    assert_eq!("{\"nodes\":[],\"links\":[]}", NetworkMap::new().to_json());
+    assert_eq!("graph zwave {\n}\n", NetworkMap::new().to_dot());
+}
+
+#[test]
+fn it_takes_role_from_protocol_info() {
+    let mut network_map = NetworkMap::new();
+
+    network_map.add_node(NodeId(5), NodeRole::of(&NodeProtocolInfo::new(0x53, 0x9C, 0x00, 0x04, 0x07, 0x01)), vec![]);
+
+    assert_eq!(Some(NodeRole::Sleeping), network_map.role(NodeId(5)));
+}
@@ -0,0 +1,336 @@
+extern crate zwave;
+
+use zwave::core::{HomeId, NodeId};
+use zwave::nvm::{NetworkData, NodeData, Route};
+use zwave::protocol::message::NodeProtocolInfo;
+
+const SIZE_500: usize = 0x4000;
+const SIZE_700: usize = 0x10000;
+const SIZE_800: usize = 0x10000;
+
+fn network() -> NetworkData {
+    let controller = NodeData::new(NodeId(1), NodeProtocolInfo::new(0xD3, 0x16, 0x01, 0x02, 0x02, 0x01), vec![NodeId(2), NodeId(5)], None);
+    let switch = NodeData::new(NodeId(2), NodeProtocolInfo::new(0xD3, 0x1C, 0x01, 0x04, 0x10, 0x01), vec![NodeId(1), NodeId(5)], Some(Route::new(vec![], 0x03)));
+    let sensor = NodeData::new(NodeId(5), NodeProtocolInfo::new(0x53, 0x1C, 0x01, 0x04, 0x21, 0x01), vec![NodeId(1), NodeId(2)], Some(Route::new(vec![NodeId(2)], 0x02)));
+    let remote = NodeData::new(NodeId(232), NodeProtocolInfo::new(0x13, 0x00, 0x00, 0x03, 0x18, 0x01), vec![], None);
+
+    NetworkData::new(HomeId(0xC0FFEE42), NodeId(1), Some(NodeId(1)), vec![controller, switch, sensor, remote])
+}
+
+/// The network in the fixtures below.
+fn fixture_network() -> NetworkData {
+    let controller = NodeData::new(NodeId(1), NodeProtocolInfo::new(0xD3, 0x16, 0x01, 0x02, 0x02, 0x01), vec![NodeId(2)], None);
+    let switch = NodeData::new(NodeId(2), NodeProtocolInfo::new(0xD3, 0x1C, 0x01, 0x04, 0x10, 0x01), vec![NodeId(1)], Some(Route::new(vec![], 0x03)));
+
+    NetworkData::new(HomeId(0xC0FFEE42), NodeId(1), Some(NodeId(1)), vec![controller, switch])
+}
+
+/// Lays out a 500-series image by hand at the entry offsets of the static controller firmware.
+fn fixture_500(configuration_valid: u8) -> Vec<u8> {
+    let mut image = vec![0xFF; SIZE_500];
+
+    for byte in &mut image[..0x2F18] {
+        *byte = 0x00;
+    }
+
+    image[0x0000..0x0004].copy_from_slice(&[0x3F, 0xFF, 0x2F, 0x18]); // total end, module size
+    image[0x0008..0x000C].copy_from_slice(&[0xC0, 0xFF, 0xEE, 0x42]); // extended home ID
+    image[0x0010..0x0014].copy_from_slice(&[0xC0, 0xFF, 0xEE, 0x42]); // home ID
+    image[0x0014..0x0017].copy_from_slice(&[0x01, configuration_valid, 0xA5]); // node ID, configuration valid
+    image[0x0052..0x005C].copy_from_slice(&[0xD3, 0x16, 0x01, 0x02, 0x01, 0xD3, 0x1C, 0x01, 0x10, 0x01]); // node table
+    image[0x04DA] = 0x02; // routing table
+    image[0x04F7] = 0x01;
+    image[0x25C7] = 0x02; // max node ID
+    image[0x25C9] = 0x02; // last used node ID
+    image[0x25CA] = 0x01; // SUC node ID
+    image[0x25ED..0x25F2].copy_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x03]); // route cache
+    image[0x2F16..0x2F18].copy_from_slice(&[0x2F, 0x18]); // module size end marker
+
+    image
+}
+
+/// Lays out a 700-series image by hand as NVM3 pages, with the protocol's files behind the
+/// application area.
+fn fixture_700(protocol_version: Option<[u8; 4]>) -> Vec<u8> {
+    const PAGE_HEADER: [u8; 20] = [
+        0x01, 0x00, 0x9A, 0xB2, // version, magic
+        0x00, 0x00, 0x00, 0xD8, // erase count
+        0xFF, 0xFF, 0xFF, 0x07, // inverted erase count
+        0xFF, 0xFF, 0xFF, 0xFF, // status
+        0xFF, 0x57, 0xFF, 0xFF, // device info, format
+    ];
+
+    let mut image = vec![0xFF; SIZE_700];
+
+    for page in image.chunks_mut(0x800) {
+        page[..20].copy_from_slice(&PAGE_HEADER);
+    }
+
+    let mut node_list = [0x00; 29];
+    node_list[0] = 0x03;
+
+    let mut route_cache_exists = [0x00; 29];
+    route_cache_exists[0] = 0x02;
+
+    let mut controller_info = vec![0xD3, 0x16, 0x01, 0x02, 0x01];
+    controller_info.push(0x02); // neighbors
+    controller_info.extend_from_slice(&[0x00; 28]);
+    controller_info.push(0x00); // SUC update index
+
+    let mut switch_info = vec![0xD3, 0x1C, 0x01, 0x10, 0x01];
+    switch_info.push(0x01); // neighbors
+    switch_info.extend_from_slice(&[0x00; 28]);
+    switch_info.push(0x00); // SUC update index
+
+    let mut objects: Vec<([u8; 4], Vec<u8>)> = vec![];
+
+    if let Some(protocol_version) = protocol_version {
+        objects.push(([0x0B, 0x00, 0x80, 0xB2], protocol_version.to_vec())); // 0x50000
+    }
+
+    objects.push(([0x14, 0x02, 0x80, 0xB2], vec![0xC0, 0xFF, 0xEE, 0x42, 0x01, 0x02, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00])); // 0x50004
+    objects.push(([0xA4, 0x02, 0x80, 0xAA], node_list.to_vec())); // 0x50005
+    objects.push(([0xA4, 0x05, 0x80, 0xA2], route_cache_exists.to_vec())); // 0x5000B
+    objects.push(([0x2A, 0x80, 0x80, 0xAA], controller_info)); // 0x50100
+    objects.push(([0xAA, 0x80, 0x80, 0xA2], switch_info)); // 0x50101
+    objects.push(([0x91, 0x00, 0x8A, 0xA2], vec![0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00])); // 0x51401
+
+    let mut offset = 0x3000 + 20;
+
+    for (header, data) in objects {
+        image[offset..offset + 4].copy_from_slice(&header);
+        image[offset + 4..offset + 4 + data.len()].copy_from_slice(&data);
+        offset += 4 + (data.len() + 3) / 4 * 4;
+    }
+
+    image
+}
+
+mod checksum {
+    use zwave::nvm::checksum;
+
+    #[test]
+    fn it_computes_crc_16_ccitt() {
+        assert_eq!(0x29B1, checksum(b"123456789"));
+    }
+
+    #[test]
+    fn it_starts_with_all_bits_set() {
+        assert_eq!(0xFFFF, checksum(&[]));
+    }
+
+    #[test]
+    fn it_tells_images_apart() {
+        assert!(checksum(&[0x00, 0x01]) != checksum(&[0x01, 0x00]));
+    }
+}
+
+mod layout {
+    use zwave::nvm::{self, Layout};
+
+    use super::{network, SIZE_500, SIZE_700, SIZE_800};
+
+    #[test]
+    fn it_detects_500_series_images() {
+        let image = nvm::write(&network(), Layout::Series500, SIZE_500).unwrap();
+        assert_eq!(Layout::Series500, Layout::detect(&image));
+    }
+
+    #[test]
+    fn it_detects_700_series_images() {
+        let image = nvm::write(&network(), Layout::Series700, SIZE_700).unwrap();
+        assert_eq!(Layout::Series700, Layout::detect(&image));
+    }
+
+    #[test]
+    fn it_detects_800_series_images() {
+        let image = nvm::write(&network(), Layout::Series800, SIZE_800).unwrap();
+        assert_eq!(Layout::Series800, Layout::detect(&image));
+    }
+}
+
+mod read {
+    use zwave::core::{ErrorKind, NodeId};
+    use zwave::nvm::{self, Layout};
+
+    use super::{network, fixture_network, fixture_500, fixture_700, SIZE_500, SIZE_700};
+
+    #[test]
+    fn it_reads_500_series_fixture() {
+        assert_eq!(fixture_network(), nvm::read(&fixture_500(0x54)).unwrap());
+    }
+
+    #[test]
+    fn it_reads_700_series_fixture() {
+        assert_eq!(fixture_network(), nvm::read(&fixture_700(Some([0x00, 0x00, 0x07, 0x00]))).unwrap());
+    }
+
+    #[test]
+    fn it_reads_700_series_fixture_of_later_firmware() {
+        assert_eq!(fixture_network(), nvm::read(&fixture_700(Some([0x05, 0x12, 0x07, 0x00]))).unwrap());
+    }
+
+    #[test]
+    fn it_rejects_500_series_images_without_valid_configuration() {
+        let error = nvm::read(&fixture_500(0x00)).unwrap_err();
+        assert_eq!(ErrorKind::Corrupt, error.kind());
+    }
+
+    #[test]
+    fn it_rejects_700_series_images_without_protocol_version() {
+        let error = nvm::read(&fixture_700(None)).unwrap_err();
+        assert_eq!(ErrorKind::Corrupt, error.kind());
+    }
+
+    #[test]
+    fn it_rejects_700_series_images_in_later_file_formats() {
+        let error = nvm::read(&fixture_700(Some([0x00, 0x10, 0x07, 0x01]))).unwrap_err();
+        assert_eq!(ErrorKind::Unsupported, error.kind());
+    }
+
+    #[test]
+    fn it_reads_what_it_wrote_in_500_series_layout() {
+        let image = nvm::write(&network(), Layout::Series500, SIZE_500).unwrap();
+        assert_eq!(network(), nvm::read(&image).unwrap());
+    }
+
+    #[test]
+    fn it_reads_what_it_wrote_in_700_series_layout() {
+        let image = nvm::write(&network(), Layout::Series700, SIZE_700).unwrap();
+        assert_eq!(network(), nvm::read(&image).unwrap());
+    }
+
+    #[test]
+    fn it_derives_basic_class_from_capabilities() {
+        let image = nvm::write(&network(), Layout::Series500, SIZE_500).unwrap();
+        let network = nvm::read(&image).unwrap();
+
+        let basic_classes = network.nodes().iter().map(|node| node.protocol_info().basic_class()).collect::<Vec<_>>();
+        assert_eq!(vec![0x02, 0x04, 0x04, 0x03], basic_classes);
+    }
+
+    #[test]
+    fn it_keeps_routes() {
+        let image = nvm::write(&network(), Layout::Series700, SIZE_700).unwrap();
+        let network = nvm::read(&image).unwrap();
+        let route = network.nodes()[2].route().unwrap();
+
+        assert_eq!(&[NodeId(2)], route.repeaters());
+        assert_eq!(0x02, route.conf());
+    }
+
+    #[test]
+    fn it_rejects_blank_500_series_images() {
+        let error = nvm::read(&vec![0xFF; SIZE_500]).unwrap_err();
+        assert_eq!(ErrorKind::Corrupt, error.kind());
+    }
+
+    #[test]
+    fn it_rejects_truncated_500_series_images() {
+        let error = nvm::read(&vec![0x00; 64]).unwrap_err();
+        assert_eq!(ErrorKind::Corrupt, error.kind());
+    }
+
+    #[test]
+    fn it_rejects_700_series_images_without_network() {
+        let mut image = nvm::write(&network(), Layout::Series700, SIZE_700).unwrap();
+
+        for byte in &mut image[0x3000..] {
+            *byte = 0xFF;
+        }
+
+        let error = nvm::read(&image).unwrap_err();
+        assert_eq!(ErrorKind::Corrupt, error.kind());
+    }
+
+    #[test]
+    fn it_rejects_damaged_object_headers() {
+        let mut image = nvm::write(&network(), Layout::Series700, SIZE_700).unwrap();
+
+        // Clears a bit in the header of the first object of the protocol area.
+        image[0x3000 + 20] &= 0xFE;
+
+        let error = nvm::read(&image).unwrap_err();
+        assert_eq!(ErrorKind::Corrupt, error.kind());
+    }
+}
+
+mod write {
+    use zwave::core::{NodeId, HomeId, ErrorKind};
+    use zwave::nvm::{self, Layout, NetworkData, NodeData, Route};
+    use zwave::protocol::message::NodeProtocolInfo;
+
+    use super::{network, SIZE_500, SIZE_700};
+
+    #[test]
+    fn it_sizes_image_as_requested() {
+        assert_eq!(SIZE_500, nvm::write(&network(), Layout::Series500, SIZE_500).unwrap().len());
+        assert_eq!(SIZE_700, nvm::write(&network(), Layout::Series700, SIZE_700).unwrap().len());
+    }
+
+    #[test]
+    fn it_rejects_images_too_small_for_500_series_layout() {
+        let error = nvm::write(&network(), Layout::Series500, 0x800).unwrap_err();
+        assert_eq!(ErrorKind::Unsupported, error.kind());
+    }
+
+    #[test]
+    fn it_rejects_images_too_small_for_700_series_layout() {
+        let error = nvm::write(&network(), Layout::Series700, 0x3000).unwrap_err();
+        assert_eq!(ErrorKind::Unsupported, error.kind());
+    }
+
+    #[test]
+    fn it_rejects_partial_pages() {
+        let error = nvm::write(&network(), Layout::Series700, SIZE_700 - 1).unwrap_err();
+        assert_eq!(ErrorKind::Unsupported, error.kind());
+    }
+
+    #[test]
+    fn it_rejects_node_ids_outside_classic_range() {
+        let info = NodeProtocolInfo::new(0x53, 0x1C, 0x01, 0x04, 0x21, 0x01);
+        let networks = vec![
+            NetworkData::new(HomeId(0xC0FFEE42), NodeId(1), None, vec![NodeData::new(NodeId(5), info.clone(), vec![NodeId(0)], None)]),
+            NetworkData::new(HomeId(0xC0FFEE42), NodeId(1), None, vec![NodeData::new(NodeId(5), info.clone(), vec![NodeId(0x0100)], None)]),
+            NetworkData::new(HomeId(0xC0FFEE42), NodeId(1), None, vec![NodeData::new(NodeId(5), info.clone(), vec![], Some(Route::new(vec![NodeId(233)], 0x02)))]),
+            NetworkData::new(HomeId(0xC0FFEE42), NodeId(1), Some(NodeId(0x0100)), vec![]),
+            NetworkData::new(HomeId(0xC0FFEE42), NodeId(0), None, vec![]),
+        ];
+
+        for network in networks {
+            assert_eq!(ErrorKind::Unsupported, nvm::write(&network, Layout::Series500, SIZE_500).unwrap_err().kind());
+            assert_eq!(ErrorKind::Unsupported, nvm::write(&network, Layout::Series700, SIZE_700).unwrap_err().kind());
+        }
+    }
+}
+
+mod convert {
+    use zwave::nvm::{self, Layout};
+
+    use super::{network, SIZE_500, SIZE_700, SIZE_800};
+
+    #[test]
+    fn it_converts_500_series_images_to_700_series() {
+        let image = nvm::write(&network(), Layout::Series500, SIZE_500).unwrap();
+        let converted = nvm::convert(&image, Layout::Series700, SIZE_700).unwrap();
+
+        assert_eq!(Layout::Series700, Layout::detect(&converted));
+        assert_eq!(network(), nvm::read(&converted).unwrap());
+    }
+
+    #[test]
+    fn it_converts_700_series_images_to_500_series() {
+        let image = nvm::write(&network(), Layout::Series700, SIZE_700).unwrap();
+        let converted = nvm::convert(&image, Layout::Series500, SIZE_500).unwrap();
+
+        assert_eq!(nvm::write(&network(), Layout::Series500, SIZE_500).unwrap(), converted);
+    }
+
+    #[test]
+    fn it_converts_500_series_images_to_800_series() {
+        let image = nvm::write(&network(), Layout::Series500, SIZE_500).unwrap();
+        let converted = nvm::convert(&image, Layout::Series800, SIZE_800).unwrap();
+
+        assert_eq!(network(), nvm::read(&converted).unwrap());
+    }
+}
@@ -232,7 +232,7 @@ mod send_data {
 
             #[test]
             fn it_serializes_destination() {
-                assert_eq!(DEFAULT.node_id.value(), serialized(DEFAULT)[4]);
+                assert_eq!(DEFAULT.node_id.value(), serialized(DEFAULT)[4] as u16);
 
                 assert_eq!(0x00, serialized(TestParameters { node_id: NodeId(0),   .. DEFAULT })[4]);
                 assert_eq!(0x2A, serialized(TestParameters { node_id: NodeId(42),  .. DEFAULT })[4]);
@@ -418,7 +418,7 @@ mod send_data {
 
             #[test]
             fn it_serializes_destination() {
-                assert_eq!(DEFAULT.node_id.value(), serialized(DEFAULT)[4]);
+                assert_eq!(DEFAULT.node_id.value(), serialized(DEFAULT)[4] as u16);
 
                 assert_eq!(0x00, serialized(TestParameters { node_id: NodeId(0),   .. DEFAULT })[4]);
                 assert_eq!(0x2A, serialized(TestParameters { node_id: NodeId(42),  .. DEFAULT })[4]);
@@ -535,4 +535,1399 @@ mod send_data {
             }
         }
     }
+
+    mod long_node_ids {
+        use zwave::core::{NodeId, ErrorKind};
+        use zwave::protocol::bits::NodeIdType;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SendData;
+        use zwave::protocol::command::basic::SetValue;
+
+        #[test]
+        fn it_serializes_two_byte_destination() {
+            let mut serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.set_node_id_type(NodeIdType::Long);
+            serializer.serialize(&SendData::new(NodeId(0x0105), SetValue::new(0), 0x11), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x0B, 0x00, 0x13, 0x01, 0x05, 0x03, 0x20, 0x01, 0x00, 0x05, 0x11, 0xD5], buffer);
+        }
+
+        #[test]
+        fn it_rejects_long_range_destination_with_one_byte_node_ids() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            let result = serializer.serialize(&SendData::new(NodeId(0x0105), SetValue::new(0), 0x11), &mut buffer);
+
+            assert!(result.is_err());
+            assert_eq!(ErrorKind::Unsupported, result.err().unwrap().kind());
+        }
+    }
+}
+
+mod send_data_multi {
+    mod serialize {
+        use zwave::core::{NodeId, ErrorKind};
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SendDataMulti;
+        use zwave::protocol::command::basic::SetValue;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&SendDataMulti::new(vec![NodeId(2), NodeId(3), NodeId(4)], SetValue::new(0), 0x11), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x0D, 0x00, 0x14, 0x03, 0x02, 0x03, 0x04, 0x03, 0x20, 0x01, 0x00, 0x05, 0x11, 0xD6], buffer);
+        }
+
+        #[test]
+        fn it_returns_unsupported_error_for_too_many_destinations() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+            let destinations = (1..234).map(|node_id| NodeId(node_id)).collect();
+
+            let result = serializer.serialize(&SendDataMulti::new(destinations, SetValue::new(0), 0x11), &mut buffer);
+
+            assert_eq!(ErrorKind::Unsupported, result.unwrap_err().kind());
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::{NodeId, ErrorKind};
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SendDataMulti;
+        use zwave::protocol::command::basic::SetValue;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x0D, 0x00, 0x14, 0x03, 0x02, 0x03, 0x04, 0x03, 0x20, 0x01, 0x00, 0x05, 0x11, 0xD6];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap().downcast::<SendDataMulti>().unwrap();
+
+            assert_eq!(&[NodeId(2), NodeId(3), NodeId(4)], request.destinations());
+            assert_eq!(0, request.command().downcast_ref::<SetValue>().unwrap().value());
+            assert_eq!(0x05, request.packet_options());
+            assert_eq!(0x11, request.callback_id());
+        }
+
+        #[test]
+        fn it_returns_protocol_error_for_truncated_node_list() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x06, 0x00, 0x14, 0x03, 0x02, 0x03, 0xEF];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+
+            assert_eq!(ErrorKind::Protocol, serializer.deserialize(&mut reader).unwrap_err().kind());
+        }
+    }
+
+    mod long_node_ids {
+        use std::io::Cursor;
+
+        use zwave::core::{NodeId, ErrorKind};
+        use zwave::protocol::bits::NodeIdType;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SendDataMulti;
+        use zwave::protocol::command::basic::SetValue;
+        use zwave::protocol::serialization::Reader;
+
+        const FRAME: &'static [u8] = &[0x01, 0x0E, 0x00, 0x14, 0x02, 0x00, 0x02, 0x00, 0x03, 0x03, 0x20, 0x01, 0x00, 0x05, 0x11, 0xD0];
+
+        #[test]
+        fn it_serializes_two_byte_destinations() {
+            let mut serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.set_node_id_type(NodeIdType::Long);
+            serializer.serialize(&SendDataMulti::new(vec![NodeId(2), NodeId(3)], SetValue::new(0), 0x11), &mut buffer).unwrap();
+
+            assert_eq!(FRAME, &buffer[..]);
+        }
+
+        #[test]
+        fn it_returns_unsupported_error_for_frames_too_long_for_two_byte_destinations() {
+            let mut serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+            let destinations = (1..129).map(|node_id| NodeId(node_id)).collect();
+
+            serializer.set_node_id_type(NodeIdType::Long);
+            let result = serializer.serialize(&SendDataMulti::new(destinations, SetValue::new(0), 0x11), &mut buffer);
+
+            assert_eq!(ErrorKind::Unsupported, result.unwrap_err().kind());
+        }
+
+        #[test]
+        fn it_deserializes_two_byte_destinations() {
+            let mut serializer = MessageSerializer::for_request();
+            let mut cursor = Cursor::new(FRAME);
+            let mut reader = Reader::new(&mut cursor);
+
+            serializer.set_node_id_type(NodeIdType::Long);
+            let request = serializer.deserialize(&mut reader).unwrap().downcast::<SendDataMulti>().unwrap();
+
+            assert_eq!(&[NodeId(2), NodeId(3)], request.destinations());
+            assert_eq!(0x11, request.callback_id());
+        }
+    }
+}
+
+mod send_data_abort {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SendDataAbort;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&SendDataAbort::new(), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x03, 0x00, 0x16, 0xEA], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SendDataAbort;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x03, 0x00, 0x16, 0xEA];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(request.is::<SendDataAbort>());
+        }
+    }
+}
+
+mod get_version {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::GetVersion;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&GetVersion::new(), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x03, 0x00, 0x15, 0xE9], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::GetVersion;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x03, 0x00, 0x15, 0xE9];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(request.is::<GetVersion>());
+        }
+    }
+}
+
+mod memory_get_id {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::MemoryGetId;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&MemoryGetId::new(), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x03, 0x00, 0x20, 0xDC], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::MemoryGetId;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x03, 0x00, 0x20, 0xDC];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(request.is::<MemoryGetId>());
+        }
+    }
+}
+
+mod serial_api_get_capabilities {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SerialApiGetCapabilities;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&SerialApiGetCapabilities::new(), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x03, 0x00, 0x07, 0xFB], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SerialApiGetCapabilities;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x03, 0x00, 0x07, 0xFB];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(request.is::<SerialApiGetCapabilities>());
+        }
+    }
+}
+
+mod serial_api_get_init_data {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SerialApiGetInitData;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&SerialApiGetInitData::new(), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x03, 0x00, 0x02, 0xFE], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SerialApiGetInitData;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x03, 0x00, 0x02, 0xFE];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(request.is::<SerialApiGetInitData>());
+        }
+    }
+}
+
+mod get_node_protocol_info {
+    mod serialize {
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::GetNodeProtocolInfo;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&GetNodeProtocolInfo::new(NodeId(5)), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x04, 0x00, 0x41, 0x05, 0xBF], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::GetNodeProtocolInfo;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_node_id() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x04, 0x00, 0x41, 0x05, 0xBF];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap();
+
+            assert_eq!(NodeId(5), request.downcast::<GetNodeProtocolInfo>().unwrap().node_id());
+        }
+    }
+}
+
+mod add_node_to_network {
+    mod serialize {
+        use zwave::protocol::bits::AddNodeMode;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::AddNodeToNetwork;
+
+        fn serialized(request: AddNodeToNetwork) -> Vec<u8> {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&request, &mut buffer).unwrap();
+
+            buffer
+        }
+
+        #[test]
+        fn it_serializes_frame() {
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x4A, 0xC1, 0x01, 0x70], serialized(AddNodeToNetwork::new(AddNodeMode::Any, 0x01)));
+        }
+
+        #[test]
+        fn it_serializes_options() {
+            assert_eq!(0x41, serialized(AddNodeToNetwork::with_options(AddNodeMode::Any, 0x01, false, true))[4]);
+            assert_eq!(0x83, serialized(AddNodeToNetwork::with_options(AddNodeMode::Slave, 0x01, true, false))[4]);
+            assert_eq!(0x05, serialized(AddNodeToNetwork::with_options(AddNodeMode::Stop, 0x00, false, false))[4]);
+        }
+
+        #[test]
+        fn it_serializes_smart_start() {
+            let dsk = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x05, 0xC4, 0x05, 0x06, 0x06];
+
+            assert_eq!(vec![0x01, 0x0D, 0x00, 0x4A, 0xE8, 0x01, 0xC1, 0x02, 0x03, 0x04, 0x04, 0x05, 0x06, 0x07, 0x95], serialized(AddNodeToNetwork::smart_start(&dsk, 0x01, true)));
+            assert_eq!(0xC8, serialized(AddNodeToNetwork::smart_start(&dsk, 0x01, false))[4]);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::HomeId;
+        use zwave::protocol::bits::AddNodeMode;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::AddNodeToNetwork;
+        use zwave::protocol::serialization::Reader;
+
+        fn deserialized(buffer: &[u8]) -> Box<AddNodeToNetwork> {
+            let serializer = MessageSerializer::for_request();
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap();
+
+            request.downcast::<AddNodeToNetwork>().unwrap()
+        }
+
+        #[test]
+        fn it_deserializes_mode() {
+            assert_eq!(AddNodeMode::Any, deserialized(&[0x01, 0x05, 0x00, 0x4A, 0xC1, 0x01, 0x70]).mode());
+            assert_eq!(AddNodeMode::Stop, deserialized(&[0x01, 0x05, 0x00, 0x4A, 0x05, 0x00, 0xB5]).mode());
+        }
+
+        #[test]
+        fn it_deserializes_options() {
+            let request = deserialized(&[0x01, 0x05, 0x00, 0x4A, 0xC1, 0x01, 0x70]);
+
+            assert!(request.high_power());
+            assert!(request.network_wide());
+        }
+
+        #[test]
+        fn it_deserializes_callback_id() {
+            assert_eq!(0x01, deserialized(&[0x01, 0x05, 0x00, 0x4A, 0xC1, 0x01, 0x70]).callback_id());
+        }
+
+        #[test]
+        fn it_deserializes_smart_start() {
+            let request = deserialized(&[0x01, 0x0D, 0x00, 0x4A, 0xE8, 0x01, 0xC1, 0x02, 0x03, 0x04, 0x04, 0x05, 0x06, 0x07, 0x95]);
+
+            assert_eq!(AddNodeMode::SmartStartDsk, request.mode());
+            assert!(request.long_range());
+            assert_eq!(Some(HomeId(0xC1020304)), request.nwi_home_id());
+            assert_eq!(Some(HomeId(0x04050607)), request.auth_home_id());
+        }
+    }
+}
+
+mod remove_node_from_network {
+    mod serialize {
+        use zwave::protocol::bits::RemoveNodeMode;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::RemoveNodeFromNetwork;
+
+        fn serialized(request: RemoveNodeFromNetwork) -> Vec<u8> {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&request, &mut buffer).unwrap();
+
+            buffer
+        }
+
+        #[test]
+        fn it_serializes_frame() {
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x4B, 0xC1, 0x01, 0x71], serialized(RemoveNodeFromNetwork::new(RemoveNodeMode::Any, 0x01)));
+        }
+
+        #[test]
+        fn it_serializes_options() {
+            assert_eq!(0x05, serialized(RemoveNodeFromNetwork::with_options(RemoveNodeMode::Stop, 0x00, false, false))[4]);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::bits::RemoveNodeMode;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::RemoveNodeFromNetwork;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x05, 0x00, 0x4B, 0xC1, 0x01, 0x71];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap().downcast::<RemoveNodeFromNetwork>().unwrap();
+
+            assert_eq!(RemoveNodeMode::Any, request.mode());
+            assert!(request.high_power());
+            assert!(request.network_wide());
+            assert_eq!(0x01, request.callback_id());
+        }
+    }
+}
+
+mod set_learn_mode {
+    mod serialize {
+        use zwave::protocol::bits::LearnMode;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SetLearnMode;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&SetLearnMode::new(LearnMode::NetworkWide, 0x01), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x50, 0x02, 0x01, 0xA9], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::bits::LearnMode;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SetLearnMode;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x05, 0x00, 0x50, 0x02, 0x01, 0xA9];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap().downcast::<SetLearnMode>().unwrap();
+
+            assert_eq!(LearnMode::NetworkWide, request.mode());
+            assert_eq!(0x01, request.callback_id());
+        }
+    }
+}
+
+mod set_default {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SetDefault;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&SetDefault::new(0x01), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x04, 0x00, 0x42, 0x01, 0xB8], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SetDefault;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_callback_id() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x04, 0x00, 0x42, 0x01, 0xB8];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap();
+
+            assert_eq!(0x01, request.downcast::<SetDefault>().unwrap().callback_id());
+        }
+    }
+}
+
+mod soft_reset {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SoftReset;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&SoftReset::new(), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x03, 0x00, 0x08, 0xF4], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SoftReset;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x03, 0x00, 0x08, 0xF4];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(request.is::<SoftReset>());
+        }
+    }
+}
+
+mod is_failed_node {
+    mod serialize {
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::IsFailedNode;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&IsFailedNode::new(NodeId(5)), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x04, 0x00, 0x62, 0x05, 0x9C], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::IsFailedNode;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_node_id() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x04, 0x00, 0x62, 0x05, 0x9C];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap();
+
+            assert_eq!(NodeId(5), request.downcast::<IsFailedNode>().unwrap().node_id());
+        }
+    }
+}
+
+mod remove_failed_node {
+    mod serialize {
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::RemoveFailedNode;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&RemoveFailedNode::new(NodeId(5), 0x01), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x61, 0x05, 0x01, 0x9F], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::RemoveFailedNode;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x05, 0x00, 0x61, 0x05, 0x01, 0x9F];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap().downcast::<RemoveFailedNode>().unwrap();
+
+            assert_eq!(NodeId(5), request.node_id());
+            assert_eq!(0x01, request.callback_id());
+        }
+    }
+}
+
+mod replace_failed_node {
+    mod serialize {
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::ReplaceFailedNode;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&ReplaceFailedNode::new(NodeId(5), 0x01), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x63, 0x05, 0x01, 0x9D], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::ReplaceFailedNode;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x05, 0x00, 0x63, 0x05, 0x01, 0x9D];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap().downcast::<ReplaceFailedNode>().unwrap();
+
+            assert_eq!(NodeId(5), request.node_id());
+            assert_eq!(0x01, request.callback_id());
+        }
+    }
+}
+
+mod get_routing_info {
+    mod serialize {
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::GetRoutingInfo;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&GetRoutingInfo::new(NodeId(5)), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x07, 0x00, 0x80, 0x05, 0x00, 0x00, 0x00, 0x7D], buffer);
+        }
+
+        #[test]
+        fn it_serializes_options() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&GetRoutingInfo::with_options(NodeId(5), true, false), &mut buffer).unwrap();
+
+            assert_eq!(&[0x01, 0x00], &buffer[5..7]);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::GetRoutingInfo;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x07, 0x00, 0x80, 0x05, 0x00, 0x00, 0x00, 0x7D];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap().downcast::<GetRoutingInfo>().unwrap();
+
+            assert_eq!(NodeId(5), request.node_id());
+            assert!(!request.remove_bad());
+            assert!(!request.remove_non_repeaters());
+        }
+    }
+}
+
+mod request_node_neighbor_update {
+    mod serialize {
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::RequestNodeNeighborUpdate;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&RequestNodeNeighborUpdate::new(NodeId(5), 0x01), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x48, 0x05, 0x01, 0xB6], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::RequestNodeNeighborUpdate;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x05, 0x00, 0x48, 0x05, 0x01, 0xB6];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap().downcast::<RequestNodeNeighborUpdate>().unwrap();
+
+            assert_eq!(NodeId(5), request.node_id());
+            assert_eq!(0x01, request.callback_id());
+        }
+    }
+}
+
+mod assign_return_route {
+    mod serialize {
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::AssignReturnRoute;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&AssignReturnRoute::new(NodeId(5), NodeId(1), 0x02), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x06, 0x00, 0x46, 0x05, 0x01, 0x02, 0xB9], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::AssignReturnRoute;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x06, 0x00, 0x46, 0x05, 0x01, 0x02, 0xB9];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap().downcast::<AssignReturnRoute>().unwrap();
+
+            assert_eq!(NodeId(5), request.node_id());
+            assert_eq!(NodeId(1), request.destination());
+            assert_eq!(0x02, request.callback_id());
+        }
+    }
+}
+
+mod delete_return_route {
+    mod serialize {
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::DeleteReturnRoute;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&DeleteReturnRoute::new(NodeId(5), 0x02), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x47, 0x05, 0x02, 0xBA], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::DeleteReturnRoute;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x05, 0x00, 0x47, 0x05, 0x02, 0xBA];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap().downcast::<DeleteReturnRoute>().unwrap();
+
+            assert_eq!(NodeId(5), request.node_id());
+            assert_eq!(0x02, request.callback_id());
+        }
+    }
+}
+
+mod assign_suc_return_route {
+    mod serialize {
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::AssignSucReturnRoute;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&AssignSucReturnRoute::new(NodeId(5), 0x02), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x51, 0x05, 0x02, 0xAC], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::AssignSucReturnRoute;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x05, 0x00, 0x51, 0x05, 0x02, 0xAC];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap().downcast::<AssignSucReturnRoute>().unwrap();
+
+            assert_eq!(NodeId(5), request.node_id());
+            assert_eq!(0x02, request.callback_id());
+        }
+    }
+}
+
+mod delete_suc_return_route {
+    mod serialize {
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::DeleteSucReturnRoute;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&DeleteSucReturnRoute::new(NodeId(5), 0x02), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x55, 0x05, 0x02, 0xA8], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::DeleteSucReturnRoute;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x05, 0x00, 0x55, 0x05, 0x02, 0xA8];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap().downcast::<DeleteSucReturnRoute>().unwrap();
+
+            assert_eq!(NodeId(5), request.node_id());
+            assert_eq!(0x02, request.callback_id());
+        }
+    }
+}
+
+mod get_controller_capabilities {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::GetControllerCapabilities;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&GetControllerCapabilities::new(), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x03, 0x00, 0x05, 0xF9], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::GetControllerCapabilities;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x03, 0x00, 0x05, 0xF9];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(request.is::<GetControllerCapabilities>());
+        }
+    }
+}
+
+mod get_suc_node_id {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::GetSucNodeId;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&GetSucNodeId::new(), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x03, 0x00, 0x56, 0xAA], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::GetSucNodeId;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x03, 0x00, 0x56, 0xAA];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(request.is::<GetSucNodeId>());
+        }
+    }
+}
+
+mod enable_suc {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::EnableSuc;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&EnableSuc::new(true, true), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x52, 0x01, 0x01, 0xA8], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::EnableSuc;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x05, 0x00, 0x52, 0x01, 0x01, 0xA8];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap().downcast::<EnableSuc>().unwrap();
+
+            assert!(request.enable());
+            assert!(request.sis());
+        }
+    }
+}
+
+mod set_suc_node_id {
+    mod serialize {
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SetSucNodeId;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&SetSucNodeId::new(NodeId(7), true, true, 0x03), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x08, 0x00, 0x54, 0x07, 0x01, 0x00, 0x01, 0x03, 0xA7], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SetSucNodeId;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x08, 0x00, 0x54, 0x07, 0x01, 0x00, 0x01, 0x03, 0xA7];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap().downcast::<SetSucNodeId>().unwrap();
+
+            assert_eq!(NodeId(7), request.node_id());
+            assert!(request.enable());
+            assert!(request.sis());
+            assert_eq!(0x03, request.callback_id());
+        }
+    }
+}
+
+mod get_nvm_id {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::GetNvmId;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&GetNvmId::new(), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x03, 0x00, 0x29, 0xD5], buffer);
+        }
+    }
+}
+
+mod ext_nvm_read_long_buffer {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::ExtNvmReadLongBuffer;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&ExtNvmReadLongBuffer::new(0x000100, 0x0040), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x08, 0x00, 0x2A, 0x00, 0x01, 0x00, 0x00, 0x40, 0x9C], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::ExtNvmReadLongBuffer;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x08, 0x00, 0x2A, 0x00, 0x01, 0x00, 0x00, 0x40, 0x9C];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap().downcast::<ExtNvmReadLongBuffer>().unwrap();
+
+            assert_eq!(0x000100, request.offset());
+            assert_eq!(0x0040, request.length());
+        }
+    }
+}
+
+mod ext_nvm_write_long_buffer {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::ExtNvmWriteLongBuffer;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&ExtNvmWriteLongBuffer::new(0x000040, vec![0xAA, 0xBB]), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x0A, 0x00, 0x2B, 0x00, 0x00, 0x40, 0x00, 0x02, 0xAA, 0xBB, 0x8D], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::ExtNvmWriteLongBuffer;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let buffer = &[0x01, 0x0A, 0x00, 0x2B, 0x00, 0x00, 0x40, 0x00, 0x02, 0xAA, 0xBB, 0x8D];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap().downcast::<ExtNvmWriteLongBuffer>().unwrap();
+
+            assert_eq!(0x000040, request.offset());
+            assert_eq!(&[0xAA, 0xBB], request.data());
+        }
+    }
+}
+
+mod nvm_backup_restore {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::NvmBackupRestore;
+
+        fn serialized(request: NvmBackupRestore) -> Vec<u8> {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&request, &mut buffer).unwrap();
+
+            buffer
+        }
+
+        #[test]
+        fn it_serializes_open() {
+            assert_eq!(vec![0x01, 0x04, 0x00, 0x2E, 0x00, 0xD5], serialized(NvmBackupRestore::open()));
+        }
+
+        #[test]
+        fn it_serializes_read() {
+            assert_eq!(vec![0x01, 0x07, 0x00, 0x2E, 0x01, 0x40, 0x00, 0x80, 0x17], serialized(NvmBackupRestore::read(0x0080, 0x40)));
+        }
+
+        #[test]
+        fn it_serializes_write() {
+            assert_eq!(vec![0x01, 0x09, 0x00, 0x2E, 0x02, 0x02, 0x00, 0x40, 0xAA, 0xBB, 0x89], serialized(NvmBackupRestore::write(0x0040, vec![0xAA, 0xBB])));
+        }
+
+        #[test]
+        fn it_serializes_close() {
+            assert_eq!(vec![0x01, 0x04, 0x00, 0x2E, 0x03, 0xD6], serialized(NvmBackupRestore::close()));
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::bits::NvmOperation;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::NvmBackupRestore;
+        use zwave::protocol::serialization::Reader;
+
+        fn deserialized(buffer: &[u8]) -> Box<NvmBackupRestore> {
+            let serializer = MessageSerializer::for_request();
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+
+            serializer.deserialize(&mut reader).unwrap().downcast::<NvmBackupRestore>().unwrap()
+        }
+
+        #[test]
+        fn it_deserializes_open() {
+            assert_eq!(NvmOperation::Open, deserialized(&[0x01, 0x04, 0x00, 0x2E, 0x00, 0xD5]).operation());
+        }
+
+        #[test]
+        fn it_deserializes_read() {
+            let request = deserialized(&[0x01, 0x07, 0x00, 0x2E, 0x01, 0x40, 0x00, 0x80, 0x17]);
+
+            assert_eq!(NvmOperation::Read, request.operation());
+            assert_eq!(0x0080, request.offset());
+            assert_eq!(0x40, request.length());
+        }
+
+        #[test]
+        fn it_deserializes_write() {
+            let request = deserialized(&[0x01, 0x09, 0x00, 0x2E, 0x02, 0x02, 0x00, 0x40, 0xAA, 0xBB, 0x89]);
+
+            assert_eq!(NvmOperation::Write, request.operation());
+            assert_eq!(0x0040, request.offset());
+            assert_eq!(&[0xAA, 0xBB], request.data());
+        }
+    }
+}
+
+mod serial_api_setup {
+    mod serialize {
+        use zwave::protocol::bits::{RfRegion, NodeIdType};
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::{SerialApiSetup, TxPower};
+
+        fn serialized(request: SerialApiSetup) -> Vec<u8> {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&request, &mut buffer).unwrap();
+
+            buffer
+        }
+
+        #[test]
+        fn it_serializes_get_rf_region() {
+            assert_eq!(vec![0x01, 0x04, 0x00, 0x0B, 0x20, 0xD0], serialized(SerialApiSetup::GetRfRegion));
+        }
+
+        #[test]
+        fn it_serializes_set_rf_region() {
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x0B, 0x40, 0x01, 0xB0], serialized(SerialApiSetup::SetRfRegion(RfRegion::Usa)));
+        }
+
+        #[test]
+        fn it_serializes_set_tx_power() {
+            assert_eq!(vec![0x01, 0x06, 0x00, 0x0B, 0x04, 0x0A, 0xF6, 0x0A], serialized(SerialApiSetup::SetTxPower(TxPower::new(10, -10))));
+        }
+
+        #[test]
+        fn it_serializes_set_long_range_max_tx_power() {
+            assert_eq!(vec![0x01, 0x06, 0x00, 0x0B, 0x03, 0x00, 0xC8, 0x39], serialized(SerialApiSetup::SetLongRangeMaxTxPower(200)));
+        }
+
+        #[test]
+        fn it_serializes_set_node_id_type() {
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x0B, 0x80, 0x02, 0x73], serialized(SerialApiSetup::SetNodeIdType(NodeIdType::Long)));
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::bits::RfRegion;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::{SerialApiSetup, TxPower};
+        use zwave::protocol::serialization::Reader;
+
+        fn deserialized(buffer: &[u8]) -> Box<SerialApiSetup> {
+            let serializer = MessageSerializer::for_request();
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+
+            serializer.deserialize(&mut reader).unwrap().downcast::<SerialApiSetup>().unwrap()
+        }
+
+        #[test]
+        fn it_deserializes_get_tx_power() {
+            assert_eq!(SerialApiSetup::GetTxPower, *deserialized(&[0x01, 0x04, 0x00, 0x0B, 0x08, 0xF8]));
+        }
+
+        #[test]
+        fn it_deserializes_set_rf_region() {
+            assert_eq!(SerialApiSetup::SetRfRegion(RfRegion::Usa), *deserialized(&[0x01, 0x05, 0x00, 0x0B, 0x40, 0x01, 0xB0]));
+        }
+
+        #[test]
+        fn it_deserializes_set_tx_power() {
+            assert_eq!(SerialApiSetup::SetTxPower(TxPower::new(10, -10)), *deserialized(&[0x01, 0x06, 0x00, 0x0B, 0x04, 0x0A, 0xF6, 0x0A]));
+        }
+    }
+}
+
+mod get_long_range_nodes {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::GetLongRangeNodes;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_request();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&GetLongRangeNodes::new(0), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x04, 0x00, 0xDA, 0x00, 0x21], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::GetLongRangeNodes;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_segment() {
+            let serializer = MessageSerializer::for_request();
+            let mut cursor = Cursor::new(&[0x01, 0x04, 0x00, 0xDA, 0x00, 0x21]);
+            let mut reader = Reader::new(&mut cursor);
+            let request = serializer.deserialize(&mut reader).unwrap();
+
+            assert_eq!(0, request.downcast::<GetLongRangeNodes>().unwrap().segment());
+        }
+    }
 }
@@ -403,3 +403,1987 @@ mod message_received {
         }
     }
 }
+
+mod multicast_transmitted {
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::MulticastTransmitted;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_flags() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x04, 0x01, 0x14, 0x01, 0xEF];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            assert_eq!(0x01, response.downcast::<MulticastTransmitted>().unwrap().flags());
+        }
+    }
+}
+
+mod multicast_completed {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::MulticastCompleted;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&MulticastCompleted::new(0x11, 0x00), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x14, 0x11, 0x00, 0xFF], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::bits::TransmitStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::MulticastCompleted;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_transmit_status() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x05, 0x00, 0x14, 0x11, 0x00, 0xFF];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let callback = serializer.deserialize(&mut reader).unwrap().downcast::<MulticastCompleted>().unwrap();
+
+            assert_eq!(0x11, callback.callback_id());
+            assert_eq!(Some(TransmitStatus::Ok), callback.transmit_status());
+        }
+    }
+}
+
+mod application_command_handler {
+    mod serialize {
+        use zwave::core::NodeId;
+
+        struct TestParameters {
+            status: u8,
+            source: NodeId,
+            value: u8,
+        }
+
+        const DEFAULT: TestParameters = TestParameters {
+            status: 0x00,
+            source: NodeId(2),
+            value: 42,
+        };
+
+        fn serialized(parameters: TestParameters) -> Vec<u8> {
+            use zwave::protocol::message::MessageSerializer;
+            use zwave::protocol::message::ApplicationCommandHandler;
+            use zwave::protocol::command::basic::Report;
+
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            let message = ApplicationCommandHandler::new(parameters.status, parameters.source, Report::new(parameters.value));
+
+            serializer.serialize(&message, &mut buffer).unwrap();
+
+            buffer
+        }
+
+        #[test]
+        fn it_serializes_header() {
+            assert_eq!(&[0x01, 0x09, 0x00, 0x04], &serialized(DEFAULT)[0..4]);
+        }
+
+        #[test]
+        fn it_serializes_status() {
+            assert_eq!(0x00, serialized(DEFAULT)[4]);
+            assert_eq!(0x01, serialized(TestParameters { status: 0x01, .. DEFAULT })[4]);
+        }
+
+        #[test]
+        fn it_serializes_source() {
+            assert_eq!(0x02, serialized(DEFAULT)[5]);
+            assert_eq!(0x07, serialized(TestParameters { source: NodeId(7), .. DEFAULT })[5]);
+        }
+
+        #[test]
+        fn it_serializes_command() {
+            assert_eq!(&[0x03, 0x20, 0x03, 0x2A], &serialized(DEFAULT)[6..10]);
+        }
+
+        #[test]
+        fn it_serializes_checksum() {
+            assert_eq!(0xFA, serialized(DEFAULT)[10]);
+            assert_eq!(0x2B, serialized(TestParameters { status: 0x01, source: NodeId(7), value: 0xFF })[10]);
+        }
+
+        #[test]
+        fn it_serializes_correct_length() {
+            assert_eq!(11, serialized(DEFAULT).len());
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::{NodeId, ErrorKind};
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::ApplicationCommandHandler;
+        use zwave::protocol::command::basic::Report;
+        use zwave::protocol::serialization::Reader;
+
+        fn deserialized(buffer: &[u8]) -> Box<ApplicationCommandHandler> {
+            let serializer = MessageSerializer::for_response();
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            response.downcast::<ApplicationCommandHandler>().unwrap()
+        }
+
+        #[test]
+        fn it_deserializes_status() {
+            assert_eq!(0x00, deserialized(&[0x01, 0x09, 0x00, 0x04, 0x00, 0x02, 0x03, 0x20, 0x03, 0x2A, 0xFA]).status());
+            assert_eq!(0x01, deserialized(&[0x01, 0x09, 0x00, 0x04, 0x01, 0x07, 0x03, 0x20, 0x03, 0xFF, 0x2B]).status());
+        }
+
+        #[test]
+        fn it_deserializes_source() {
+            assert_eq!(NodeId(2), deserialized(&[0x01, 0x09, 0x00, 0x04, 0x00, 0x02, 0x03, 0x20, 0x03, 0x2A, 0xFA]).source());
+            assert_eq!(NodeId(7), deserialized(&[0x01, 0x09, 0x00, 0x04, 0x01, 0x07, 0x03, 0x20, 0x03, 0xFF, 0x2B]).source());
+        }
+
+        #[test]
+        fn it_deserializes_command() {
+            let message = deserialized(&[0x01, 0x09, 0x00, 0x04, 0x00, 0x02, 0x03, 0x20, 0x03, 0x2A, 0xFA]);
+
+            assert!(message.command().is::<Report>());
+            assert_eq!(42, message.command().downcast_ref::<Report>().unwrap().value());
+        }
+
+        #[test]
+        fn it_rejects_unknown_commands() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x09, 0x00, 0x04, 0x00, 0x02, 0x03, 0x99, 0x03, 0x2A, 0x43];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let result = serializer.deserialize(&mut reader);
+
+            assert!(result.is_err());
+            assert_eq!(ErrorKind::Protocol, result.err().unwrap().kind());
+        }
+
+        #[test]
+        fn it_handles_short_packets() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x09, 0x00, 0x04, 0x00, 0x02, 0x03, 0x20, 0x03, 0x2A];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let result = serializer.deserialize(&mut reader);
+
+            assert!(result.is_err());
+            assert_eq!(ErrorKind::ShortRead, result.err().unwrap().kind());
+        }
+    }
+}
+
+mod application_update {
+    fn node_info() -> ::zwave::protocol::message::NodeInfo {
+        ::zwave::protocol::message::NodeInfo::new(0x04, 0x10, 0x01, vec![0x25, 0x27], vec![0x20])
+    }
+
+    mod serialize {
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::{ApplicationUpdate, NodeInfo};
+
+        fn serialized(update: ApplicationUpdate) -> Vec<u8> {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&update, &mut buffer).unwrap();
+
+            buffer
+        }
+
+        #[test]
+        fn it_serializes_node_info_received() {
+            assert_eq!(vec![0x01, 0x0D, 0x00, 0x49, 0x84, 0x05, 0x07, 0x04, 0x10, 0x01, 0x25, 0x27, 0xEF, 0x20, 0xE5],
+                       serialized(ApplicationUpdate::NodeInfoReceived(NodeId(5), super::node_info())));
+        }
+
+        #[test]
+        fn it_omits_mark_without_controlled_command_classes() {
+            let node_info = NodeInfo::new(0x04, 0x10, 0x01, vec![0x25, 0x27], vec![]);
+
+            assert_eq!(vec![0x01, 0x0B, 0x00, 0x49, 0x84, 0x05, 0x05, 0x04, 0x10, 0x01, 0x25, 0x27, 0x2E],
+                       serialized(ApplicationUpdate::NodeInfoReceived(NodeId(5), node_info)));
+        }
+
+        #[test]
+        fn it_serializes_node_info_request_failed() {
+            assert_eq!(vec![0x01, 0x06, 0x00, 0x49, 0x81, 0x00, 0x00, 0x31], serialized(ApplicationUpdate::NodeInfoRequestFailed));
+        }
+
+        #[test]
+        fn it_serializes_new_id_assigned() {
+            assert_eq!(vec![0x01, 0x06, 0x00, 0x49, 0x40, 0x07, 0x00, 0xF7], serialized(ApplicationUpdate::NewIdAssigned(NodeId(7))));
+        }
+
+        #[test]
+        fn it_serializes_delete_done() {
+            assert_eq!(vec![0x01, 0x06, 0x00, 0x49, 0x20, 0x07, 0x00, 0x97], serialized(ApplicationUpdate::DeleteDone(NodeId(7))));
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::{NodeId, ErrorKind};
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::ApplicationUpdate;
+        use zwave::protocol::serialization::Reader;
+
+        fn deserialized(buffer: &[u8]) -> ApplicationUpdate {
+            let serializer = MessageSerializer::for_response();
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            *response.downcast::<ApplicationUpdate>().unwrap()
+        }
+
+        #[test]
+        fn it_deserializes_node_info_received() {
+            assert_eq!(ApplicationUpdate::NodeInfoReceived(NodeId(5), super::node_info()),
+                       deserialized(&[0x01, 0x0D, 0x00, 0x49, 0x84, 0x05, 0x07, 0x04, 0x10, 0x01, 0x25, 0x27, 0xEF, 0x20, 0xE5]));
+        }
+
+        #[test]
+        fn it_deserializes_node_classes() {
+            match deserialized(&[0x01, 0x0B, 0x00, 0x49, 0x84, 0x05, 0x05, 0x04, 0x10, 0x01, 0x25, 0x27, 0x2E]) {
+                ApplicationUpdate::NodeInfoReceived(_, node_info) => {
+                    assert_eq!(0x04, node_info.basic_class());
+                    assert_eq!(0x10, node_info.generic_class());
+                    assert_eq!(0x01, node_info.specific_class());
+                    assert_eq!(&[0x25, 0x27], node_info.command_classes());
+                    assert!(node_info.controlled_command_classes().is_empty());
+                },
+                update => panic!("unexpected update: {:?}", update),
+            }
+        }
+
+        #[test]
+        fn it_deserializes_node_info_request_done() {
+            assert_eq!(ApplicationUpdate::NodeInfoRequestDone(NodeId(5)), deserialized(&[0x01, 0x06, 0x00, 0x49, 0x82, 0x05, 0x00, 0x37]));
+        }
+
+        #[test]
+        fn it_deserializes_node_info_request_failed() {
+            assert_eq!(ApplicationUpdate::NodeInfoRequestFailed, deserialized(&[0x01, 0x06, 0x00, 0x49, 0x81, 0x00, 0x00, 0x31]));
+        }
+
+        #[test]
+        fn it_deserializes_new_id_assigned() {
+            assert_eq!(ApplicationUpdate::NewIdAssigned(NodeId(7)), deserialized(&[0x01, 0x06, 0x00, 0x49, 0x40, 0x07, 0x00, 0xF7]));
+        }
+
+        #[test]
+        fn it_deserializes_delete_done() {
+            assert_eq!(ApplicationUpdate::DeleteDone(NodeId(7)), deserialized(&[0x01, 0x06, 0x00, 0x49, 0x20, 0x07, 0x00, 0x97]));
+        }
+
+        #[test]
+        fn it_deserializes_suc_id_changed() {
+            assert_eq!(ApplicationUpdate::SucIdChanged(NodeId(1)), deserialized(&[0x01, 0x06, 0x00, 0x49, 0x10, 0x01, 0x00, 0xA1]));
+        }
+
+        #[test]
+        fn it_rejects_unknown_status() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x06, 0x00, 0x49, 0x2A, 0x01, 0x00, 0x9B];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let result = serializer.deserialize(&mut reader);
+
+            assert!(result.is_err());
+            assert_eq!(ErrorKind::Protocol, result.err().unwrap().kind());
+        }
+
+        #[test]
+        fn it_handles_short_packets() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x0D, 0x00, 0x49, 0x84, 0x05, 0x07, 0x04, 0x10, 0x01, 0x25, 0x27, 0xEF, 0x20];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let result = serializer.deserialize(&mut reader);
+
+            assert!(result.is_err());
+            assert_eq!(ErrorKind::ShortRead, result.err().unwrap().kind());
+        }
+    }
+}
+
+mod version {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::Version;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(32);
+
+            serializer.serialize(&Version::new("Z-Wave 4.05", 0x01), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x10, 0x01, 0x15, 0x5A, 0x2D, 0x57, 0x61, 0x76, 0x65, 0x20, 0x34, 0x2E, 0x30, 0x35, 0x00, 0x01, 0x97], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::ErrorKind;
+        use zwave::protocol::bits::LibraryType;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::Version;
+        use zwave::protocol::serialization::Reader;
+
+        const FRAME: &'static [u8] = &[0x01, 0x10, 0x01, 0x15, 0x5A, 0x2D, 0x57, 0x61, 0x76, 0x65, 0x20, 0x34, 0x2E, 0x30, 0x35, 0x00, 0x01, 0x97];
+
+        fn deserialized(buffer: &[u8]) -> Box<Version> {
+            let serializer = MessageSerializer::for_response();
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            response.downcast::<Version>().unwrap()
+        }
+
+        #[test]
+        fn it_deserializes_library_version() {
+            assert_eq!("Z-Wave 4.05", deserialized(FRAME).library_version());
+        }
+
+        #[test]
+        fn it_deserializes_library_type() {
+            assert_eq!(Some(LibraryType::StaticController), deserialized(FRAME).library_type());
+        }
+
+        #[test]
+        fn it_handles_unterminated_version() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x06, 0x01, 0x15, 0x5A, 0x2D, 0x57, 0xCD];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let result = serializer.deserialize(&mut reader);
+
+            assert!(result.is_err());
+            assert_eq!(ErrorKind::Protocol, result.err().unwrap().kind());
+        }
+    }
+}
+
+mod memory_id {
+    mod serialize {
+        use zwave::core::{NodeId, HomeId};
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::MemoryId;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&MemoryId::new(HomeId(0xC0FFEE42), NodeId(1)), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x08, 0x01, 0x20, 0xC0, 0xFF, 0xEE, 0x42, 0x01, 0x44], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::{NodeId, HomeId, ErrorKind};
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::MemoryId;
+        use zwave::protocol::serialization::Reader;
+
+        fn deserialized(buffer: &[u8]) -> Box<MemoryId> {
+            let serializer = MessageSerializer::for_response();
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            response.downcast::<MemoryId>().unwrap()
+        }
+
+        #[test]
+        fn it_deserializes_home_id() {
+            assert_eq!(HomeId(0xC0FFEE42), deserialized(&[0x01, 0x08, 0x01, 0x20, 0xC0, 0xFF, 0xEE, 0x42, 0x01, 0x44]).home_id());
+        }
+
+        #[test]
+        fn it_deserializes_node_id() {
+            assert_eq!(NodeId(1), deserialized(&[0x01, 0x08, 0x01, 0x20, 0xC0, 0xFF, 0xEE, 0x42, 0x01, 0x44]).node_id());
+        }
+
+        #[test]
+        fn it_handles_short_packets() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x08, 0x01, 0x20, 0xC0, 0xFF, 0xEE, 0x42, 0x01];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let result = serializer.deserialize(&mut reader);
+
+            assert!(result.is_err());
+            assert_eq!(ErrorKind::ShortRead, result.err().unwrap().kind());
+        }
+    }
+}
+
+mod serial_api_capabilities {
+    const FRAME: &'static [u8] = &[
+        0x01, 0x2B, 0x01, 0x07, 0x05, 0x0A, 0x00, 0x86, 0x01, 0x01, 0x00, 0x5A,
+        0xFE, 0x80, 0x0F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x70];
+
+    fn function_mask() -> [u8; 32] {
+        let mut function_mask = [0u8; 32];
+
+        function_mask[0] = 0xFE;
+        function_mask[1] = 0x80;
+        function_mask[2] = 0x0F;
+
+        function_mask
+    }
+
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SerialApiCapabilities;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(64);
+
+            serializer.serialize(&SerialApiCapabilities::new(5, 10, 0x0086, 0x0101, 0x005A, super::function_mask()), &mut buffer).unwrap();
+
+            assert_eq!(super::FRAME, &buffer[..]);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::ErrorKind;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SerialApiCapabilities;
+        use zwave::protocol::serialization::Reader;
+
+        fn deserialized(buffer: &[u8]) -> Box<SerialApiCapabilities> {
+            let serializer = MessageSerializer::for_response();
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            response.downcast::<SerialApiCapabilities>().unwrap()
+        }
+
+        #[test]
+        fn it_deserializes_application_version() {
+            assert_eq!(5, deserialized(super::FRAME).application_version());
+            assert_eq!(10, deserialized(super::FRAME).application_revision());
+        }
+
+        #[test]
+        fn it_deserializes_manufacturer_id() {
+            assert_eq!(0x0086, deserialized(super::FRAME).manufacturer_id());
+        }
+
+        #[test]
+        fn it_deserializes_product_ids() {
+            assert_eq!(0x0101, deserialized(super::FRAME).product_type());
+            assert_eq!(0x005A, deserialized(super::FRAME).product_id());
+        }
+
+        #[test]
+        fn it_deserializes_function_mask() {
+            assert_eq!(&super::function_mask(), deserialized(super::FRAME).function_mask());
+        }
+
+        #[test]
+        fn it_handles_short_packets() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &super::FRAME[..super::FRAME.len() - 1];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let result = serializer.deserialize(&mut reader);
+
+            assert!(result.is_err());
+            assert_eq!(ErrorKind::ShortRead, result.err().unwrap().kind());
+        }
+    }
+}
+
+mod resynchronization {
+    use std::io::Cursor;
+
+    use zwave::core::ErrorKind;
+    use zwave::protocol::message::MessageSerializer;
+    use zwave::protocol::message::{Ack, MessageTransmitted};
+    use zwave::protocol::serialization::Reader;
+
+    #[test]
+    fn it_discards_bytes_before_preamble() {
+        let serializer = MessageSerializer::for_response();
+        let buffer = &[0x00, 0xFF, 0x2A, 0x06];
+        let mut cursor = Cursor::new(buffer);
+        let mut reader = Reader::new(&mut cursor);
+        let response = serializer.deserialize(&mut reader).unwrap();
+
+        assert!(response.is::<Ack>());
+    }
+
+    #[test]
+    fn it_discards_bytes_before_frame() {
+        let serializer = MessageSerializer::for_response();
+        let buffer = &[0x42, 0x01, 0x04, 0x01, 0x13, 0x01, 0xE8];
+        let mut cursor = Cursor::new(buffer);
+        let mut reader = Reader::new(&mut cursor);
+        let response = serializer.deserialize(&mut reader).unwrap();
+
+        assert!(response.is::<MessageTransmitted>());
+    }
+
+    #[test]
+    fn it_handles_garbage_without_preamble() {
+        let serializer = MessageSerializer::for_response();
+        let buffer = &[0x00, 0xFF, 0x2A];
+        let mut cursor = Cursor::new(buffer);
+        let mut reader = Reader::new(&mut cursor);
+        let result = serializer.deserialize(&mut reader);
+
+        assert!(result.is_err());
+        assert_eq!(ErrorKind::ShortRead, result.err().unwrap().kind());
+    }
+
+    #[test]
+    fn it_rejects_frames_too_short_to_hold_function() {
+        let serializer = MessageSerializer::for_response();
+        let buffer = &[0x01, 0x01, 0xFE];
+        let mut cursor = Cursor::new(buffer);
+        let mut reader = Reader::new(&mut cursor);
+        let result = serializer.deserialize(&mut reader);
+
+        assert!(result.is_err());
+        assert_eq!(ErrorKind::Corrupt, result.err().unwrap().kind());
+    }
+
+    #[test]
+    fn it_reports_undecodable_payload_as_protocol_error() {
+        let serializer = MessageSerializer::for_response();
+        let buffer = &[0x01, 0x04, 0x00, 0x13, 0x11, 0xF9];
+        let mut cursor = Cursor::new(buffer);
+        let mut reader = Reader::new(&mut cursor);
+        let result = serializer.deserialize(&mut reader);
+
+        assert!(result.is_err());
+        assert_eq!(ErrorKind::Protocol, result.err().unwrap().kind());
+    }
+}
+
+mod serial_api_init_data {
+    const FRAME: &'static [u8] = &[
+        0x01, 0x25, 0x01, 0x02, 0x05, 0x08, 0x1D, 0x13, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0xDE];
+
+    mod serialize {
+        use zwave::core::{NodeId, ErrorKind};
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SerialApiInitData;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(64);
+            let node_ids = vec![NodeId(1), NodeId(2), NodeId(5), NodeId(9)];
+
+            serializer.serialize(&SerialApiInitData::new(0x05, 0x08, node_ids, 0x05, 0x00), &mut buffer).unwrap();
+
+            assert_eq!(super::FRAME, &buffer[..]);
+        }
+
+        #[test]
+        fn it_returns_unsupported_error_for_node_ids_outside_mask() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(64);
+
+            for node_id in vec![NodeId(0), NodeId(233), NodeId(0x0100)] {
+                let result = serializer.serialize(&SerialApiInitData::new(0x05, 0x08, vec![node_id], 0x05, 0x00), &mut buffer);
+
+                assert_eq!(ErrorKind::Unsupported, result.unwrap_err().kind());
+            }
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::{NodeId, ErrorKind};
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SerialApiInitData;
+        use zwave::protocol::serialization::Reader;
+
+        fn deserialized(buffer: &[u8]) -> Box<SerialApiInitData> {
+            let serializer = MessageSerializer::for_response();
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            response.downcast::<SerialApiInitData>().unwrap()
+        }
+
+        #[test]
+        fn it_deserializes_node_ids() {
+            assert_eq!(&[NodeId(1), NodeId(2), NodeId(5), NodeId(9)], deserialized(super::FRAME).node_ids());
+        }
+
+        #[test]
+        fn it_deserializes_capabilities() {
+            assert_eq!(0x08, deserialized(super::FRAME).capabilities());
+        }
+
+        #[test]
+        fn it_deserializes_chip() {
+            let init_data = deserialized(super::FRAME);
+
+            assert_eq!((0x05, 0x00), (init_data.chip_type(), init_data.chip_version()));
+        }
+
+        #[test]
+        fn it_returns_protocol_error_for_truncated_node_mask() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x08, 0x01, 0x02, 0x05, 0x08, 0x1D, 0x13, 0x01, 0xF6];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+
+            assert_eq!(ErrorKind::Protocol, serializer.deserialize(&mut reader).unwrap_err().kind());
+        }
+    }
+}
+
+mod node_protocol_info {
+    const LISTENING: &'static [u8] = &[0x01, 0x09, 0x01, 0x41, 0xD3, 0x9C, 0x01, 0x04, 0x10, 0x01, 0xED];
+    const FREQUENTLY_LISTENING: &'static [u8] = &[0x01, 0x09, 0x01, 0x41, 0x52, 0x5C, 0x00, 0x04, 0x21, 0x01, 0x9C];
+
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::NodeProtocolInfo;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&NodeProtocolInfo::new(0xD3, 0x9C, 0x01, 0x04, 0x10, 0x01), &mut buffer).unwrap();
+
+            assert_eq!(super::LISTENING, &buffer[..]);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::NodeProtocolInfo;
+        use zwave::protocol::serialization::Reader;
+
+        fn deserialized(buffer: &[u8]) -> Box<NodeProtocolInfo> {
+            let serializer = MessageSerializer::for_response();
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            response.downcast::<NodeProtocolInfo>().unwrap()
+        }
+
+        #[test]
+        fn it_deserializes_listening_flag() {
+            assert!(deserialized(super::LISTENING).is_listening());
+            assert!(!deserialized(super::FREQUENTLY_LISTENING).is_listening());
+        }
+
+        #[test]
+        fn it_deserializes_frequently_listening_flag() {
+            assert!(!deserialized(super::LISTENING).is_frequently_listening());
+            assert!(deserialized(super::FREQUENTLY_LISTENING).is_frequently_listening());
+        }
+
+        #[test]
+        fn it_deserializes_routing_flag() {
+            assert!(deserialized(super::LISTENING).is_routing());
+        }
+
+        #[test]
+        fn it_deserializes_max_baud_rate() {
+            assert_eq!(100_000, deserialized(super::LISTENING).max_baud_rate());
+            assert_eq!(40_000, deserialized(super::FREQUENTLY_LISTENING).max_baud_rate());
+        }
+
+        #[test]
+        fn it_deserializes_security_flag() {
+            assert!(!deserialized(super::LISTENING).supports_security());
+        }
+
+        #[test]
+        fn it_deserializes_device_classes() {
+            let info = deserialized(super::FREQUENTLY_LISTENING);
+
+            assert_eq!((0x04, 0x21, 0x01), (info.basic_class(), info.generic_class(), info.specific_class()));
+        }
+    }
+}
+
+mod add_node_update {
+    mod serialize {
+        use zwave::core::NodeId;
+        use zwave::protocol::bits::AddNodeStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::{AddNodeUpdate, NodeInfo};
+
+        fn serialized(update: AddNodeUpdate) -> Vec<u8> {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&update, &mut buffer).unwrap();
+
+            buffer
+        }
+
+        #[test]
+        fn it_serializes_node_info() {
+            let node_info = NodeInfo::new(0x04, 0x10, 0x01, vec![0x25, 0x27], vec![]);
+
+            assert_eq!(vec![0x01, 0x0C, 0x00, 0x4A, 0x01, 0x03, 0x05, 0x05, 0x04, 0x10, 0x01, 0x25, 0x27, 0xAC],
+                       serialized(AddNodeUpdate::new(0x01, AddNodeStatus::AddingSlave, NodeId(5), Some(node_info))));
+        }
+
+        #[test]
+        fn it_serializes_status_without_node_info() {
+            assert_eq!(vec![0x01, 0x07, 0x00, 0x4A, 0x01, 0x01, 0x00, 0x00, 0xB2],
+                       serialized(AddNodeUpdate::new(0x01, AddNodeStatus::LearnReady, NodeId(0), None)));
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::NodeId;
+        use zwave::protocol::bits::{AddNodeStatus, NodeIdType};
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::{AddNodeUpdate, NodeInfo};
+        use zwave::protocol::serialization::Reader;
+
+        const ADDING_SLAVE: &'static [u8] = &[0x01, 0x0C, 0x00, 0x4A, 0x01, 0x03, 0x05, 0x05, 0x04, 0x10, 0x01, 0x25, 0x27, 0xAC];
+
+        fn deserialized(buffer: &[u8]) -> Box<AddNodeUpdate> {
+            let serializer = MessageSerializer::for_response();
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            response.downcast::<AddNodeUpdate>().unwrap()
+        }
+
+        #[test]
+        fn it_deserializes_callback_id() {
+            assert_eq!(0x01, deserialized(ADDING_SLAVE).callback_id());
+        }
+
+        #[test]
+        fn it_deserializes_status() {
+            assert_eq!(Some(AddNodeStatus::AddingSlave), deserialized(ADDING_SLAVE).status());
+            assert_eq!(Some(AddNodeStatus::LearnReady), deserialized(&[0x01, 0x07, 0x00, 0x4A, 0x01, 0x01, 0x00, 0x00, 0xB2]).status());
+        }
+
+        #[test]
+        fn it_deserializes_node_id() {
+            assert_eq!(NodeId(5), deserialized(ADDING_SLAVE).node_id());
+        }
+
+        #[test]
+        fn it_deserializes_node_info() {
+            let node_info = NodeInfo::new(0x04, 0x10, 0x01, vec![0x25, 0x27], vec![]);
+
+            assert_eq!(Some(&node_info), deserialized(ADDING_SLAVE).node_info());
+        }
+
+        #[test]
+        fn it_deserializes_status_without_node_id() {
+            let update = deserialized(&[0x01, 0x05, 0x00, 0x4A, 0x01, 0x06, 0xB7]);
+
+            assert_eq!(Some(AddNodeStatus::Done), update.status());
+            assert_eq!(None, update.node_info());
+        }
+
+        #[test]
+        fn it_deserializes_two_byte_node_id() {
+            let mut serializer = MessageSerializer::for_response();
+            let mut cursor = Cursor::new(&[0x01, 0x0D, 0x00, 0x4A, 0x01, 0x03, 0x01, 0x05, 0x05, 0x04, 0x10, 0x01, 0x25, 0x27, 0xAC]);
+            let mut reader = Reader::new(&mut cursor);
+
+            serializer.set_node_id_type(NodeIdType::Long);
+            let update = serializer.deserialize(&mut reader).unwrap().downcast::<AddNodeUpdate>().unwrap();
+
+            assert_eq!(NodeId(0x0105), update.node_id());
+            assert_eq!(Some(&NodeInfo::new(0x04, 0x10, 0x01, vec![0x25, 0x27], vec![])), update.node_info());
+        }
+    }
+}
+
+mod remove_node_update {
+    mod serialize {
+        use zwave::core::NodeId;
+        use zwave::protocol::bits::RemoveNodeStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::RemoveNodeUpdate;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&RemoveNodeUpdate::new(0x01, RemoveNodeStatus::RemovingSlave, NodeId(5)), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x07, 0x00, 0x4B, 0x01, 0x03, 0x05, 0x00, 0xB4], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::NodeId;
+        use zwave::protocol::bits::RemoveNodeStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::RemoveNodeUpdate;
+        use zwave::protocol::serialization::Reader;
+
+        fn deserialized(buffer: &[u8]) -> Box<RemoveNodeUpdate> {
+            let serializer = MessageSerializer::for_response();
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            response.downcast::<RemoveNodeUpdate>().unwrap()
+        }
+
+        #[test]
+        fn it_deserializes_removed_node() {
+            let update = deserialized(&[0x01, 0x0C, 0x00, 0x4B, 0x01, 0x03, 0x05, 0x05, 0x04, 0x10, 0x01, 0x25, 0x27, 0xAD]);
+
+            assert_eq!(0x01, update.callback_id());
+            assert_eq!(Some(RemoveNodeStatus::RemovingSlave), update.status());
+            assert_eq!(NodeId(5), update.node_id());
+        }
+
+        #[test]
+        fn it_deserializes_status_without_node_id() {
+            assert_eq!(Some(RemoveNodeStatus::Done), deserialized(&[0x01, 0x05, 0x00, 0x4B, 0x01, 0x06, 0xB6]).status());
+        }
+    }
+}
+
+mod learn_mode_result {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::LearnModeResult;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&LearnModeResult::new(0x01), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x04, 0x01, 0x50, 0x01, 0xAB], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::LearnModeResult;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_accepted_flag() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x04, 0x01, 0x50, 0x01, 0xAB];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(response.downcast::<LearnModeResult>().unwrap().accepted());
+        }
+    }
+}
+
+mod learn_mode_update {
+    mod serialize {
+        use zwave::core::NodeId;
+        use zwave::protocol::bits::LearnModeStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::LearnModeUpdate;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&LearnModeUpdate::new(0x01, LearnModeStatus::Done, NodeId(7)), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x07, 0x00, 0x50, 0x01, 0x06, 0x07, 0x00, 0xA8], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::NodeId;
+        use zwave::protocol::bits::LearnModeStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::LearnModeUpdate;
+        use zwave::protocol::serialization::Reader;
+
+        fn deserialized(buffer: &[u8]) -> Box<LearnModeUpdate> {
+            let serializer = MessageSerializer::for_response();
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            response.downcast::<LearnModeUpdate>().unwrap()
+        }
+
+        #[test]
+        fn it_deserializes_frame() {
+            let update = deserialized(&[0x01, 0x07, 0x00, 0x50, 0x01, 0x06, 0x07, 0x00, 0xA8]);
+
+            assert_eq!(0x01, update.callback_id());
+            assert_eq!(Some(LearnModeStatus::Done), update.status());
+            assert_eq!(NodeId(7), update.node_id());
+        }
+
+        #[test]
+        fn it_deserializes_status_without_node_id() {
+            assert_eq!(Some(LearnModeStatus::Started), deserialized(&[0x01, 0x05, 0x00, 0x50, 0x01, 0x01, 0xAA]).status());
+        }
+    }
+}
+
+mod default_set {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::DefaultSet;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&DefaultSet::new(0x01), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x04, 0x00, 0x42, 0x01, 0xB8], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::DefaultSet;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_callback_id() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x04, 0x00, 0x42, 0x01, 0xB8];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            assert_eq!(0x01, response.downcast::<DefaultSet>().unwrap().callback_id());
+        }
+    }
+}
+
+mod serial_api_started {
+    const FRAME: &'static [u8] = &[0x01, 0x0C, 0x00, 0x0A, 0x07, 0x00, 0x01, 0x02, 0x07, 0x02, 0x5E, 0x86, 0x00, 0x20];
+
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SerialApiStarted;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&SerialApiStarted::new(0x07, false, 0x01, 0x02, 0x07, vec![0x5E, 0x86], false), &mut buffer).unwrap();
+
+            assert_eq!(super::FRAME, &buffer[..]);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SerialApiStarted;
+        use zwave::protocol::serialization::Reader;
+
+        fn deserialized(buffer: &[u8]) -> Box<SerialApiStarted> {
+            let serializer = MessageSerializer::for_response();
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            response.downcast::<SerialApiStarted>().unwrap()
+        }
+
+        #[test]
+        fn it_deserializes_wake_up_reason() {
+            assert_eq!(0x07, deserialized(super::FRAME).wake_up_reason());
+        }
+
+        #[test]
+        fn it_deserializes_device_classes() {
+            let started = deserialized(super::FRAME);
+
+            assert_eq!((0x02, 0x07), (started.generic_class(), started.specific_class()));
+        }
+
+        #[test]
+        fn it_deserializes_command_classes() {
+            assert_eq!(&[0x5E, 0x86], deserialized(super::FRAME).command_classes());
+        }
+
+        #[test]
+        fn it_deserializes_frame_without_supported_protocols() {
+            let started = deserialized(&[0x01, 0x09, 0x00, 0x0A, 0x00, 0x00, 0x01, 0x02, 0x07, 0x00, 0xF8]);
+
+            assert!(started.command_classes().is_empty());
+            assert!(!started.supports_long_range());
+        }
+    }
+}
+
+mod is_failed_node_result {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::IsFailedNodeResult;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&IsFailedNodeResult::new(true), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x04, 0x01, 0x62, 0x01, 0x99], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::IsFailedNodeResult;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_failed_flag() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x04, 0x01, 0x62, 0x01, 0x99];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(response.downcast::<IsFailedNodeResult>().unwrap().failed());
+        }
+    }
+}
+
+mod remove_failed_node_result {
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::RemoveFailedNodeResult;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_started_flag() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x04, 0x01, 0x61, 0x00, 0x9B];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(response.downcast::<RemoveFailedNodeResult>().unwrap().started());
+        }
+    }
+}
+
+mod remove_failed_node_update {
+    mod serialize {
+        use zwave::protocol::bits::FailedNodeStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::RemoveFailedNodeUpdate;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&RemoveFailedNodeUpdate::new(0x01, FailedNodeStatus::Removed), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x61, 0x01, 0x01, 0x9B], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::bits::FailedNodeStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::RemoveFailedNodeUpdate;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_status() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x05, 0x00, 0x61, 0x01, 0x01, 0x9B];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let update = serializer.deserialize(&mut reader).unwrap().downcast::<RemoveFailedNodeUpdate>().unwrap();
+
+            assert_eq!(0x01, update.callback_id());
+            assert_eq!(Some(FailedNodeStatus::Removed), update.status());
+        }
+    }
+}
+
+mod replace_failed_node_result {
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::ReplaceFailedNodeResult;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_refusal() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x04, 0x01, 0x63, 0x04, 0x9D];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap().downcast::<ReplaceFailedNodeResult>().unwrap();
+
+            assert!(!response.started());
+            assert_eq!(0x04, response.flags());
+        }
+    }
+}
+
+mod replace_failed_node_update {
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::bits::FailedNodeStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::ReplaceFailedNodeUpdate;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_status() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x05, 0x00, 0x63, 0x01, 0x04, 0x9C];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let update = serializer.deserialize(&mut reader).unwrap().downcast::<ReplaceFailedNodeUpdate>().unwrap();
+
+            assert_eq!(Some(FailedNodeStatus::ReplaceDone), update.status());
+        }
+    }
+}
+
+mod routing_info {
+    const FRAME: &'static [u8] = &[
+        0x01, 0x20, 0x01, 0x80, 0x41, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x1F];
+
+    mod serialize {
+        use zwave::core::{NodeId, ErrorKind};
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::RoutingInfo;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(64);
+
+            serializer.serialize(&RoutingInfo::new(vec![NodeId(1), NodeId(7)]), &mut buffer).unwrap();
+
+            assert_eq!(super::FRAME, &buffer[..]);
+        }
+
+        #[test]
+        fn it_returns_unsupported_error_for_long_range_neighbor() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(64);
+
+            let result = serializer.serialize(&RoutingInfo::new(vec![NodeId(1), NodeId(0x0100)]), &mut buffer);
+
+            assert_eq!(ErrorKind::Unsupported, result.unwrap_err().kind());
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::RoutingInfo;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_neighbors() {
+            let serializer = MessageSerializer::for_response();
+            let mut cursor = Cursor::new(super::FRAME);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            assert_eq!(&[NodeId(1), NodeId(7)], response.downcast::<RoutingInfo>().unwrap().neighbors());
+        }
+    }
+}
+
+mod neighbor_update {
+    mod serialize {
+        use zwave::protocol::bits::NeighborUpdateStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::NeighborUpdate;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&NeighborUpdate::new(0x01, NeighborUpdateStatus::Done), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x48, 0x01, 0x22, 0x91], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::bits::NeighborUpdateStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::NeighborUpdate;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_status() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x05, 0x00, 0x48, 0x01, 0x22, 0x91];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let update = serializer.deserialize(&mut reader).unwrap().downcast::<NeighborUpdate>().unwrap();
+
+            assert_eq!(0x01, update.callback_id());
+            assert_eq!(Some(NeighborUpdateStatus::Done), update.status());
+        }
+    }
+}
+
+mod assign_return_route_result {
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::AssignReturnRouteResult;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_accepted_flag() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x04, 0x01, 0x46, 0x01, 0xBD];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(response.downcast::<AssignReturnRouteResult>().unwrap().accepted());
+        }
+    }
+}
+
+mod return_route_assigned {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::ReturnRouteAssigned;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&ReturnRouteAssigned::new(0x02, 0x00), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x46, 0x02, 0x00, 0xBE], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::bits::TransmitStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::ReturnRouteAssigned;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_transmit_status() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x05, 0x00, 0x46, 0x02, 0x00, 0xBE];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let callback = serializer.deserialize(&mut reader).unwrap().downcast::<ReturnRouteAssigned>().unwrap();
+
+            assert_eq!(0x02, callback.callback_id());
+            assert_eq!(Some(TransmitStatus::Ok), callback.transmit_status());
+        }
+    }
+}
+
+mod delete_return_route_result {
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::DeleteReturnRouteResult;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_accepted_flag() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x04, 0x01, 0x47, 0x01, 0xBC];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(response.downcast::<DeleteReturnRouteResult>().unwrap().accepted());
+        }
+    }
+}
+
+mod return_route_deleted {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::ReturnRouteDeleted;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&ReturnRouteDeleted::new(0x02, 0x00), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x47, 0x02, 0x00, 0xBF], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::bits::TransmitStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::ReturnRouteDeleted;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_transmit_status() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x05, 0x00, 0x47, 0x02, 0x00, 0xBF];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let callback = serializer.deserialize(&mut reader).unwrap().downcast::<ReturnRouteDeleted>().unwrap();
+
+            assert_eq!(0x02, callback.callback_id());
+            assert_eq!(Some(TransmitStatus::Ok), callback.transmit_status());
+        }
+    }
+}
+
+mod assign_suc_return_route_result {
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::AssignSucReturnRouteResult;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_accepted_flag() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x04, 0x01, 0x51, 0x01, 0xAA];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(response.downcast::<AssignSucReturnRouteResult>().unwrap().accepted());
+        }
+    }
+}
+
+mod suc_return_route_assigned {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SucReturnRouteAssigned;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&SucReturnRouteAssigned::new(0x02, 0x00), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x51, 0x02, 0x00, 0xA9], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::bits::TransmitStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SucReturnRouteAssigned;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_transmit_status() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x05, 0x00, 0x51, 0x02, 0x00, 0xA9];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let callback = serializer.deserialize(&mut reader).unwrap().downcast::<SucReturnRouteAssigned>().unwrap();
+
+            assert_eq!(0x02, callback.callback_id());
+            assert_eq!(Some(TransmitStatus::Ok), callback.transmit_status());
+        }
+    }
+}
+
+mod delete_suc_return_route_result {
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::DeleteSucReturnRouteResult;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_accepted_flag() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x04, 0x01, 0x55, 0x01, 0xAE];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(response.downcast::<DeleteSucReturnRouteResult>().unwrap().accepted());
+        }
+    }
+}
+
+mod suc_return_route_deleted {
+    mod serialize {
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SucReturnRouteDeleted;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&SucReturnRouteDeleted::new(0x02, 0x00), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x55, 0x02, 0x00, 0xAD], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::bits::TransmitStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SucReturnRouteDeleted;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_transmit_status() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x05, 0x00, 0x55, 0x02, 0x00, 0xAD];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let callback = serializer.deserialize(&mut reader).unwrap().downcast::<SucReturnRouteDeleted>().unwrap();
+
+            assert_eq!(0x02, callback.callback_id());
+            assert_eq!(Some(TransmitStatus::Ok), callback.transmit_status());
+        }
+    }
+}
+
+mod controller_capabilities {
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::ControllerCapabilities;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_capabilities() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x04, 0x01, 0x05, 0x14, 0xEB];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap().downcast::<ControllerCapabilities>().unwrap();
+
+            assert_eq!(0x14, response.capabilities());
+            assert!(response.sis_present());
+            assert!(response.is_suc());
+            assert!(!response.is_secondary());
+            assert!(!response.is_on_other_network());
+            assert!(!response.is_real_primary());
+        }
+    }
+}
+
+mod suc_node_id {
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SucNodeId;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_node_id() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x04, 0x01, 0x56, 0x01, 0xAD];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap().downcast::<SucNodeId>().unwrap();
+
+            assert_eq!(Some(NodeId(1)), response.node_id());
+        }
+
+        #[test]
+        fn it_deserializes_missing_suc() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x04, 0x01, 0x56, 0x00, 0xAC];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap().downcast::<SucNodeId>().unwrap();
+
+            assert_eq!(None, response.node_id());
+        }
+    }
+}
+
+mod enable_suc_result {
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::EnableSucResult;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_accepted_flag() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x04, 0x01, 0x52, 0x01, 0xA9];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(response.downcast::<EnableSucResult>().unwrap().accepted());
+        }
+    }
+}
+
+mod set_suc_node_id_result {
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SetSucNodeIdResult;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_accepted_flag() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x04, 0x01, 0x54, 0x01, 0xAF];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(response.downcast::<SetSucNodeIdResult>().unwrap().accepted());
+        }
+    }
+}
+
+mod suc_node_id_set {
+    mod serialize {
+        use zwave::protocol::bits::SetSucStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SucNodeIdSet;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&SucNodeIdSet::new(0x03, SetSucStatus::Succeeded), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x05, 0x00, 0x54, 0x03, 0x05, 0xA8], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::bits::SetSucStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SucNodeIdSet;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_status() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x05, 0x00, 0x54, 0x03, 0x05, 0xA8];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let callback = serializer.deserialize(&mut reader).unwrap().downcast::<SucNodeIdSet>().unwrap();
+
+            assert_eq!(0x03, callback.callback_id());
+            assert_eq!(Some(SetSucStatus::Succeeded), callback.status());
+        }
+    }
+}
+
+mod nvm_id {
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::NvmId;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_size() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x06, 0x01, 0x29, 0xEF, 0x01, 0x0F, 0x30];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap().downcast::<NvmId>().unwrap();
+
+            assert_eq!(0xEF, response.manufacturer_id());
+            assert_eq!(Some(32 * 1024), response.size());
+        }
+
+        #[test]
+        fn it_reports_unknown_size() {
+            assert_eq!(None, NvmId::new(0xEF, 0x01, 0xFF).size());
+        }
+    }
+}
+
+mod nvm_buffer {
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::NvmBuffer;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_data() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x07, 0x01, 0x2A, 0x01, 0x02, 0x03, 0x04, 0xD7];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap().downcast::<NvmBuffer>().unwrap();
+
+            assert_eq!(&[0x01, 0x02, 0x03, 0x04], response.data());
+        }
+    }
+}
+
+mod ext_nvm_write_long_buffer_result {
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::ExtNvmWriteLongBufferResult;
+        use zwave::protocol::serialization::Reader;
+
+        #[test]
+        fn it_deserializes_result() {
+            let serializer = MessageSerializer::for_response();
+            let buffer = &[0x01, 0x04, 0x01, 0x2B, 0x01, 0xD0];
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            assert!(response.downcast::<ExtNvmWriteLongBufferResult>().unwrap().succeeded());
+        }
+    }
+}
+
+mod nvm_backup_restore_result {
+    mod serialize {
+        use zwave::protocol::bits::NvmStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::NvmBackupRestoreResult;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&NvmBackupRestoreResult::new(NvmStatus::Ok, 0x0040, vec![0xAA, 0xBB]), &mut buffer).unwrap();
+
+            assert_eq!(vec![0x01, 0x09, 0x01, 0x2E, 0x00, 0x02, 0x00, 0x40, 0xAA, 0xBB, 0x8A], buffer);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::bits::NvmStatus;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::NvmBackupRestoreResult;
+        use zwave::protocol::serialization::Reader;
+
+        fn deserialized(buffer: &[u8]) -> Box<NvmBackupRestoreResult> {
+            let serializer = MessageSerializer::for_response();
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+
+            serializer.deserialize(&mut reader).unwrap().downcast::<NvmBackupRestoreResult>().unwrap()
+        }
+
+        #[test]
+        fn it_deserializes_size() {
+            let response = deserialized(&[0x01, 0x07, 0x01, 0x2E, 0x00, 0x00, 0xC0, 0x00, 0x17]);
+
+            assert_eq!(Some(NvmStatus::Ok), response.status());
+            assert_eq!(0xC000, response.offset());
+        }
+
+        #[test]
+        fn it_deserializes_data() {
+            let response = deserialized(&[0x01, 0x09, 0x01, 0x2E, 0xFF, 0x02, 0x00, 0x40, 0xAA, 0xBB, 0x75]);
+
+            assert_eq!(Some(NvmStatus::EndOfFile), response.status());
+            assert_eq!(0x0040, response.offset());
+            assert_eq!(&[0xAA, 0xBB], response.data());
+        }
+
+        #[test]
+        fn it_deserializes_status_only() {
+            let response = deserialized(&[0x01, 0x04, 0x01, 0x2E, 0x00, 0xD4]);
+
+            assert_eq!(Some(NvmStatus::Ok), response.status());
+            assert!(response.data().is_empty());
+        }
+    }
+}
+
+mod serial_api_setup_result {
+    mod serialize {
+        use zwave::protocol::bits::{SerialApiSetupCommand, RfRegion};
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::SerialApiSetupResult;
+
+        fn serialized(response: SerialApiSetupResult) -> Vec<u8> {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&response, &mut buffer).unwrap();
+
+            buffer
+        }
+
+        #[test]
+        fn it_serializes_rf_region() {
+            assert_eq!(vec![0x01, 0x05, 0x01, 0x0B, 0x20, 0x01, 0xD1], serialized(SerialApiSetupResult::RfRegion(RfRegion::Usa)));
+        }
+
+        #[test]
+        fn it_serializes_set() {
+            assert_eq!(vec![0x01, 0x05, 0x01, 0x0B, 0x40, 0x00, 0xB0], serialized(SerialApiSetupResult::Set(SerialApiSetupCommand::SetRfRegion, false)));
+        }
+
+        #[test]
+        fn it_serializes_supported_commands() {
+            let commands = vec![SerialApiSetupCommand::GetRfRegion, SerialApiSetupCommand::SetRfRegion];
+
+            assert_eq!(vec![0x01, 0x0E, 0x01, 0x0B, 0x01, 0x60, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x9A], serialized(SerialApiSetupResult::SupportedCommands(commands)));
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::protocol::bits::{SerialApiSetupCommand, RfRegion};
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::{SerialApiSetupResult, TxPower};
+        use zwave::protocol::serialization::Reader;
+
+        fn deserialized(buffer: &[u8]) -> Box<SerialApiSetupResult> {
+            let serializer = MessageSerializer::for_response();
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+
+            serializer.deserialize(&mut reader).unwrap().downcast::<SerialApiSetupResult>().unwrap()
+        }
+
+        #[test]
+        fn it_deserializes_rf_region() {
+            assert_eq!(SerialApiSetupResult::RfRegion(RfRegion::Usa), *deserialized(&[0x01, 0x05, 0x01, 0x0B, 0x20, 0x01, 0xD1]));
+        }
+
+        #[test]
+        fn it_deserializes_unknown_rf_region() {
+            assert_eq!(SerialApiSetupResult::RfRegion(RfRegion::Unknown), *deserialized(&[0x01, 0x05, 0x01, 0x0B, 0x20, 0x42, 0x92]));
+        }
+
+        #[test]
+        fn it_deserializes_tx_power() {
+            assert_eq!(SerialApiSetupResult::TxPower(TxPower::new(10, -10)), *deserialized(&[0x01, 0x06, 0x01, 0x0B, 0x08, 0x0A, 0xF6, 0x07]));
+        }
+
+        #[test]
+        fn it_deserializes_long_range_max_tx_power() {
+            assert_eq!(SerialApiSetupResult::LongRangeMaxTxPower(200), *deserialized(&[0x01, 0x06, 0x01, 0x0B, 0x05, 0x00, 0xC8, 0x3E]));
+        }
+
+        #[test]
+        fn it_deserializes_set() {
+            assert_eq!(SerialApiSetupResult::Set(SerialApiSetupCommand::SetRfRegion, true), *deserialized(&[0x01, 0x05, 0x01, 0x0B, 0x40, 0x01, 0xB1]));
+        }
+
+        #[test]
+        fn it_deserializes_unsupported() {
+            assert_eq!(SerialApiSetupResult::Unsupported(0x20), *deserialized(&[0x01, 0x05, 0x01, 0x0B, 0x00, 0x20, 0xD0]));
+        }
+
+        #[test]
+        fn it_deserializes_legacy_supported_commands() {
+            let commands = vec![
+                SerialApiSetupCommand::GetSupportedCommands,
+                SerialApiSetupCommand::SetTxPower,
+                SerialApiSetupCommand::GetTxPower,
+                SerialApiSetupCommand::GetRfRegion,
+                SerialApiSetupCommand::SetRfRegion,
+                SerialApiSetupCommand::SetNodeIdType,
+            ];
+
+            assert_eq!(SerialApiSetupResult::SupportedCommands(commands), *deserialized(&[0x01, 0x05, 0x01, 0x0B, 0x01, 0xFD, 0x0C]));
+        }
+
+        #[test]
+        fn it_deserializes_extended_supported_commands() {
+            let response = deserialized(&[0x01, 0x16, 0x01, 0x0B, 0x01, 0xFD, 0x3A, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x24]);
+
+            match *response {
+                SerialApiSetupResult::SupportedCommands(ref commands) => {
+                    assert!(commands.contains(&SerialApiSetupCommand::SetLongRangeMaxTxPower));
+                    assert!(commands.contains(&SerialApiSetupCommand::GetLongRangeMaxTxPower));
+                    assert_eq!(8, commands.len());
+                },
+                _ => panic!("expected supported commands"),
+            }
+        }
+    }
+}
+
+mod long_range_nodes {
+    const FRAME: &'static [u8] = &[0x01, 0x08, 0x01, 0xDA, 0x01, 0x00, 0x02, 0x05, 0x80, 0xAA];
+
+    mod serialize {
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::LongRangeNodes;
+
+        #[test]
+        fn it_serializes_frame() {
+            let serializer = MessageSerializer::for_response();
+            let mut buffer = Vec::<u8>::with_capacity(16);
+
+            serializer.serialize(&LongRangeNodes::new(true, 0, vec![NodeId(256), NodeId(258), NodeId(271)]), &mut buffer).unwrap();
+
+            assert_eq!(super::FRAME, &buffer[..]);
+        }
+    }
+
+    mod deserialize {
+        use std::io::Cursor;
+
+        use zwave::core::NodeId;
+        use zwave::protocol::message::MessageSerializer;
+        use zwave::protocol::message::LongRangeNodes;
+        use zwave::protocol::serialization::Reader;
+
+        fn deserialized(buffer: &[u8]) -> Box<LongRangeNodes> {
+            let serializer = MessageSerializer::for_response();
+            let mut cursor = Cursor::new(buffer);
+            let mut reader = Reader::new(&mut cursor);
+            let response = serializer.deserialize(&mut reader).unwrap();
+
+            response.downcast::<LongRangeNodes>().unwrap()
+        }
+
+        #[test]
+        fn it_deserializes_node_ids() {
+            assert_eq!(&[NodeId(256), NodeId(258), NodeId(271)], deserialized(super::FRAME).node_ids());
+        }
+
+        #[test]
+        fn it_deserializes_node_ids_of_later_segments() {
+            let response = deserialized(&[0x01, 0x07, 0x01, 0xDA, 0x00, 0x01, 0x01, 0x01, 0x22]);
+
+            assert!(!response.more());
+            assert_eq!(1, response.segment());
+            assert_eq!(&[NodeId(1280)], response.node_ids());
+        }
+
+        #[test]
+        fn it_deserializes_more() {
+            assert!(deserialized(super::FRAME).more());
+        }
+    }
+}